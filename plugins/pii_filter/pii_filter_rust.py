@@ -92,6 +92,9 @@ class RustPIIDetector:
 
         # Convert Pydantic config to dictionary for Rust
         config_dict = config.model_dump()
+        # The plugin masks from detect() results, which needs offsets;
+        # include_detection_details only governs its context metadata
+        config_dict["include_detection_details"] = True
 
         try:
             # Create Rust detector (this calls into Rust via PyO3)
@@ -0,0 +1,9 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Exposes PyO3's `Py_3_*` / `Py_LIMITED_API` cfgs, so code paths needing
+// newer stable-ABI functions compile only where they are available.
+
+fn main() {
+    pyo3_build_config::use_pyo3_cfgs();
+}
@@ -0,0 +1,17 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Fuzz target: config parsing and pattern compilation
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plugins_rust::pii_filter::{config::PIIConfig, Detector};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(config) = serde_json::from_slice::<PIIConfig>(data) {
+        if let Ok(detector) = Detector::from_config(config) {
+            let _ = detector.detect_internal("SSN 123-45-6789, email john@example.com");
+        }
+    }
+});
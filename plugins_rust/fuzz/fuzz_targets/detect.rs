@@ -0,0 +1,21 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Fuzz target: detection and masking over arbitrary UTF-8 input
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use once_cell::sync::Lazy;
+use plugins_rust::pii_filter::{config::PIIConfig, masking::mask_pii, Detector};
+
+static CONFIG: Lazy<PIIConfig> = Lazy::new(PIIConfig::default);
+static DETECTOR: Lazy<Detector> =
+    Lazy::new(|| Detector::from_config(CONFIG.clone()).unwrap());
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let detections = DETECTOR.detect_internal(text);
+        let _ = mask_pii(text, &detections, &CONFIG);
+    }
+});
@@ -0,0 +1,52 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Fuzz target: masking with arbitrary (possibly invalid) detection spans
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plugins_rust::pii_filter::{
+    config::{MaskingStrategy, PIIConfig, PIIType},
+    detector::Detection,
+    masking::{mask_pii, validate_detections},
+};
+use std::collections::BTreeMap;
+
+const TYPES: [PIIType; 6] = [
+    PIIType::Ssn,
+    PIIType::CreditCard,
+    PIIType::Email,
+    PIIType::Phone,
+    PIIType::BankAccount,
+    PIIType::Custom,
+];
+
+const STRATEGIES: [MaskingStrategy; 5] = [
+    MaskingStrategy::Redact,
+    MaskingStrategy::Partial,
+    MaskingStrategy::Hash,
+    MaskingStrategy::Tokenize,
+    MaskingStrategy::Remove,
+];
+
+fuzz_target!(|input: (String, Vec<(usize, usize, u8, String)>)| {
+    let (text, spans) = input;
+    let config = PIIConfig::default();
+
+    let mut detections: BTreeMap<PIIType, Vec<Detection>> = BTreeMap::new();
+    for (start, end, selector, value) in spans {
+        let pii_type = TYPES[selector as usize % TYPES.len()];
+        detections.entry(pii_type).or_default().push(Detection {
+            value,
+            start,
+            end,
+            mask_strategy: STRATEGIES[selector as usize % STRATEGIES.len()],
+            country: None,
+            subtype: None,
+        });
+    }
+
+    let _ = validate_detections(&text, &detections);
+    let _ = mask_pii(&text, &detections, &config);
+});
@@ -0,0 +1,13 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Classifier Plugin - Rust Implementation
+//
+// Labels payloads ("contains_code", "contains_medical", ...) by matching them
+// against weighted regex rule groups in a single RegexSet pass, so the
+// gateway can route requests to different policies without an LLM call.
+
+pub mod plugin;
+pub mod rules;
+
+pub use plugin::Classifier;
@@ -0,0 +1,385 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// RegexSet-based payload classification
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString, PyTuple};
+use regex::{Regex, RegexSet, RegexSetBuilder};
+use std::borrow::Cow;
+
+use super::rules::{ClassifierConfig, Rule};
+use crate::pii_filter::config::MAX_DEPTH;
+use crate::pii_filter::error::{PIIError, PIIResult};
+use crate::plugin::{GatewayPlugin, Hook, HookContext};
+
+/// A label assigned to a payload
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub label: String,
+    /// Combined weight of the matched rules: `1 - Π(1 - weight)`
+    pub confidence: f64,
+    pub matched_rules: usize,
+}
+
+impl Label {
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("label", &self.label)?;
+        py_dict.set_item("confidence", self.confidence)?;
+        py_dict.set_item("matched_rules", self.matched_rules)?;
+        Ok(py_dict)
+    }
+}
+
+/// Compiled rule groups, matched in one pass per text
+pub struct RuleSet {
+    set: RegexSet,
+    rules: Vec<Rule>,
+    /// Distinct labels in first-seen order
+    labels: Vec<String>,
+    /// Index into `labels` for each rule
+    rule_labels: Vec<usize>,
+}
+
+impl RuleSet {
+    pub fn compile(rules: Vec<Rule>, case_sensitive: bool) -> PIIResult<Self> {
+        // Compile individually first so errors name the offending pattern
+        for rule in &rules {
+            Regex::new(&rule.pattern).map_err(|source| PIIError::InvalidPattern {
+                pattern: rule.pattern.clone(),
+                source,
+            })?;
+        }
+        let set = RegexSetBuilder::new(rules.iter().map(|rule| &rule.pattern))
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|source| PIIError::InvalidPattern {
+                pattern: "<classifier rules>".to_string(),
+                source,
+            })?;
+
+        let mut labels: Vec<String> = Vec::new();
+        let mut rule_labels = Vec::with_capacity(rules.len());
+        for rule in &rules {
+            let idx = match labels.iter().position(|label| *label == rule.label) {
+                Some(idx) => idx,
+                None => {
+                    labels.push(rule.label.clone());
+                    labels.len() - 1
+                }
+            };
+            rule_labels.push(idx);
+        }
+
+        Ok(Self {
+            set,
+            rules,
+            labels,
+            rule_labels,
+        })
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Label `texts` as a whole, most confident first
+    ///
+    /// Each rule counts once however many texts it matches.
+    pub fn classify<'a>(
+        &self,
+        texts: impl IntoIterator<Item = &'a str>,
+        min_confidence: f64,
+    ) -> Vec<Label> {
+        let mut matched = vec![false; self.rules.len()];
+        for text in texts {
+            for idx in self.set.matches(text).iter() {
+                matched[idx] = true;
+            }
+        }
+
+        let mut misses = vec![1.0f64; self.labels.len()];
+        let mut counts = vec![0usize; self.labels.len()];
+        for (idx, _) in matched.iter().enumerate().filter(|(_, hit)| **hit) {
+            let label = self.rule_labels[idx];
+            misses[label] *= 1.0 - self.rules[idx].weight;
+            counts[label] += 1;
+        }
+
+        let mut labels: Vec<Label> = self
+            .labels
+            .iter()
+            .zip(misses.iter().zip(&counts))
+            .filter(|(_, (_, count))| **count > 0)
+            .map(|(label, (miss, count))| Label {
+                label: label.clone(),
+                confidence: 1.0 - miss,
+                matched_rules: *count,
+            })
+            .filter(|label| label.confidence >= min_confidence)
+            .collect();
+        labels.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        labels
+    }
+}
+
+/// Collect every string value (not dict keys) in a nested payload, from
+/// `value` at `path` and nesting `depth` down
+fn collect_strings<'py>(
+    value: &Bound<'py, PyAny>,
+    path: &str,
+    depth: usize,
+    out: &mut Vec<Bound<'py, PyString>>,
+) -> PyResult<()> {
+    // Bounds the recursion, e.g. on a list that contains itself
+    if depth > MAX_DEPTH {
+        return Err(PIIError::NestingLimit {
+            limit: "max_depth",
+            max: MAX_DEPTH,
+            path: path.to_string(),
+        }
+        .into());
+    }
+
+    if let Ok(text) = value.cast::<PyString>() {
+        out.push(text.clone());
+    } else if let Ok(dict) = value.cast::<PyDict>() {
+        for (key, item) in dict.iter() {
+            let key = key.str()?;
+            let key = key.to_string_lossy();
+            let item_path = if path.is_empty() {
+                key.into_owned()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            collect_strings(&item, &item_path, depth + 1, out)?;
+        }
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        for (idx, item) in value.try_iter()?.enumerate() {
+            collect_strings(&item?, &format!("{}[{}]", path, idx), depth + 1, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Payload classifier exposed to Python and registered as a gateway plugin
+///
+/// # Example (Python)
+/// ```python
+/// from plugins_rust import Classifier
+///
+/// classifier = Classifier({
+///     "rules": {"contains_finance": [{"pattern": r"\bIBAN\b", "weight": 0.8}]},
+///     "include_builtin": False,
+/// })
+/// classifier.classify({"prompt": "Pay to IBAN DE89 3704 0044 0532 0130 00"})
+/// # [{"label": "contains_finance", "confidence": 0.8, "matched_rules": 1}]
+/// ```
+#[pyclass(name = "Classifier", module = "plugins_rust", frozen)]
+pub struct Classifier {
+    rules: RuleSet,
+    min_confidence: f64,
+}
+
+#[pymethods]
+impl Classifier {
+    /// Create a classifier
+    ///
+    /// # Arguments
+    /// * `config` - Optional dict:
+    ///   * `rules` (dict): Label -> non-empty list of patterns, each a string
+    ///     (weight 0.5) or `{"pattern": str, "weight": float}`
+    ///   * `include_builtin` (bool): Also use the built-in "contains_code",
+    ///     "contains_medical" and "contains_legal" groups (default: True)
+    ///   * `min_confidence` (float): Lowest confidence reported (default: 0.5)
+    ///   * `case_sensitive` (bool): Match rules case-sensitively (default: False)
+    #[new]
+    #[pyo3(signature = (config=None))]
+    pub fn new(config: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = match config {
+            Some(dict) => ClassifierConfig::from_py_dict(dict)?,
+            None => ClassifierConfig::default(),
+        };
+        Ok(Self::from_config(config)?)
+    }
+
+    /// All labels this classifier can assign
+    #[getter]
+    pub fn labels(&self) -> Vec<String> {
+        self.rules.labels().to_vec()
+    }
+
+    /// Label a string or nested payload, most confident first
+    ///
+    /// # Returns
+    /// List of `{"label": str, "confidence": float, "matched_rules": int}`
+    ///
+    /// # Raises
+    /// `PayloadLimitError` for payloads nested more than 512 levels deep
+    pub fn classify(&self, py: Python, payload: &Bound<'_, PyAny>) -> PyResult<Py<PyList>> {
+        let labels = self.classify_payload(py, payload)?;
+        let py_labels = PyList::empty(py);
+        for label in &labels {
+            py_labels.append(label.to_py_dict(py)?)?;
+        }
+        Ok(py_labels.unbind())
+    }
+
+    /// Run the classifier as a gateway plugin on a shared `HookContext`
+    pub fn process(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        GatewayPlugin::process(self, py, context)
+    }
+}
+
+impl Classifier {
+    pub const PLUGIN_NAME: &'static str = "classifier";
+    pub const HOOKS: &'static [Hook] = &[Hook::OnRequest, Hook::OnResponse, Hook::OnToolResult];
+
+    pub fn from_config(config: ClassifierConfig) -> PIIResult<Self> {
+        Ok(Self {
+            rules: RuleSet::compile(config.all_rules(), config.case_sensitive)?,
+            min_confidence: config.min_confidence,
+        })
+    }
+
+    fn classify_payload(&self, py: Python, payload: &Bound<'_, PyAny>) -> PyResult<Vec<Label>> {
+        let mut strings = Vec::new();
+        collect_strings(payload, "", 1, &mut strings)?;
+        // Strings with lone surrogates cannot be borrowed as UTF-8; skip them
+        let texts: Vec<Cow<str>> = strings.iter().filter_map(|s| s.to_cow().ok()).collect();
+        let texts: Vec<&str> = texts.iter().map(AsRef::as_ref).collect();
+        Ok(py.detach(|| self.rules.classify(texts, self.min_confidence)))
+    }
+
+    fn classify_context(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        let payload = context.borrow().payload.clone_ref(py);
+        let labels = self.classify_payload(py, payload.bind(py))?;
+
+        let py_labels = PyList::empty(py);
+        for label in &labels {
+            py_labels.append(label.to_py_dict(py)?)?;
+        }
+        let finding = PyDict::new(py);
+        finding.set_item("labels", py_labels)?;
+        context
+            .borrow()
+            .add_finding(py, Self::PLUGIN_NAME, finding.into_any().unbind())
+    }
+}
+
+impl GatewayPlugin for Classifier {
+    fn name(&self) -> &'static str {
+        Self::PLUGIN_NAME
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn hooks(&self) -> &'static [Hook] {
+        Self::HOOKS
+    }
+
+    fn on_request(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.classify_context(py, context)
+    }
+
+    fn on_response(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.classify_context(py, context)
+    }
+
+    fn on_tool_result(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.classify_context(py, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin() -> RuleSet {
+        RuleSet::compile(ClassifierConfig::default().all_rules(), false).unwrap()
+    }
+
+    #[test]
+    fn test_classify_builtin_groups() {
+        let labels = builtin().classify(["def main():\n    import os"], 0.5);
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].label, "contains_code");
+        assert_eq!(labels[0].matched_rules, 2);
+        assert!((labels[0].confidence - 0.76).abs() < 1e-9);
+
+        let labels = builtin().classify(
+            ["The plaintiff", "and the DEFENDANT agree, hereinafter"],
+            0.5,
+        );
+        assert_eq!(labels[0].label, "contains_legal");
+        assert!(builtin().classify(["hello there"], 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_min_confidence_and_invalid_pattern() {
+        // A single weak rule stays below the default threshold
+        assert!(builtin().classify(["see the contract"], 0.5).is_empty());
+        assert_eq!(builtin().classify(["see the contract"], 0.1).len(), 1);
+
+        let rules = vec![Rule {
+            label: "broken".to_string(),
+            pattern: "(".to_string(),
+            weight: 0.5,
+        }];
+        assert!(matches!(
+            RuleSet::compile(rules, false),
+            Err(PIIError::InvalidPattern { .. })
+        ));
+    }
+}
+
+/// These tests need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::*;
+    use crate::pii_filter::error::exceptions;
+
+    #[test]
+    fn test_classify_nested_payload() {
+        Python::initialize();
+        Python::attach(|py| {
+            let classifier = Classifier::from_config(ClassifierConfig::default()).unwrap();
+            let payload = py
+                .eval(
+                    c"{'a': [('x', {'code': 'def main():'})], 'def f():': 1}",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let labels = classifier.classify_payload(py, &payload).unwrap();
+            assert_eq!(labels.len(), 1);
+            assert_eq!(labels[0].label, "contains_code");
+            // Dict keys are not classified
+            assert_eq!(labels[0].matched_rules, 1);
+        });
+    }
+
+    #[test]
+    fn test_depth_is_bounded() {
+        Python::initialize();
+        // Unoptimized frames at MAX_DEPTH outgrow the default test thread stack
+        let deep = std::thread::Builder::new().stack_size(64 << 20);
+        deep.spawn(|| {
+            Python::attach(|py| {
+                let classifier = Classifier::from_config(ClassifierConfig::default()).unwrap();
+                let looped = PyList::empty(py);
+                looped.append(&looped).unwrap();
+                let err = classifier.classify_payload(py, &looped).unwrap_err();
+                assert!(err.is_instance_of::<exceptions::PayloadLimitError>(py));
+                looped.call_method0("clear").unwrap();
+            })
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    }
+}
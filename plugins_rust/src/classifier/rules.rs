@@ -0,0 +1,273 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Rule groups for the classifier plugin
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+
+use crate::pii_filter::error::PIIError;
+
+/// Weight of a rule given as a bare pattern string
+pub const DEFAULT_WEIGHT: f64 = 0.5;
+
+/// One weighted pattern contributing to a label
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub label: String,
+    pub pattern: String,
+    /// Confidence contributed by a match, in (0, 1]
+    pub weight: f64,
+}
+
+/// Built-in rule groups: (label, [(pattern, weight)])
+const BUILTIN_RULES: &[(&str, &[(&str, f64)])] = &[
+    (
+        "contains_code",
+        &[
+            (r"\b(def|class)\s+\w+\s*[(:]", 0.6),
+            (r"\b(function|const|let|var)\s+\w+\s*[=(]", 0.5),
+            (r"#include\s*<\w+", 0.7),
+            (
+                r"\b(public|private|static)\s+(void|int|class|String)\b",
+                0.6,
+            ),
+            (r"```", 0.5),
+            (
+                r"\b(SELECT|INSERT|UPDATE|DELETE)\b.+\b(FROM|INTO|SET)\b",
+                0.5,
+            ),
+            (r"\bimport\s+[\w.]+|\bfrom\s+[\w.]+\s+import\b", 0.4),
+        ],
+    ),
+    (
+        "contains_medical",
+        &[
+            (r"\b(diagnos(is|ed)|prognosis|symptoms?)\b", 0.4),
+            (r"\b(prescri(bed|ption)|dosage|\d+\s?mg)\b", 0.5),
+            (
+                r"\b(patient|clinical|physician|oncolog\w*|cardiolog\w*)\b",
+                0.3,
+            ),
+            (r"\bICD-?10\b", 0.6),
+            (r"\b(HIPAA|medical record|health record)\b", 0.5),
+        ],
+    ),
+    (
+        "contains_legal",
+        &[
+            (r"\b(plaintiff|defendant|litigation|lawsuit)\b", 0.5),
+            (r"\b(hereinafter|heretofore|whereas|indemnif\w+)\b", 0.6),
+            (r"\b(attorney[- ]client|privileged and confidential)\b", 0.7),
+            (r"\b(non-disclosure|governing law|jurisdiction)\b", 0.4),
+            (r"\b(contract|agreement|clause|statute)\b", 0.2),
+        ],
+    ),
+];
+
+/// Classifier configuration
+#[derive(Debug, Clone)]
+pub struct ClassifierConfig {
+    /// Rules from the config, added to the built-ins when enabled
+    pub rules: Vec<Rule>,
+    pub include_builtin: bool,
+    /// Labels below this confidence are not reported
+    pub min_confidence: f64,
+    pub case_sensitive: bool,
+}
+
+impl Default for ClassifierConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            include_builtin: true,
+            min_confidence: 0.5,
+            case_sensitive: false,
+        }
+    }
+}
+
+impl ClassifierConfig {
+    /// All rules to compile, built-ins first
+    pub fn all_rules(&self) -> Vec<Rule> {
+        let mut rules = Vec::new();
+        if self.include_builtin {
+            for (label, patterns) in BUILTIN_RULES {
+                for (pattern, weight) in *patterns {
+                    rules.push(Rule {
+                        label: label.to_string(),
+                        pattern: pattern.to_string(),
+                        weight: *weight,
+                    });
+                }
+            }
+        }
+        rules.extend(self.rules.iter().cloned());
+        rules
+    }
+
+    /// Extract configuration from a Python dict
+    ///
+    /// `rules` maps each label to a non-empty list of patterns, given either
+    /// as a string or as `{"pattern": str, "weight": float}`. Unknown keys in
+    /// a pattern dict are rejected so a typo doesn't silently fall back to
+    /// the default weight.
+    pub fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut config = Self::default();
+
+        if let Some(value) = dict.get_item("rules")? {
+            for (label, patterns) in value.cast::<PyDict>()?.iter() {
+                let label: String = label.extract()?;
+                let count = config.rules.len();
+                for entry in patterns.try_iter()? {
+                    let entry = entry?;
+                    let (pattern, weight) = if let Ok(pattern) = entry.cast::<PyString>() {
+                        (pattern.to_string(), DEFAULT_WEIGHT)
+                    } else {
+                        let mut pattern: Option<String> = None;
+                        let mut weight = DEFAULT_WEIGHT;
+                        for (key, value) in entry.cast::<PyDict>()?.iter() {
+                            let key: String = key.extract()?;
+                            match key.as_str() {
+                                "pattern" => pattern = Some(value.extract()?),
+                                "weight" => weight = value.extract()?,
+                                _ => {
+                                    return Err(PIIError::InvalidValue {
+                                        field: "rules",
+                                        value: format!("{}: unknown key '{}'", label, key),
+                                    }
+                                    .into())
+                                }
+                            }
+                        }
+                        (pattern.ok_or(PIIError::MissingField("pattern"))?, weight)
+                    };
+                    if !(weight > 0.0 && weight <= 1.0) {
+                        return Err(PIIError::InvalidValue {
+                            field: "weight",
+                            value: weight.to_string(),
+                        }
+                        .into());
+                    }
+                    config.rules.push(Rule {
+                        label: label.clone(),
+                        pattern,
+                        weight,
+                    });
+                }
+                // An empty group could never assign its label
+                if config.rules.len() == count {
+                    return Err(PIIError::InvalidValue {
+                        field: "rules",
+                        value: format!("{}: no patterns", label),
+                    }
+                    .into());
+                }
+            }
+        }
+        if let Some(value) = dict.get_item("include_builtin")? {
+            config.include_builtin = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("min_confidence")? {
+            config.min_confidence = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("case_sensitive")? {
+            config.case_sensitive = value.extract()?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// These tests need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::*;
+    use crate::pii_filter::error::exceptions;
+
+    fn parse(py: Python, rules: &std::ffi::CStr) -> PyResult<ClassifierConfig> {
+        let dict = PyDict::new(py);
+        dict.set_item("rules", py.eval(rules, None, None)?)?;
+        dict.set_item("include_builtin", false)?;
+        ClassifierConfig::from_py_dict(&dict)
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        Python::initialize();
+        Python::attach(|py| {
+            let config = parse(
+                py,
+                c"{'finance': [r'\\bIBAN\\b', {'pattern': 'SWIFT', 'weight': 1}], 'x': [{'pattern': 'y'}]}",
+            )
+            .unwrap();
+            assert!(!config.include_builtin);
+            let weights: Vec<_> = config
+                .all_rules()
+                .iter()
+                .map(|rule| (rule.label.clone(), rule.weight))
+                .collect();
+            assert_eq!(
+                weights,
+                vec![
+                    ("finance".to_string(), DEFAULT_WEIGHT),
+                    ("finance".to_string(), 1.0),
+                    ("x".to_string(), DEFAULT_WEIGHT),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_malformed_weights() {
+        Python::initialize();
+        Python::attach(|py| {
+            for rules in [
+                c"{'a': [{'pattern': 'x', 'weight': 0}]}",
+                c"{'a': [{'pattern': 'x', 'weight': -0.5}]}",
+                c"{'a': [{'pattern': 'x', 'weight': 1.5}]}",
+                c"{'a': [{'pattern': 'x', 'weight': float('nan')}]}",
+            ] {
+                let err = parse(py, rules).unwrap_err();
+                assert!(
+                    err.is_instance_of::<exceptions::ConfigError>(py),
+                    "{:?}",
+                    rules
+                );
+                assert!(err.to_string().contains("weight"));
+            }
+            let err = parse(py, c"{'a': [{'pattern': 'x', 'weight': 'high'}]}").unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_empty_groups() {
+        Python::initialize();
+        Python::attach(|py| {
+            let err = parse(py, c"{'a': ['x'], 'empty': []}").unwrap_err();
+            assert!(err.is_instance_of::<exceptions::ConfigError>(py));
+            assert!(err.to_string().contains("empty: no patterns"));
+            // No groups at all is fine
+            assert!(parse(py, c"{}").unwrap().rules.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        Python::initialize();
+        Python::attach(|py| {
+            let err = parse(py, c"{'a': [{'pattern': 'x', 'wieght': 0.9}]}").unwrap_err();
+            assert!(err.is_instance_of::<exceptions::ConfigError>(py));
+            assert!(err.to_string().contains("unknown key 'wieght'"));
+
+            let err = parse(py, c"{'a': [{'weight': 0.9}]}").unwrap_err();
+            assert!(err.is_instance_of::<exceptions::ConfigError>(py));
+            assert!(err.to_string().contains("pattern"));
+
+            let err = parse(py, c"{'a': [42]}").unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+        });
+    }
+}
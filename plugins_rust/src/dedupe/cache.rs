@@ -0,0 +1,133 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Sharded, TTL-bounded set of content keys
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Content key: the first 128 bits of a SHA-256 digest
+pub type Key = u128;
+
+const SHARDS: usize = 16;
+
+#[derive(Default)]
+struct Shard {
+    /// Key -> time it was (last) inserted
+    entries: HashMap<Key, Instant>,
+    /// Insertion order; may hold stale duplicates of re-inserted keys
+    order: VecDeque<(Key, Instant)>,
+}
+
+impl Shard {
+    /// Drop expired entries and evict the oldest beyond `capacity`
+    fn evict(&mut self, now: Instant, ttl: Duration, capacity: usize) {
+        while let Some(&(key, inserted)) = self.order.front() {
+            let expired = now.duration_since(inserted) >= ttl;
+            if !expired && self.entries.len() <= capacity {
+                break;
+            }
+            self.order.pop_front();
+            // Only remove the map entry if it was not re-inserted later
+            if self.entries.get(&key) == Some(&inserted) {
+                self.entries.remove(&key);
+            }
+        }
+    }
+}
+
+/// Set of recently seen keys with a TTL and a memory bound
+///
+/// Keys are spread over independently locked shards, so concurrent callers
+/// rarely contend on the same lock.
+pub struct SeenCache {
+    shards: Vec<Mutex<Shard>>,
+    ttl: Duration,
+    shard_capacity: usize,
+}
+
+impl SeenCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            shards: (0..SHARDS).map(|_| Mutex::default()).collect(),
+            ttl,
+            shard_capacity: max_entries.div_ceil(SHARDS).max(1),
+        }
+    }
+
+    fn shard(&self, key: Key) -> &Mutex<Shard> {
+        &self.shards[(key as usize) % SHARDS]
+    }
+
+    /// Whether `key` was seen within the TTL; records it if not
+    ///
+    /// A hit does not extend the TTL, so a payload repeated continuously is
+    /// still let through once per TTL window.
+    pub fn check_and_insert(&self, key: Key, now: Instant) -> bool {
+        let mut shard = self.shard(key).lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&inserted) = shard.entries.get(&key) {
+            if now.duration_since(inserted) < self.ttl {
+                return true;
+            }
+        }
+        shard.entries.insert(key, now);
+        shard.order.push_back((key, now));
+        shard.evict(now, self.ttl, self.shard_capacity);
+        false
+    }
+
+    /// Number of cached keys, including any not yet evicted after expiring
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                shard
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .entries
+                    .len()
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            *shard.lock().unwrap_or_else(|e| e.into_inner()) = Shard::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seen_within_ttl() {
+        let cache = SeenCache::new(Duration::from_secs(10), 100);
+        let now = Instant::now();
+
+        assert!(!cache.check_and_insert(1, now));
+        assert!(cache.check_and_insert(1, now + Duration::from_secs(5)));
+        assert!(!cache.check_and_insert(1, now + Duration::from_secs(10)));
+        assert!(!cache.check_and_insert(2, now));
+    }
+
+    #[test]
+    fn test_memory_bound_evicts_oldest() {
+        let cache = SeenCache::new(Duration::from_secs(60), SHARDS);
+        let now = Instant::now();
+
+        // Keys 0, SHARDS, 2*SHARDS land in the same shard of capacity 1
+        for key in [0, SHARDS as Key, 2 * SHARDS as Key] {
+            assert!(!cache.check_and_insert(key, now));
+        }
+        assert_eq!(cache.len(), 1);
+        assert!(cache.check_and_insert(2 * SHARDS as Key, now));
+        assert!(!cache.check_and_insert(0, now));
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Configuration for the dedupe plugin
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::time::Duration;
+
+use crate::pii_filter::error::PIIError;
+
+/// Dedupe cache configuration
+#[derive(Debug, Clone)]
+pub struct DedupeConfig {
+    /// How long a payload counts as seen
+    pub ttl: Duration,
+    /// Upper bound on cached keys; the oldest are evicted first
+    pub max_entries: usize,
+    /// Include tenant and tool name in the key when run as a plugin
+    pub scope_by_tool: bool,
+}
+
+impl Default for DedupeConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            max_entries: 100_000,
+            scope_by_tool: true,
+        }
+    }
+}
+
+impl DedupeConfig {
+    /// Extract configuration from a Python dict
+    pub fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut config = Self::default();
+
+        if let Some(value) = dict.get_item("ttl_seconds")? {
+            let seconds: f64 = value.extract()?;
+            config.ttl =
+                Duration::try_from_secs_f64(seconds).map_err(|_| PIIError::InvalidValue {
+                    field: "ttl_seconds",
+                    value: seconds.to_string(),
+                })?;
+        }
+        if let Some(value) = dict.get_item("max_entries")? {
+            config.max_entries = value.extract()?;
+            if config.max_entries == 0 {
+                return Err(PIIError::InvalidValue {
+                    field: "max_entries",
+                    value: "0".to_string(),
+                }
+                .into());
+            }
+        }
+        if let Some(value) = dict.get_item("scope_by_tool")? {
+            config.scope_by_tool = value.extract()?;
+        }
+
+        Ok(config)
+    }
+}
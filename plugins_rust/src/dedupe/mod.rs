@@ -0,0 +1,15 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Dedupe Plugin - Rust Implementation
+//
+// Content-hash cache of recently seen payloads, so the gateway can
+// short-circuit repeated idempotent tool calls. The cache is sharded and
+// checked without the GIL, avoiding the single dict-and-lock hot spot of a
+// Python implementation.
+
+pub mod cache;
+pub mod config;
+pub mod plugin;
+
+pub use plugin::DedupeCache;
@@ -0,0 +1,390 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Python-facing dedupe cache and GatewayPlugin implementation
+
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::time::Instant;
+
+use super::cache::{Key, SeenCache};
+use super::config::DedupeConfig;
+use crate::pii_filter::config::MAX_DEPTH;
+use crate::pii_filter::PIIError;
+use crate::plugin::{GatewayPlugin, Hook, HookContext};
+
+/// Feed a canonical encoding of `value`, at `path` and nesting `depth`, into
+/// `hasher`
+///
+/// Every value is tagged with its kind so `1`, `"1"` and `[1]` differ, and
+/// dict items are hashed independently and sorted so key order is ignored.
+/// Lists and tuples with equal items hash alike, as they serialize alike.
+/// Strings are hashed by their code units, lone surrogates included, so no
+/// two distinct strings share a key. Other types have no canonical form and
+/// raise `TypeError`.
+fn hash_value(
+    hasher: &mut Sha256,
+    value: &Bound<'_, PyAny>,
+    path: &str,
+    depth: usize,
+) -> PyResult<()> {
+    // Bounds the recursion, e.g. on a list that contains itself
+    if depth > MAX_DEPTH {
+        return Err(PIIError::NestingLimit {
+            limit: "max_depth",
+            max: MAX_DEPTH,
+            path: path.to_string(),
+        }
+        .into());
+    }
+
+    if value.is_none() {
+        hasher.update(b"n");
+    } else if let Ok(text) = value.cast::<PyString>() {
+        let bytes: Cow<'_, [u8]> = match text.to_cow() {
+            Ok(Cow::Borrowed(text)) => Cow::Borrowed(text.as_bytes()),
+            Ok(Cow::Owned(text)) => Cow::Owned(text.into_bytes()),
+            Err(_) => {
+                let encoded = text.call_method1("encode", ("utf-8", "surrogatepass"))?;
+                Cow::Owned(encoded.cast::<PyBytes>()?.as_bytes().to_vec())
+            }
+        };
+        hasher.update(b"s");
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(&bytes);
+    } else if let Ok(flag) = value.cast::<PyBool>() {
+        hasher.update(if flag.is_true() { b"T" } else { b"F" });
+    } else if value.is_instance_of::<PyInt>() || value.is_instance_of::<PyFloat>() {
+        let repr = value.repr()?;
+        hasher.update(b"i");
+        hasher.update(repr.to_cow()?.as_bytes());
+        hasher.update(b";");
+    } else if let Ok(bytes) = value.cast::<PyBytes>() {
+        hasher.update(b"y");
+        hasher.update((bytes.as_bytes().len() as u64).to_le_bytes());
+        hasher.update(bytes.as_bytes());
+    } else if let Ok(dict) = value.cast::<PyDict>() {
+        let mut items = Vec::with_capacity(dict.len());
+        for (key, item) in dict.iter() {
+            let item_path = if path.is_empty() {
+                key.str()?.to_string_lossy().into_owned()
+            } else {
+                format!("{}.{}", path, key.str()?.to_string_lossy())
+            };
+            let mut item_hasher = Sha256::new();
+            hash_value(&mut item_hasher, &key, &item_path, depth + 1)?;
+            hash_value(&mut item_hasher, &item, &item_path, depth + 1)?;
+            items.push(item_hasher.finalize());
+        }
+        items.sort_unstable();
+        hasher.update(b"d");
+        hasher.update((items.len() as u64).to_le_bytes());
+        for item in items {
+            hasher.update(item);
+        }
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        hasher.update(b"l");
+        hasher.update((value.len()? as u64).to_le_bytes());
+        for (idx, item) in value.try_iter()?.enumerate() {
+            hash_value(hasher, &item?, &format!("{}[{}]", path, idx), depth + 1)?;
+        }
+    } else {
+        return Err(PyTypeError::new_err(format!(
+            "Cannot dedupe '{}' at '{}': expected None, bool, int, float, str, bytes, \
+             dict, list or tuple",
+            value.get_type().name()?,
+            path
+        )));
+    }
+    Ok(())
+}
+
+/// Content key of a payload, optionally scoped to a tenant and tool
+pub fn content_key(
+    payload: &Bound<'_, PyAny>,
+    scope: Option<(Option<&str>, Option<&str>)>,
+) -> PyResult<Key> {
+    let mut hasher = Sha256::new();
+    if let Some((tenant, tool_name)) = scope {
+        for part in [tenant, tool_name] {
+            let part = part.unwrap_or("");
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+    }
+    hash_value(&mut hasher, payload, "", 1)?;
+    let digest = hasher.finalize();
+    let mut key = [0u8; 16];
+    key.copy_from_slice(&digest[..16]);
+    Ok(Key::from_le_bytes(key))
+}
+
+/// Cache of recently seen payloads exposed to Python and registered as the
+/// `dedupe` gateway plugin
+///
+/// # Example (Python)
+/// ```python
+/// from plugins_rust import DedupeCache
+///
+/// cache = DedupeCache({"ttl_seconds": 30, "max_entries": 50_000})
+/// if cache.seen(tool_call_args):
+///     return cached_response
+/// ```
+#[pyclass(name = "DedupeCache", module = "plugins_rust", frozen)]
+pub struct DedupeCache {
+    config: DedupeConfig,
+    cache: SeenCache,
+}
+
+#[pymethods]
+impl DedupeCache {
+    /// Create a dedupe cache
+    ///
+    /// # Arguments
+    /// * `config` - Optional dict:
+    ///   * `ttl_seconds` (float): How long a payload counts as seen (default: 60)
+    ///   * `max_entries` (int): Maximum cached payload keys (default: 100000)
+    ///   * `scope_by_tool` (bool): As a plugin, key on tenant and tool name too (default: True)
+    #[new]
+    #[pyo3(signature = (config=None))]
+    pub fn new(config: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = match config {
+            Some(dict) => DedupeConfig::from_py_dict(dict)?,
+            None => DedupeConfig::default(),
+        };
+        Ok(Self::from_config(config))
+    }
+
+    /// Whether an equal payload was seen within the TTL
+    ///
+    /// Records the payload when it was not, so the first call for a payload
+    /// returns False and repeats within the TTL return True. Dict key order
+    /// does not matter.
+    pub fn seen(&self, py: Python, payload: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let key = content_key(payload, None)?;
+        Ok(py.detach(|| self.cache.check_and_insert(key, Instant::now())))
+    }
+
+    /// Forget all cached payloads
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Run the cache as a gateway plugin on a shared `HookContext`
+    ///
+    /// Repeated requests are reported as a `{"duplicate": True}` finding;
+    /// the gateway decides whether to short-circuit them.
+    pub fn process(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        GatewayPlugin::process(self, py, context)
+    }
+}
+
+impl DedupeCache {
+    pub const PLUGIN_NAME: &'static str = "dedupe";
+    pub const HOOKS: &'static [Hook] = &[Hook::OnRequest];
+
+    pub fn from_config(config: DedupeConfig) -> Self {
+        let cache = SeenCache::new(config.ttl, config.max_entries);
+        Self { config, cache }
+    }
+}
+
+impl GatewayPlugin for DedupeCache {
+    fn name(&self) -> &'static str {
+        Self::PLUGIN_NAME
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn hooks(&self) -> &'static [Hook] {
+        Self::HOOKS
+    }
+
+    fn on_request(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        let key = {
+            let context = context.borrow();
+            let scope = self
+                .config
+                .scope_by_tool
+                .then(|| (context.tenant.as_deref(), context.tool_name.as_deref()));
+            content_key(context.payload.bind(py), scope)?
+        };
+        if !py.detach(|| self.cache.check_and_insert(key, Instant::now())) {
+            return Ok(());
+        }
+
+        let finding = PyDict::new(py);
+        finding.set_item("duplicate", true)?;
+        context
+            .borrow()
+            .add_finding(py, Self::PLUGIN_NAME, finding.into_any().unbind())
+    }
+}
+
+/// These tests need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::*;
+    use pyo3::types::IntoPyDict;
+
+    fn eval<'py>(py: Python<'py>, code: &std::ffi::CStr) -> Bound<'py, PyAny> {
+        py.eval(code, None, None).unwrap()
+    }
+
+    fn key(value: &Bound<'_, PyAny>) -> Key {
+        content_key(value, None).unwrap()
+    }
+
+    #[test]
+    fn test_key_ignores_dict_order() {
+        Python::initialize();
+        Python::attach(|py| {
+            let first = eval(py, c"{'a': 1, 'b': [1, 2], 'c': {'x': None, 'y': 'z'}}");
+            let second = eval(py, c"{'c': {'y': 'z', 'x': None}, 'b': [1, 2], 'a': 1}");
+            assert_eq!(key(&first), key(&second));
+            assert_ne!(key(&first), key(&eval(py, c"{'a': 1, 'b': [2, 1]}")));
+        });
+    }
+
+    #[test]
+    fn test_key_tags_kinds() {
+        Python::initialize();
+        Python::attach(|py| {
+            // Lists and tuples serialize alike, so they dedupe alike
+            assert_eq!(key(&eval(py, c"[1, 'a']")), key(&eval(py, c"(1, 'a')")));
+            let keys = [
+                key(&eval(py, c"1")),
+                key(&eval(py, c"'1'")),
+                key(&eval(py, c"[1]")),
+                key(&eval(py, c"b'1'")),
+                key(&eval(py, c"True")),
+                key(&eval(py, c"None")),
+            ];
+            for (idx, first) in keys.iter().enumerate() {
+                for second in &keys[idx + 1..] {
+                    assert_ne!(first, second);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_key_separates_scopes() {
+        Python::initialize();
+        Python::attach(|py| {
+            let payload = eval(py, c"{'q': 'x'}");
+            let scoped = |tenant, tool| content_key(&payload, Some((tenant, tool))).unwrap();
+            assert_ne!(scoped(Some("a"), Some("t")), scoped(Some("b"), Some("t")));
+            assert_ne!(scoped(Some("a"), Some("t")), scoped(Some("a"), Some("u")));
+            // Length prefixes keep the parts from running together
+            assert_ne!(scoped(Some("ab"), Some("c")), scoped(Some("a"), Some("bc")));
+            assert_ne!(scoped(None, None), key(&payload));
+        });
+    }
+
+    #[test]
+    fn test_lone_surrogates_do_not_collide() {
+        Python::initialize();
+        Python::attach(|py| {
+            let first = key(&eval(py, c"'a\\ud800'"));
+            assert_ne!(first, key(&eval(py, c"'a\\ud801'")));
+            assert_ne!(first, key(&eval(py, c"'a\\ufffd'")));
+            assert_eq!(first, key(&eval(py, c"'a' + '\\ud800'")));
+        });
+    }
+
+    #[test]
+    fn test_unsupported_types_raise() {
+        Python::initialize();
+        Python::attach(|py| {
+            // Objects with equal `str()` must not be treated as equal payloads
+            let obj = eval(py, c"type('Same', (), {'__str__': lambda self: 'same'})()");
+            let err = content_key(&obj, None).unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert!(err.to_string().contains("Same"));
+            let err = content_key(&eval(py, c"{'ids': [{1, 2}]}"), None).unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert!(err.to_string().contains("ids[0]"));
+        });
+    }
+
+    #[test]
+    fn test_depth_is_bounded() {
+        Python::initialize();
+        // Unoptimized frames at MAX_DEPTH outgrow the default test thread stack
+        let deep = std::thread::Builder::new().stack_size(64 << 20);
+        deep.spawn(|| {
+            Python::attach(|py| {
+                let nested = PyList::empty(py);
+                let mut inner = nested.clone();
+                for _ in 0..MAX_DEPTH {
+                    let next = PyList::empty(py);
+                    inner.append(&next).unwrap();
+                    inner = next;
+                }
+                let err = content_key(&nested, None).unwrap_err();
+                assert!(err.to_string().contains("max_depth"));
+
+                let looped = PyList::empty(py);
+                looped.append(&looped).unwrap();
+                assert!(content_key(&looped, None).is_err());
+                looped.call_method0("clear").unwrap();
+            })
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_seen_records_payloads() {
+        Python::initialize();
+        Python::attach(|py| {
+            let cache = DedupeCache::new(None).unwrap();
+            assert!(!cache.seen(py, &eval(py, c"{'a': 1, 'b': 2}")).unwrap());
+            assert!(cache.seen(py, &eval(py, c"{'b': 2, 'a': 1}")).unwrap());
+            assert!(!cache.seen(py, &eval(py, c"{'a': 2}")).unwrap());
+            assert_eq!(cache.__len__(), 2);
+            cache.clear();
+            assert!(!cache.seen(py, &eval(py, c"{'a': 1, 'b': 2}")).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_process_reports_duplicates() {
+        Python::initialize();
+        Python::attach(|py| {
+            let cache = DedupeCache::new(None).unwrap();
+            let run = |tool: &str| {
+                let context = HookContext::new(
+                    py,
+                    eval(py, c"{'q': 'x'}").unbind(),
+                    "request",
+                    Some("tenant".to_string()),
+                    Some(tool.to_string()),
+                    None,
+                )
+                .unwrap();
+                let context = Bound::new(py, context).unwrap();
+                cache.process(py, &context).unwrap();
+                let findings = context.borrow().findings.bind(py).clone();
+                findings.get_item(DedupeCache::PLUGIN_NAME).unwrap()
+            };
+            assert!(run("search").is_none());
+            assert!(run("fetch").is_none());
+            let finding = run("search").unwrap().get_item(0).unwrap();
+            let expected = [("duplicate", true)].into_py_dict(py).unwrap();
+            assert!(finding.eq(expected).unwrap());
+        });
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Configuration for the encoding normalizer plugin
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::str::FromStr;
+
+use crate::pii_filter::error::PIIError;
+
+/// How to handle bytes that are not valid UTF-8 (and carry no BOM)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidPolicy {
+    /// Decode as Latin-1, which maps every byte to a character
+    #[default]
+    Latin1,
+    /// Replace invalid sequences with U+FFFD
+    Replace,
+    /// Raise `PIIError` naming the offending field
+    Strict,
+}
+
+impl FromStr for InvalidPolicy {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latin-1" | "latin1" => Ok(InvalidPolicy::Latin1),
+            "replace" => Ok(InvalidPolicy::Replace),
+            "strict" => Ok(InvalidPolicy::Strict),
+            _ => Err(PIIError::InvalidValue {
+                field: "on_invalid",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Encoding normalizer configuration
+#[derive(Debug, Clone)]
+pub struct NormalizerConfig {
+    pub on_invalid: InvalidPolicy,
+    /// Convert `bytes`/`bytearray` values to `str`
+    pub decode_bytes: bool,
+    /// Drop a leading U+FEFF from strings
+    pub strip_bom: bool,
+}
+
+impl Default for NormalizerConfig {
+    fn default() -> Self {
+        Self {
+            on_invalid: InvalidPolicy::Latin1,
+            decode_bytes: true,
+            strip_bom: true,
+        }
+    }
+}
+
+impl NormalizerConfig {
+    /// Extract configuration from a Python dict
+    pub fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut config = Self::default();
+
+        if let Some(value) = dict.get_item("on_invalid")? {
+            config.on_invalid = value.extract::<String>()?.parse()?;
+        }
+        if let Some(value) = dict.get_item("decode_bytes")? {
+            config.decode_bytes = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("strip_bom")? {
+            config.strip_bom = value.extract()?;
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_policy_from_str() {
+        assert_eq!(
+            "latin1".parse::<InvalidPolicy>().unwrap(),
+            InvalidPolicy::Latin1
+        );
+        assert_eq!(
+            "strict".parse::<InvalidPolicy>().unwrap(),
+            InvalidPolicy::Strict
+        );
+        assert!(matches!(
+            "ignore".parse::<InvalidPolicy>(),
+            Err(PIIError::InvalidValue {
+                field: "on_invalid",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Byte-level charset detection and decoding
+
+use std::borrow::Cow;
+
+use super::config::InvalidPolicy;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Encoding a field was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceEncoding {
+    Utf8,
+    /// UTF-8 with a byte order mark
+    Utf8Sig,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+    /// Invalid UTF-8 decoded with U+FFFD replacements
+    Utf8Lossy,
+}
+
+impl SourceEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceEncoding::Utf8 => "utf-8",
+            SourceEncoding::Utf8Sig => "utf-8-sig",
+            SourceEncoding::Utf16Le => "utf-16-le",
+            SourceEncoding::Utf16Be => "utf-16-be",
+            SourceEncoding::Latin1 => "latin-1",
+            SourceEncoding::Utf8Lossy => "utf-8-replace",
+        }
+    }
+}
+
+/// Decode `bytes` to UTF-8 text
+///
+/// A BOM selects UTF-8 or UTF-16; otherwise the bytes are tried as UTF-8
+/// and `policy` decides what happens when they are not. UTF-16 content is
+/// never reinterpreted as Latin-1: invalid code units are replaced unless
+/// the policy is strict. Returns `None` only under `InvalidPolicy::Strict`.
+pub fn decode(bytes: &[u8], policy: InvalidPolicy) -> Option<(Cow<'_, str>, SourceEncoding)> {
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        return decode_utf8(rest, policy, SourceEncoding::Utf8Sig);
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        return decode_utf16(rest, u16::from_le_bytes, policy)
+            .map(|text| (Cow::Owned(text), SourceEncoding::Utf16Le));
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        return decode_utf16(rest, u16::from_be_bytes, policy)
+            .map(|text| (Cow::Owned(text), SourceEncoding::Utf16Be));
+    }
+    decode_utf8(bytes, policy, SourceEncoding::Utf8)
+}
+
+fn decode_utf8(
+    bytes: &[u8],
+    policy: InvalidPolicy,
+    valid: SourceEncoding,
+) -> Option<(Cow<'_, str>, SourceEncoding)> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Some((Cow::Borrowed(text), valid));
+    }
+    match policy {
+        InvalidPolicy::Latin1 => Some((
+            Cow::Owned(bytes.iter().map(|&b| b as char).collect()),
+            SourceEncoding::Latin1,
+        )),
+        InvalidPolicy::Replace => Some((String::from_utf8_lossy(bytes), SourceEncoding::Utf8Lossy)),
+        InvalidPolicy::Strict => None,
+    }
+}
+
+/// Decode UTF-16 code units, replacing unpaired surrogates with U+FFFD
+/// unless `policy` is strict
+pub(super) fn decode_utf16(
+    bytes: &[u8],
+    unit: fn([u8; 2]) -> u16,
+    policy: InvalidPolicy,
+) -> Option<String> {
+    let chunks = bytes.chunks_exact(2);
+    let odd_length = !chunks.remainder().is_empty();
+    let units = chunks.map(|pair| unit([pair[0], pair[1]]));
+
+    let mut text = String::with_capacity(bytes.len() / 2);
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => text.push(c),
+            Err(_) if policy == InvalidPolicy::Strict => return None,
+            Err(_) => text.push(char::REPLACEMENT_CHARACTER),
+        }
+    }
+    if odd_length {
+        if policy == InvalidPolicy::Strict {
+            return None;
+        }
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf8_and_bom() {
+        let (text, encoding) = decode("héllo".as_bytes(), InvalidPolicy::Strict).unwrap();
+        assert!(matches!(text, Cow::Borrowed("héllo")));
+        assert_eq!(encoding, SourceEncoding::Utf8);
+
+        let (text, encoding) = decode(b"\xEF\xBB\xBFhi", InvalidPolicy::Strict).unwrap();
+        assert_eq!(text, "hi");
+        assert_eq!(encoding, SourceEncoding::Utf8Sig);
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let le = b"\xFF\xFEh\x00\xE9\x00";
+        assert_eq!(
+            decode(le, InvalidPolicy::Latin1).unwrap(),
+            (Cow::Owned("hé".to_string()), SourceEncoding::Utf16Le)
+        );
+        let be = b"\xFE\xFF\x00h\x00\xE9";
+        assert_eq!(decode(be, InvalidPolicy::Latin1).unwrap().0, "hé");
+
+        // Unpaired surrogate and a dangling byte
+        let broken = b"\xFF\xFE\x00\xD8h";
+        assert_eq!(
+            decode(broken, InvalidPolicy::Latin1).unwrap().0,
+            "\u{FFFD}\u{FFFD}"
+        );
+        assert!(decode(broken, InvalidPolicy::Strict).is_none());
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8_policies() {
+        let bytes = b"caf\xE9";
+        assert_eq!(
+            decode(bytes, InvalidPolicy::Latin1).unwrap(),
+            (Cow::Owned("café".to_string()), SourceEncoding::Latin1)
+        );
+        assert_eq!(
+            decode(bytes, InvalidPolicy::Replace).unwrap(),
+            (
+                Cow::Owned("caf\u{FFFD}".to_string()),
+                SourceEncoding::Utf8Lossy
+            )
+        );
+        assert!(decode(bytes, InvalidPolicy::Strict).is_none());
+    }
+}
@@ -0,0 +1,14 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Encoding Normalizer Plugin - Rust Implementation
+//
+// Transcodes non-UTF-8 payload fields (raw bytes, UTF-16 with a BOM, Latin-1,
+// surrogate-escaped strings) to UTF-8 so downstream plugins scan real text
+// instead of skipping or mis-scanning mojibake.
+
+pub mod config;
+pub mod decode;
+pub mod normalizer;
+
+pub use normalizer::EncodingNormalizer;
@@ -0,0 +1,471 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Transcoding of nested Python payloads to UTF-8 text
+
+use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyBytes, PyDict, PyList, PyString, PyTuple};
+
+use super::config::NormalizerConfig;
+use super::decode::{decode, decode_utf16, SourceEncoding};
+use crate::pii_filter::config::MAX_DEPTH;
+use crate::pii_filter::error::PIIError;
+use crate::plugin::{GatewayPlugin, Hook, HookContext};
+
+/// A field that was transcoded, and what it was decoded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conversion {
+    pub path: String,
+    pub encoding: SourceEncoding,
+}
+
+impl Conversion {
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("path", &self.path)?;
+        py_dict.set_item("encoding", self.encoding.as_str())?;
+        Ok(py_dict)
+    }
+}
+
+/// Encoding normalizer exposed to Python and registered as a gateway plugin
+///
+/// # Example (Python)
+/// ```python
+/// from plugins_rust import EncodingNormalizer
+///
+/// normalizer = EncodingNormalizer({"on_invalid": "replace"})
+/// payload, conversions = normalizer.normalize({"body": b"caf\xe9"})
+/// # conversions == [{"path": "body", "encoding": "utf-8-replace"}]
+/// ```
+#[pyclass(name = "EncodingNormalizer", module = "plugins_rust", frozen)]
+pub struct EncodingNormalizer {
+    config: NormalizerConfig,
+}
+
+#[pymethods]
+impl EncodingNormalizer {
+    /// Create an encoding normalizer
+    ///
+    /// # Arguments
+    /// * `config` - Optional dict:
+    ///   * `on_invalid` (str): "latin-1" (default), "replace" or "strict"
+    ///   * `decode_bytes` (bool): Convert bytes/bytearray values to str (default: True)
+    ///   * `strip_bom` (bool): Drop a leading U+FEFF from strings (default: True)
+    #[new]
+    #[pyo3(signature = (config=None))]
+    pub fn new(config: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = match config {
+            Some(dict) => NormalizerConfig::from_py_dict(dict)?,
+            None => NormalizerConfig::default(),
+        };
+        Ok(Self::from_config(config))
+    }
+
+    /// Transcode every non-UTF-8 field of `payload` to `str`
+    ///
+    /// # Returns
+    /// `(payload, conversions)`: the payload (the same object when nothing
+    /// needed converting) and a list of `{"path", "encoding"}` dicts
+    ///
+    /// # Raises
+    /// * `PIIError` under `on_invalid="strict"` for a field that cannot be decoded
+    /// * `PayloadLimitError` for payloads nested more than 512 levels deep
+    pub fn normalize(
+        &self,
+        py: Python,
+        payload: &Bound<'_, PyAny>,
+    ) -> PyResult<(Py<PyAny>, Py<PyList>)> {
+        let mut conversions = Vec::new();
+        let normalized = self
+            .normalize_value(py, payload, "", 1, &mut conversions)?
+            .unwrap_or_else(|| payload.clone().unbind());
+
+        let py_conversions = PyList::empty(py);
+        for conversion in &conversions {
+            py_conversions.append(conversion.to_py_dict(py)?)?;
+        }
+        Ok((normalized, py_conversions.unbind()))
+    }
+
+    /// Run the normalizer as a gateway plugin on a shared `HookContext`
+    pub fn process(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        GatewayPlugin::process(self, py, context)
+    }
+}
+
+impl EncodingNormalizer {
+    pub const PLUGIN_NAME: &'static str = "encoding_normalizer";
+    pub const HOOKS: &'static [Hook] = &[Hook::OnRequest, Hook::OnResponse, Hook::OnToolResult];
+
+    pub fn from_config(config: NormalizerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Decode raw bytes found at `path` according to the configured policy
+    fn decode_field(
+        &self,
+        py: Python,
+        bytes: &[u8],
+        path: &str,
+        conversions: &mut Vec<Conversion>,
+    ) -> PyResult<Py<PyAny>> {
+        let (text, encoding) =
+            decode(bytes, self.config.on_invalid).ok_or_else(|| PIIError::InvalidEncoding {
+                path: path.to_string(),
+            })?;
+        conversions.push(Conversion {
+            path: path.to_string(),
+            encoding,
+        });
+        Ok(PyString::new(py, &text).into_any().unbind())
+    }
+
+    /// Normalize one value at nesting `depth`, returning `None` when it is
+    /// already clean UTF-8
+    fn normalize_value(
+        &self,
+        py: Python,
+        value: &Bound<'_, PyAny>,
+        path: &str,
+        depth: usize,
+        conversions: &mut Vec<Conversion>,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        // Bounds the recursion, e.g. on a list that contains itself
+        if depth > MAX_DEPTH {
+            return Err(PIIError::NestingLimit {
+                limit: "max_depth",
+                max: MAX_DEPTH,
+                path: path.to_string(),
+            }
+            .into());
+        }
+
+        if let Ok(py_str) = value.cast::<PyString>() {
+            return match py_str.to_cow() {
+                Ok(text) => match text.strip_prefix('\u{FEFF}') {
+                    Some(rest) if self.config.strip_bom => {
+                        conversions.push(Conversion {
+                            path: path.to_string(),
+                            encoding: SourceEncoding::Utf8Sig,
+                        });
+                        Ok(Some(PyString::new(py, rest).into_any().unbind()))
+                    }
+                    _ => Ok(None),
+                },
+                // Lone surrogates, typically undecodable bytes smuggled in
+                // through `surrogateescape`: recover the original bytes
+                Err(_) => match py_str.call_method1("encode", ("utf-8", "surrogateescape")) {
+                    Ok(bytes) => self
+                        .decode_field(py, bytes.cast::<PyBytes>()?.as_bytes(), path, conversions)
+                        .map(Some),
+                    // Other surrogates stand for no byte, so there is nothing
+                    // to decode: replace them with U+FFFD in the str itself
+                    Err(_) => {
+                        let units =
+                            py_str.call_method1("encode", ("utf-16-le", "surrogatepass"))?;
+                        let text = decode_utf16(
+                            units.cast::<PyBytes>()?.as_bytes(),
+                            u16::from_le_bytes,
+                            self.config.on_invalid,
+                        )
+                        .ok_or_else(|| PIIError::InvalidEncoding {
+                            path: path.to_string(),
+                        })?;
+                        conversions.push(Conversion {
+                            path: path.to_string(),
+                            encoding: SourceEncoding::Utf8Lossy,
+                        });
+                        Ok(Some(PyString::new(py, &text).into_any().unbind()))
+                    }
+                },
+            };
+        }
+
+        if self.config.decode_bytes {
+            if let Ok(bytes) = value.cast::<PyBytes>() {
+                return self
+                    .decode_field(py, bytes.as_bytes(), path, conversions)
+                    .map(Some);
+            }
+            if let Ok(bytes) = value.cast::<PyByteArray>() {
+                return self
+                    .decode_field(py, &bytes.to_vec(), path, conversions)
+                    .map(Some);
+            }
+        }
+
+        if let Ok(dict) = value.cast::<PyDict>() {
+            let mut changed: Vec<(Bound<'_, PyAny>, Py<PyAny>)> = Vec::new();
+            for (key, item) in dict.iter() {
+                let key_str = key.str()?;
+                let key_str = key_str.to_string_lossy();
+                let item_path = if path.is_empty() {
+                    key_str.into_owned()
+                } else {
+                    format!("{}.{}", path, key_str)
+                };
+                if let Some(new_item) =
+                    self.normalize_value(py, &item, &item_path, depth + 1, conversions)?
+                {
+                    changed.push((key, new_item));
+                }
+            }
+            if changed.is_empty() {
+                return Ok(None);
+            }
+            let new_dict = dict.copy()?;
+            for (key, new_item) in changed {
+                new_dict.set_item(key, new_item)?;
+            }
+            return Ok(Some(new_dict.into_any().unbind()));
+        }
+
+        if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+            let items: Vec<Bound<'_, PyAny>> = value.try_iter()?.collect::<PyResult<_>>()?;
+            let mut new_items: Vec<Py<PyAny>> = Vec::with_capacity(items.len());
+            let mut modified = false;
+            for (idx, item) in items.iter().enumerate() {
+                let item_path = format!("{}[{}]", path, idx);
+                match self.normalize_value(py, item, &item_path, depth + 1, conversions)? {
+                    Some(new_item) => {
+                        modified = true;
+                        new_items.push(new_item);
+                    }
+                    None => new_items.push(item.clone().unbind()),
+                }
+            }
+            if !modified {
+                return Ok(None);
+            }
+            if value.is_instance_of::<PyTuple>() {
+                return Ok(Some(PyTuple::new(py, new_items)?.into_any().unbind()));
+            }
+            return Ok(Some(PyList::new(py, new_items)?.into_any().unbind()));
+        }
+
+        Ok(None)
+    }
+
+    fn normalize_context(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        let payload = context.borrow().payload.clone_ref(py);
+        let mut conversions = Vec::new();
+        let Some(normalized) =
+            self.normalize_value(py, payload.bind(py), "", 1, &mut conversions)?
+        else {
+            return Ok(());
+        };
+
+        let py_conversions = PyList::empty(py);
+        for conversion in &conversions {
+            py_conversions.append(conversion.to_py_dict(py)?)?;
+        }
+        let finding = PyDict::new(py);
+        finding.set_item("transcoded", py_conversions)?;
+
+        let mut context = context.borrow_mut();
+        context.add_finding(py, Self::PLUGIN_NAME, finding.into_any().unbind())?;
+        context.payload = normalized;
+        Ok(())
+    }
+}
+
+impl GatewayPlugin for EncodingNormalizer {
+    fn name(&self) -> &'static str {
+        Self::PLUGIN_NAME
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn hooks(&self) -> &'static [Hook] {
+        Self::HOOKS
+    }
+
+    fn on_request(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.normalize_context(py, context)
+    }
+
+    fn on_response(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.normalize_context(py, context)
+    }
+
+    fn on_tool_result(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.normalize_context(py, context)
+    }
+}
+
+/// These tests need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::*;
+    use crate::encoding_normalizer::config::InvalidPolicy;
+    use crate::pii_filter::error::exceptions;
+
+    fn normalizer(on_invalid: InvalidPolicy) -> EncodingNormalizer {
+        EncodingNormalizer::from_config(NormalizerConfig {
+            on_invalid,
+            ..NormalizerConfig::default()
+        })
+    }
+
+    fn eval<'py>(py: Python<'py>, code: &std::ffi::CStr) -> Bound<'py, PyAny> {
+        py.eval(code, None, None).unwrap()
+    }
+
+    /// Normalize `payload`, returning the result and `(path, encoding)` pairs
+    fn run<'py>(
+        normalizer: &EncodingNormalizer,
+        payload: &Bound<'py, PyAny>,
+    ) -> PyResult<(Bound<'py, PyAny>, Vec<(String, String)>)> {
+        let py = payload.py();
+        let (normalized, conversions) = normalizer.normalize(py, payload)?;
+        let conversions = conversions
+            .bind(py)
+            .iter()
+            .map(|conversion| {
+                let path = conversion.get_item("path").unwrap().extract().unwrap();
+                let encoding = conversion.get_item("encoding").unwrap().extract().unwrap();
+                (path, encoding)
+            })
+            .collect();
+        Ok((normalized.into_bound(py), conversions))
+    }
+
+    fn pair(path: &str, encoding: &str) -> (String, String) {
+        (path.to_string(), encoding.to_string())
+    }
+
+    #[test]
+    fn test_normalize_nested_payload() {
+        Python::initialize();
+        Python::attach(|py| {
+            let payload = eval(
+                py,
+                c"{'body': b'caf\\xe9', 'items': [b'ok', ('x', bytearray(b'\\xff\\xfeh\\x00'))], 'n': 1}",
+            );
+            let (normalized, conversions) =
+                run(&normalizer(InvalidPolicy::Latin1), &payload).unwrap();
+            let expected = eval(
+                py,
+                c"{'body': 'caf\\xe9', 'items': ['ok', ('x', 'h')], 'n': 1}",
+            );
+            assert!(normalized.eq(&expected).unwrap());
+            // Tuples stay tuples
+            let inner = normalized.get_item("items").unwrap().get_item(1).unwrap();
+            assert!(inner.is_instance_of::<PyTuple>());
+            assert_eq!(
+                conversions,
+                vec![
+                    pair("body", "latin-1"),
+                    pair("items[0]", "utf-8"),
+                    pair("items[1][1]", "utf-16-le"),
+                ]
+            );
+            // The input is left untouched
+            assert!(payload
+                .get_item("body")
+                .unwrap()
+                .is_instance_of::<PyBytes>());
+        });
+    }
+
+    #[test]
+    fn test_clean_payload_is_returned_as_is() {
+        Python::initialize();
+        Python::attach(|py| {
+            let payload = eval(py, c"{'a': ['x', ('y', 1)], 'b': None}");
+            let (normalized, conversions) =
+                run(&normalizer(InvalidPolicy::Strict), &payload).unwrap();
+            assert!(normalized.is(&payload));
+            assert!(conversions.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_strip_bom() {
+        Python::initialize();
+        Python::attach(|py| {
+            let payload = eval(py, c"['\\ufeffhi', 'a\\ufeff']");
+            let (normalized, conversions) =
+                run(&normalizer(InvalidPolicy::Strict), &payload).unwrap();
+            assert!(normalized.eq(eval(py, c"['hi', 'a\\ufeff']")).unwrap());
+            assert_eq!(conversions, vec![pair("[0]", "utf-8-sig")]);
+
+            let keep = EncodingNormalizer::from_config(NormalizerConfig {
+                strip_bom: false,
+                ..NormalizerConfig::default()
+            });
+            let (normalized, _) = run(&keep, &payload).unwrap();
+            assert!(normalized.is(&payload));
+        });
+    }
+
+    #[test]
+    fn test_escaped_bytes_are_recovered() {
+        Python::initialize();
+        Python::attach(|py| {
+            // b'caf\xe9' decoded with `surrogateescape`
+            let payload = eval(py, c"{'body': 'caf\\udce9'}");
+            let (normalized, conversions) =
+                run(&normalizer(InvalidPolicy::Latin1), &payload).unwrap();
+            assert!(normalized.eq(eval(py, c"{'body': 'caf\\xe9'}")).unwrap());
+            assert_eq!(conversions, vec![pair("body", "latin-1")]);
+
+            let (normalized, _) = run(&normalizer(InvalidPolicy::Replace), &payload).unwrap();
+            assert!(normalized.eq(eval(py, c"{'body': 'caf\\ufffd'}")).unwrap());
+
+            let err = run(&normalizer(InvalidPolicy::Strict), &payload).unwrap_err();
+            assert!(err.is_instance_of::<exceptions::PIIError>(py));
+        });
+    }
+
+    #[test]
+    fn test_other_surrogates_are_replaced() {
+        Python::initialize();
+        Python::attach(|py| {
+            // Not produced by `surrogateescape`, so there is no byte to recover
+            let payload = eval(py, c"{'body': 'a\\ud800b\\udce9'}");
+            for policy in [InvalidPolicy::Latin1, InvalidPolicy::Replace] {
+                let (normalized, conversions) = run(&normalizer(policy), &payload).unwrap();
+                let expected = eval(py, c"{'body': 'a\\ufffdb\\ufffd'}");
+                assert!(normalized.eq(expected).unwrap(), "{:?}", policy);
+                assert_eq!(conversions, vec![pair("body", "utf-8-replace")]);
+            }
+
+            let err = run(&normalizer(InvalidPolicy::Strict), &payload).unwrap_err();
+            assert!(err.is_instance_of::<exceptions::PIIError>(py));
+            assert!(err.to_string().contains("body"));
+        });
+    }
+
+    #[test]
+    fn test_depth_is_bounded() {
+        Python::initialize();
+        // Unoptimized frames at MAX_DEPTH outgrow the default test thread stack
+        let deep = std::thread::Builder::new().stack_size(64 << 20);
+        deep.spawn(|| {
+            Python::attach(|py| {
+                // Bytes nested `depth` levels deep, counting the bytes
+                let nest = |depth: usize| {
+                    let mut value = PyBytes::new(py, b"x").into_any();
+                    for _ in 1..depth {
+                        value = PyList::new(py, [value]).unwrap().into_any();
+                    }
+                    value
+                };
+                let (_, conversions) =
+                    run(&normalizer(InvalidPolicy::Latin1), &nest(MAX_DEPTH)).unwrap();
+                assert_eq!(conversions.len(), 1);
+                let err =
+                    run(&normalizer(InvalidPolicy::Latin1), &nest(MAX_DEPTH + 1)).unwrap_err();
+                assert!(err.is_instance_of::<exceptions::PayloadLimitError>(py));
+            })
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    }
+}
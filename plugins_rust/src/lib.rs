@@ -3,13 +3,86 @@
 //
 // Rust-accelerated plugins for MCP Gateway
 // Built with PyO3 for seamless Python integration
+//
+// Subinterpreters are NOT supported. PyO3 0.27 initializes modules
+// single-phase, with process-wide type objects, and its `#[pymodule]` raises
+// ImportError when a second interpreter imports the extension. Beyond that,
+// module state is process-global, not per-interpreter: the shared result
+// cache (`pii_filter::result_cache::shared`) holds scan results of every
+// detector in the process, and the `log` bridge, the sample verifier and the
+// token counter are process-wide too. Supporting subinterpreters needs a PyO3
+// with per-interpreter module state and moving that state into it.
 
 // Allow non-local definitions for PyO3 macros (known issue with PyO3 0.20.x)
 #![allow(non_local_definitions)]
 
 use pyo3::prelude::*;
 
+pub mod classifier;
+pub mod dedupe;
+pub mod encoding_normalizer;
+pub mod payload_guard;
 pub mod pii_filter;
+pub mod plugin;
+pub mod policy_router;
+
+use pii_filter::error::exceptions;
+use plugin::{GatewayPlugin, PluginRegistration};
+
+/// Registry of Rust plugins, in the order they are listed to Python
+pub static PLUGINS: &[PluginRegistration] = &[
+    PluginRegistration {
+        name: pii_filter::PIIDetectorRust::PLUGIN_NAME,
+        version: env!("CARGO_PKG_VERSION"),
+        hooks: pii_filter::PIIDetectorRust::HOOKS,
+        build: |config| {
+            Ok(Box::new(pii_filter::PIIDetectorRust::new(config)?) as Box<dyn GatewayPlugin>)
+        },
+    },
+    PluginRegistration {
+        name: payload_guard::PayloadGuard::PLUGIN_NAME,
+        version: env!("CARGO_PKG_VERSION"),
+        hooks: payload_guard::PayloadGuard::HOOKS,
+        build: |config| {
+            Ok(Box::new(payload_guard::PayloadGuard::new(Some(config))?) as Box<dyn GatewayPlugin>)
+        },
+    },
+    PluginRegistration {
+        name: encoding_normalizer::EncodingNormalizer::PLUGIN_NAME,
+        version: env!("CARGO_PKG_VERSION"),
+        hooks: encoding_normalizer::EncodingNormalizer::HOOKS,
+        build: |config| {
+            Ok(
+                Box::new(encoding_normalizer::EncodingNormalizer::new(Some(config))?)
+                    as Box<dyn GatewayPlugin>,
+            )
+        },
+    },
+    PluginRegistration {
+        name: dedupe::DedupeCache::PLUGIN_NAME,
+        version: env!("CARGO_PKG_VERSION"),
+        hooks: dedupe::DedupeCache::HOOKS,
+        build: |config| {
+            Ok(Box::new(dedupe::DedupeCache::new(Some(config))?) as Box<dyn GatewayPlugin>)
+        },
+    },
+    PluginRegistration {
+        name: classifier::Classifier::PLUGIN_NAME,
+        version: env!("CARGO_PKG_VERSION"),
+        hooks: classifier::Classifier::HOOKS,
+        build: |config| {
+            Ok(Box::new(classifier::Classifier::new(Some(config))?) as Box<dyn GatewayPlugin>)
+        },
+    },
+    PluginRegistration {
+        name: policy_router::PolicyRouter::PLUGIN_NAME,
+        version: env!("CARGO_PKG_VERSION"),
+        hooks: policy_router::PolicyRouter::HOOKS,
+        build: |config| {
+            Ok(Box::new(policy_router::PolicyRouter::new(config)?) as Box<dyn GatewayPlugin>)
+        },
+    },
+];
 
 /// Python module: plugins_rust
 ///
@@ -42,6 +115,92 @@ pub mod pii_filter;
 fn plugins_rust(m: &Bound<'_, pyo3::types::PyModule>) -> PyResult<()> {
     // Export PII Filter Rust implementation
     m.add_class::<pii_filter::PIIDetectorRust>()?;
+    #[cfg(feature = "arrow")]
+    m.add_class::<pii_filter::arrow_batch::ArrowBatch>()?;
+    m.add_function(wrap_pyfunction!(pii_filter::benchmark::benchmark, m)?)?;
+    #[cfg(feature = "yaml")]
+    m.add_function(wrap_pyfunction!(pii_filter::corpus::verify_corpus, m)?)?;
+    #[cfg(feature = "differential")]
+    m.add_function(wrap_pyfunction!(
+        pii_filter::differential::differential_report,
+        m
+    )?)?;
+    #[cfg(feature = "yaml")]
+    m.add_function(wrap_pyfunction!(
+        pii_filter::rule_import::load_presidio_recognizers,
+        m
+    )?)?;
+    #[cfg(feature = "toml")]
+    m.add_function(wrap_pyfunction!(
+        pii_filter::rule_import::load_gitleaks_rules,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(pii_filter::samples::generate_samples, m)?)?;
+    #[cfg(feature = "ed25519")]
+    m.add_function(wrap_pyfunction!(
+        pii_filter::pattern_pack::load_pattern_pack,
+        m
+    )?)?;
+    #[cfg(feature = "ed25519")]
+    m.add_function(wrap_pyfunction!(
+        pii_filter::pattern_pack::load_signed_config,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(pii_filter::simulation::simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        pii_filter::result_cache::configure_result_cache,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        pii_filter::result_cache::result_cache_stats,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(pii_filter::log_bridge::init_logging, m)?)?;
+    pii_filter::log_bridge::install(m.py(), pii_filter::log_bridge::DEFAULT_RATE)?;
+    m.add_class::<pii_filter::masker::Masker>()?;
+    #[cfg(feature = "pattern_packs")]
+    m.add_class::<pii_filter::pack_fetcher::PatternPackFetcher>()?;
+    m.add_class::<pii_filter::cancel::CancelToken>()?;
+    m.add_class::<pii_filter::pool::DetectorPool>()?;
+    m.add_class::<pii_filter::shadow::ShadowDetector>()?;
+    m.add_function(wrap_pyfunction!(pii_filter::masker::make_masker, m)?)?;
+    m.add_function(wrap_pyfunction!(plugin::list_plugins, m)?)?;
+    m.add_class::<plugin::HookContext>()?;
+    m.add_class::<plugin::PluginPipeline>()?;
+    m.add_class::<payload_guard::PayloadGuard>()?;
+    m.add_class::<encoding_normalizer::EncodingNormalizer>()?;
+    m.add_class::<dedupe::DedupeCache>()?;
+    m.add_class::<classifier::Classifier>()?;
+    m.add_class::<policy_router::PolicyRouter>()?;
+
+    // Exception hierarchy
+    let py = m.py();
+    m.add("PIIError", py.get_type::<exceptions::PIIError>())?;
+    m.add("ConfigError", py.get_type::<exceptions::ConfigError>())?;
+    m.add(
+        "PatternCompileError",
+        py.get_type::<exceptions::PatternCompileError>(),
+    )?;
+    m.add(
+        "SignatureError",
+        py.get_type::<exceptions::SignatureError>(),
+    )?;
+    m.add(
+        "BlockedContentError",
+        py.get_type::<exceptions::BlockedContentError>(),
+    )?;
+    m.add(
+        "PayloadLimitError",
+        py.get_type::<exceptions::PayloadLimitError>(),
+    )?;
+    m.add(
+        "PoolOverloadedError",
+        py.get_type::<exceptions::PoolOverloadedError>(),
+    )?;
+    m.add(
+        "ScanCancelledError",
+        py.get_type::<exceptions::ScanCancelledError>(),
+    )?;
 
     // Module metadata
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
@@ -0,0 +1,146 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Configuration for the payload guard plugin
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::str::FromStr;
+
+use crate::pii_filter::config::MAX_DEPTH;
+use crate::pii_filter::error::{PIIError, PIIResult};
+
+/// What to do with a payload that exceeds a limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LimitAction {
+    /// Cut oversized strings/arrays and drop over-deep containers
+    #[default]
+    Truncate,
+    /// Raise `PayloadLimitError` on the first violation
+    Reject,
+}
+
+impl FromStr for LimitAction {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "truncate" => Ok(LimitAction::Truncate),
+            "reject" => Ok(LimitAction::Reject),
+            _ => Err(PIIError::InvalidValue {
+                field: "action",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Limits enforced by the payload guard (0 disables a limit, except that
+/// nesting never goes past `MAX_DEPTH`)
+#[derive(Debug, Clone)]
+pub struct GuardConfig {
+    /// Approximate JSON-encoded size of the whole payload, in bytes. Always
+    /// rejects: there is no meaningful way to truncate a payload as a whole
+    pub max_payload_bytes: usize,
+    /// Characters per string value
+    pub max_string_length: usize,
+    /// Items per list
+    pub max_array_length: usize,
+    /// Nesting depth of dicts/lists (the top-level container is depth 1),
+    /// at most `MAX_DEPTH`
+    pub max_depth: usize,
+    pub action: LimitAction,
+    /// Appended to truncated strings
+    pub truncation_marker: String,
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        Self {
+            max_payload_bytes: 10 * 1024 * 1024,
+            max_string_length: 1024 * 1024,
+            max_array_length: 10_000,
+            max_depth: 64,
+            action: LimitAction::Truncate,
+            truncation_marker: "...[truncated]".to_string(),
+        }
+    }
+}
+
+impl GuardConfig {
+    /// Extract configuration from a Python dict
+    pub fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut config = Self::default();
+
+        macro_rules! extract_limit {
+            ($field:ident) => {
+                if let Some(value) = dict.get_item(stringify!($field))? {
+                    config.$field = value.extract()?;
+                }
+            };
+        }
+
+        extract_limit!(max_payload_bytes);
+        extract_limit!(max_string_length);
+        extract_limit!(max_array_length);
+        extract_limit!(max_depth);
+
+        if let Some(value) = dict.get_item("action")? {
+            config.action = value.extract::<String>()?.parse()?;
+        }
+        if let Some(value) = dict.get_item("truncation_marker")? {
+            config.truncation_marker = value.extract()?;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> PIIResult<()> {
+        if self.max_depth > MAX_DEPTH {
+            return Err(PIIError::InvalidValue {
+                field: "max_depth",
+                value: self.max_depth.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Deepest nesting allowed: `max_depth`, or `MAX_DEPTH` when disabled
+    pub fn depth_limit(&self) -> usize {
+        match self.max_depth {
+            0 => MAX_DEPTH,
+            max => max,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_action_from_str() {
+        assert_eq!(
+            "reject".parse::<LimitAction>().unwrap(),
+            LimitAction::Reject
+        );
+        assert!(matches!(
+            "drop".parse::<LimitAction>(),
+            Err(PIIError::InvalidValue {
+                field: "action",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_depth_is_always_bounded() {
+        let mut config = GuardConfig::default();
+        assert_eq!(config.depth_limit(), 64);
+        config.max_depth = 0;
+        assert_eq!(config.depth_limit(), MAX_DEPTH);
+        config.max_depth = MAX_DEPTH + 1;
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,571 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Payload size limits enforced over nested Python data
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString, PyTuple};
+
+use super::config::{GuardConfig, LimitAction};
+use crate::pii_filter::error::exceptions::PayloadLimitError;
+use crate::plugin::{GatewayPlugin, Hook, HookContext};
+
+/// A single exceeded limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Name of the config limit, e.g. "max_string_length"
+    pub limit: &'static str,
+    /// Path of the offending value ("" for the whole payload)
+    pub path: String,
+    pub max: usize,
+    pub actual: usize,
+}
+
+impl Violation {
+    pub fn message(&self) -> String {
+        let location = if self.path.is_empty() {
+            "payload".to_string()
+        } else {
+            format!("'{}'", self.path)
+        };
+        format!(
+            "{} exceeded at {}: {} > {}",
+            self.limit, location, self.actual, self.max
+        )
+    }
+
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("limit", self.limit)?;
+        py_dict.set_item("path", &self.path)?;
+        py_dict.set_item("max", self.max)?;
+        py_dict.set_item("actual", self.actual)?;
+        Ok(py_dict)
+    }
+
+    /// `PayloadLimitError(message, details)` for this violation
+    pub fn to_err(&self, py: Python) -> PyResult<PyErr> {
+        Ok(PayloadLimitError::new_err((
+            format!("Payload rejected: {}", self.message()),
+            self.to_py_dict(py)?.unbind(),
+        )))
+    }
+}
+
+/// First `max` characters of `text`
+pub fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
+
+/// Payload guard exposed to Python and registered as a gateway plugin
+///
+/// # Example (Python)
+/// ```python
+/// from plugins_rust import PayloadGuard
+///
+/// guard = PayloadGuard({"max_string_length": 10_000, "max_depth": 32, "action": "truncate"})
+/// payload, violations = guard.check(payload)
+/// ```
+#[pyclass(name = "PayloadGuard", module = "plugins_rust", frozen)]
+pub struct PayloadGuard {
+    config: GuardConfig,
+}
+
+#[pymethods]
+impl PayloadGuard {
+    /// Create a payload guard
+    ///
+    /// # Arguments
+    /// * `config` - Optional dict; limits of 0 are disabled:
+    ///   * `max_payload_bytes` (int): Approximate JSON size of the whole payload (default: 10 MiB, always rejects)
+    ///   * `max_string_length` (int): Characters per string value (default: 1 Mi)
+    ///   * `max_array_length` (int): Items per list (default: 10000)
+    ///   * `max_depth` (int): Nesting depth of dicts/lists, at most 512; 0 still
+    ///     stops at 512 (default: 64)
+    ///   * `action` (str): "truncate" (default) or "reject"
+    ///   * `truncation_marker` (str): Appended to truncated strings (default: "...[truncated]")
+    #[new]
+    #[pyo3(signature = (config=None))]
+    pub fn new(config: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = match config {
+            Some(dict) => GuardConfig::from_py_dict(dict)?,
+            None => GuardConfig::default(),
+        };
+        Ok(Self::from_config(config))
+    }
+
+    /// Enforce the limits on `payload`
+    ///
+    /// # Returns
+    /// `(payload, violations)`: the payload, truncated where limits were
+    /// exceeded, and a list of `{"limit", "path", "max", "actual"}` dicts.
+    /// Raises `PayloadLimitError` (with the same dict as its second argument)
+    /// in "reject" mode or when `max_payload_bytes` is exceeded.
+    pub fn check(
+        &self,
+        py: Python,
+        payload: &Bound<'_, PyAny>,
+    ) -> PyResult<(Py<PyAny>, Py<PyList>)> {
+        let (payload, violations) = self.enforce(py, payload)?;
+        let py_violations = PyList::empty(py);
+        for violation in &violations {
+            py_violations.append(violation.to_py_dict(py)?)?;
+        }
+        Ok((payload, py_violations.unbind()))
+    }
+
+    /// Run the guard as a gateway plugin on a shared `HookContext`
+    pub fn process(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        GatewayPlugin::process(self, py, context)
+    }
+}
+
+impl PayloadGuard {
+    pub const PLUGIN_NAME: &'static str = "payload_guard";
+    pub const HOOKS: &'static [Hook] = &[Hook::OnRequest, Hook::OnResponse, Hook::OnToolResult];
+
+    pub fn from_config(config: GuardConfig) -> Self {
+        Self { config }
+    }
+
+    /// Enforce all limits, returning the guarded payload and the violations
+    /// that were truncated
+    pub fn enforce(
+        &self,
+        py: Python,
+        payload: &Bound<'_, PyAny>,
+    ) -> PyResult<(Py<PyAny>, Vec<Violation>)> {
+        let mut violations = Vec::new();
+        let (payload, size) = self.guard_value(py, payload, "", 1, &mut violations)?;
+
+        let max = self.config.max_payload_bytes;
+        if max > 0 && size > max {
+            let violation = Violation {
+                limit: "max_payload_bytes",
+                path: String::new(),
+                max,
+                actual: size,
+            };
+            return Err(violation.to_err(py)?);
+        }
+        Ok((payload, violations))
+    }
+
+    /// Record a violation, or fail with it in reject mode
+    fn violate(
+        &self,
+        py: Python,
+        violation: Violation,
+        violations: &mut Vec<Violation>,
+    ) -> PyResult<()> {
+        match self.config.action {
+            LimitAction::Reject => Err(violation.to_err(py)?),
+            LimitAction::Truncate => {
+                violations.push(violation);
+                Ok(())
+            }
+        }
+    }
+
+    /// Guard one value, returning it (truncated if needed) and its approximate
+    /// JSON-encoded size
+    fn guard_value(
+        &self,
+        py: Python,
+        value: &Bound<'_, PyAny>,
+        path: &str,
+        depth: usize,
+        violations: &mut Vec<Violation>,
+    ) -> PyResult<(Py<PyAny>, usize)> {
+        // Strings
+        if let Ok(py_str) = value.cast::<PyString>() {
+            let text = py_str.to_cow()?;
+            let max = self.config.max_string_length;
+            if max > 0 && text.len() > max {
+                let chars = text.chars().count();
+                if chars > max {
+                    let violation = Violation {
+                        limit: "max_string_length",
+                        path: path.to_string(),
+                        max,
+                        actual: chars,
+                    };
+                    self.violate(py, violation, violations)?;
+                    let truncated = format!(
+                        "{}{}",
+                        truncate_chars(&text, max),
+                        self.config.truncation_marker
+                    );
+                    let size = truncated.len() + 2;
+                    return Ok((PyString::new(py, &truncated).into_any().unbind(), size));
+                }
+            }
+            return Ok((value.clone().unbind(), text.len() + 2));
+        }
+
+        let is_container = value.is_instance_of::<PyDict>()
+            || value.is_instance_of::<PyList>()
+            || value.is_instance_of::<PyTuple>();
+        if !is_container {
+            // Scalars: numbers, booleans, None
+            return Ok((value.clone().unbind(), value.str()?.len()?));
+        }
+
+        let max_depth = self.config.depth_limit();
+        if depth > max_depth {
+            let violation = Violation {
+                limit: "max_depth",
+                path: path.to_string(),
+                max: max_depth,
+                actual: depth,
+            };
+            self.violate(py, violation, violations)?;
+            return Ok((py.None(), 4));
+        }
+
+        // Dicts
+        if let Ok(dict) = value.cast::<PyDict>() {
+            let new_dict = PyDict::new(py);
+            let mut size = 2;
+            for (key, item) in dict.iter() {
+                let key_str = key.str()?;
+                let key_str = key_str.to_cow()?;
+                let item_path = if path.is_empty() {
+                    key_str.to_string()
+                } else {
+                    format!("{}.{}", path, key_str)
+                };
+                let (new_item, item_size) =
+                    self.guard_value(py, &item, &item_path, depth + 1, violations)?;
+                size += key_str.len() + 4 + item_size;
+                new_dict.set_item(key, new_item)?;
+            }
+            return Ok((new_dict.into_any().unbind(), size));
+        }
+
+        // Lists and tuples
+        let items: Vec<Bound<'_, PyAny>> = value.try_iter()?.collect::<PyResult<_>>()?;
+        let max = self.config.max_array_length;
+        let keep = if max > 0 && items.len() > max {
+            let violation = Violation {
+                limit: "max_array_length",
+                path: path.to_string(),
+                max,
+                actual: items.len(),
+            };
+            self.violate(py, violation, violations)?;
+            max
+        } else {
+            items.len()
+        };
+
+        let new_list = PyList::empty(py);
+        let mut size = 2;
+        for (idx, item) in items.iter().take(keep).enumerate() {
+            let item_path = format!("{}[{}]", path, idx);
+            let (new_item, item_size) =
+                self.guard_value(py, item, &item_path, depth + 1, violations)?;
+            size += item_size + 1;
+            new_list.append(new_item)?;
+        }
+        Ok((new_list.into_any().unbind(), size))
+    }
+
+    fn guard_context(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        let payload = context.borrow().payload.clone_ref(py);
+        let (guarded, violations) = self.enforce(py, payload.bind(py))?;
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let py_violations = PyList::empty(py);
+        for violation in &violations {
+            py_violations.append(violation.to_py_dict(py)?)?;
+        }
+        let finding = PyDict::new(py);
+        finding.set_item("truncated", py_violations)?;
+
+        let mut context = context.borrow_mut();
+        context.add_finding(py, Self::PLUGIN_NAME, finding.into_any().unbind())?;
+        context.payload = guarded;
+        Ok(())
+    }
+}
+
+impl GatewayPlugin for PayloadGuard {
+    fn name(&self) -> &'static str {
+        Self::PLUGIN_NAME
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn hooks(&self) -> &'static [Hook] {
+        Self::HOOKS
+    }
+
+    fn on_request(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.guard_context(py, context)
+    }
+
+    fn on_response(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.guard_context(py, context)
+    }
+
+    fn on_tool_result(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.guard_context(py, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("hello", 3), "hel");
+        assert_eq!(truncate_chars("héllo", 2), "hé");
+        assert_eq!(truncate_chars("hi", 5), "hi");
+    }
+
+    #[test]
+    fn test_violation_message() {
+        let violation = Violation {
+            limit: "max_array_length",
+            path: "items".to_string(),
+            max: 10,
+            actual: 12,
+        };
+        assert_eq!(
+            violation.message(),
+            "max_array_length exceeded at 'items': 12 > 10"
+        );
+    }
+}
+
+/// These tests need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::*;
+    use crate::pii_filter::config::MAX_DEPTH;
+
+    fn guard(action: LimitAction, config: GuardConfig) -> PayloadGuard {
+        PayloadGuard::from_config(GuardConfig { action, ..config })
+    }
+
+    fn limits(max_string_length: usize, max_array_length: usize) -> GuardConfig {
+        GuardConfig {
+            max_string_length,
+            max_array_length,
+            ..GuardConfig::default()
+        }
+    }
+
+    fn eval<'py>(py: Python<'py>, code: &std::ffi::CStr) -> Bound<'py, PyAny> {
+        py.eval(code, None, None).unwrap()
+    }
+
+    /// `(limit, path, max, actual)` from the dict a `PayloadLimitError` carries
+    fn rejected(py: Python, err: PyErr) -> (String, String, usize, usize) {
+        assert!(err.is_instance_of::<PayloadLimitError>(py));
+        let details = err.value(py).getattr("args").unwrap().get_item(1).unwrap();
+        let field = |name: &str| details.get_item(name).unwrap();
+        (
+            field("limit").extract().unwrap(),
+            field("path").extract().unwrap(),
+            field("max").extract().unwrap(),
+            field("actual").extract().unwrap(),
+        )
+    }
+
+    /// A list nested `depth` levels deep, counting the outermost list
+    fn nest<'py>(py: Python<'py>, depth: usize) -> Bound<'py, PyAny> {
+        let mut value = PyList::empty(py).into_any();
+        for _ in 1..depth {
+            value = PyList::new(py, [value]).unwrap().into_any();
+        }
+        value
+    }
+
+    #[test]
+    fn test_long_string() {
+        Python::initialize();
+        Python::attach(|py| {
+            let payload = eval(py, c"{'a': {'b': 'h\\xe9llo world'}, 'c': 'ok'}");
+
+            let truncate = guard(LimitAction::Truncate, limits(5, 0));
+            let (guarded, violations) = truncate.enforce(py, &payload).unwrap();
+            let expected = eval(py, c"{'a': {'b': 'h\\xe9llo...[truncated]'}, 'c': 'ok'}");
+            assert!(guarded.bind(py).eq(expected).unwrap());
+            assert_eq!(
+                violations,
+                vec![Violation {
+                    limit: "max_string_length",
+                    path: "a.b".to_string(),
+                    max: 5,
+                    actual: 11,
+                }]
+            );
+
+            // Byte length alone does not trip the limit
+            let (_, violations) = truncate
+                .enforce(py, &eval(py, c"'\\xe9\\xe9\\xe9'"))
+                .unwrap();
+            assert!(violations.is_empty());
+
+            let reject = guard(LimitAction::Reject, limits(5, 0));
+            let err = reject.enforce(py, &payload).unwrap_err();
+            assert_eq!(
+                rejected(py, err),
+                ("max_string_length".to_string(), "a.b".to_string(), 5, 11)
+            );
+        });
+    }
+
+    #[test]
+    fn test_long_array() {
+        Python::initialize();
+        Python::attach(|py| {
+            let payload = eval(py, c"{'items': [1, 2, 3, [4, 5, 6, 7]]}");
+
+            let truncate = guard(LimitAction::Truncate, limits(0, 3));
+            let (guarded, violations) = truncate.enforce(py, &payload).unwrap();
+            assert!(guarded
+                .bind(py)
+                .eq(eval(py, c"{'items': [1, 2, 3]}"))
+                .unwrap());
+            assert_eq!(violations.len(), 1);
+            assert_eq!(violations[0].path, "items");
+            assert_eq!(violations[0].actual, 4);
+
+            // Kept items are still guarded
+            let payload = eval(py, c"[[1, 2, 3, 4]]");
+            let (guarded, violations) = truncate.enforce(py, &payload).unwrap();
+            assert!(guarded.bind(py).eq(eval(py, c"[[1, 2, 3]]")).unwrap());
+            assert_eq!(violations[0].path, "[0]");
+
+            let reject = guard(LimitAction::Reject, limits(0, 3));
+            let err = reject.enforce(py, &payload).unwrap_err();
+            assert_eq!(
+                rejected(py, err),
+                ("max_array_length".to_string(), "[0]".to_string(), 3, 4)
+            );
+        });
+    }
+
+    #[test]
+    fn test_max_payload_bytes() {
+        Python::initialize();
+        Python::attach(|py| {
+            // {"a": "xxxx"} is 13 bytes as JSON
+            let payload = eval(py, c"{'a': 'xxxx'}");
+            let config = |max_payload_bytes| GuardConfig {
+                max_payload_bytes,
+                ..GuardConfig::default()
+            };
+            assert!(guard(LimitAction::Truncate, config(13))
+                .enforce(py, &payload)
+                .is_ok());
+
+            // The whole payload always rejects, even in truncate mode
+            for action in [LimitAction::Truncate, LimitAction::Reject] {
+                let err = guard(action, config(12)).enforce(py, &payload).unwrap_err();
+                assert_eq!(
+                    rejected(py, err),
+                    ("max_payload_bytes".to_string(), String::new(), 12, 13)
+                );
+            }
+
+            // The size is measured after truncation
+            let truncated = GuardConfig {
+                max_string_length: 1,
+                truncation_marker: String::new(),
+                ..config(12)
+            };
+            assert!(guard(LimitAction::Truncate, truncated)
+                .enforce(py, &payload)
+                .is_ok());
+        });
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        Python::initialize();
+        Python::attach(|py| {
+            let config = || GuardConfig {
+                max_depth: 3,
+                ..GuardConfig::default()
+            };
+            let payload = eval(py, c"{'a': [{'b': [1]}, 2], 'c': [3]}");
+
+            let (guarded, violations) = guard(LimitAction::Truncate, config())
+                .enforce(py, &payload)
+                .unwrap();
+            assert!(guarded
+                .bind(py)
+                .eq(eval(py, c"{'a': [{'b': None}, 2], 'c': [3]}"))
+                .unwrap());
+            assert_eq!(
+                violations,
+                vec![Violation {
+                    limit: "max_depth",
+                    path: "a[0].b".to_string(),
+                    max: 3,
+                    actual: 4,
+                }]
+            );
+
+            let err = guard(LimitAction::Reject, config())
+                .enforce(py, &payload)
+                .unwrap_err();
+            assert_eq!(
+                rejected(py, err),
+                ("max_depth".to_string(), "a[0].b".to_string(), 3, 4)
+            );
+        });
+    }
+
+    #[test]
+    fn test_depth_is_capped() {
+        Python::initialize();
+        // Unoptimized frames at MAX_DEPTH outgrow the default test thread stack
+        let deep = std::thread::Builder::new().stack_size(64 << 20);
+        deep.spawn(|| {
+            Python::attach(|py| {
+                // A disabled limit still stops at MAX_DEPTH
+                let config = || GuardConfig {
+                    max_depth: 0,
+                    ..GuardConfig::default()
+                };
+                let truncate = guard(LimitAction::Truncate, config());
+                let (_, violations) = truncate.enforce(py, &nest(py, MAX_DEPTH)).unwrap();
+                assert!(violations.is_empty());
+                let (_, violations) = truncate.enforce(py, &nest(py, MAX_DEPTH + 1)).unwrap();
+                assert_eq!(violations.len(), 1);
+                assert_eq!(violations[0].max, MAX_DEPTH);
+                assert_eq!(violations[0].actual, MAX_DEPTH + 1);
+
+                let err = guard(LimitAction::Reject, config())
+                    .enforce(py, &nest(py, MAX_DEPTH + 1))
+                    .unwrap_err();
+                let (limit, _, max, _) = rejected(py, err);
+                assert_eq!((limit.as_str(), max), ("max_depth", MAX_DEPTH));
+
+                // A list that contains itself is cut off rather than recursed
+                let looped = PyList::empty(py);
+                looped.append(&looped).unwrap();
+                let (_, violations) = truncate.enforce(py, &looped).unwrap();
+                assert_eq!(violations[0].limit, "max_depth");
+                looped.call_method0("clear").unwrap();
+            })
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    }
+}
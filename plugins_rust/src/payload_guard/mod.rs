@@ -0,0 +1,12 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Payload Guard Plugin - Rust Implementation
+//
+// Enforces size limits on payloads (total size, string length, array length,
+// nesting depth) before expensive plugins such as the PII filter run.
+
+pub mod config;
+pub mod guard;
+
+pub use guard::PayloadGuard;
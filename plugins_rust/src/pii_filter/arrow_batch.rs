@@ -0,0 +1,275 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Arrow record batch scanning for PII Filter
+//
+// Batches cross the Python boundary through the Arrow PyCapsule interface
+// (`__arrow_c_array__`), so any producer implementing it (pyarrow, Polars via
+// `to_arrow()`, ...) works without linking against pyarrow.
+
+use arrow_array::cast::AsArray;
+use arrow_array::ffi::{from_ffi, to_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use arrow_array::{
+    Array, ArrayRef, LargeStringArray, RecordBatch, StringArray, StringViewArray, StructArray,
+    UInt32Array,
+};
+use arrow_schema::{DataType, Field, Schema};
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyCapsule, PyTuple};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::config::PIIType;
+use super::detector::Detector;
+use super::error::{PIIError, PIIResult};
+use super::masking::MaskMemo;
+
+/// Mask a string column of `batch` and append per-row detection counts
+///
+/// The returned batch has `column` replaced by its masked text (same string
+/// type, nulls preserved), followed by `<column>_pii_count` and one
+/// `<column>_<type>_count` column per PII type the detector can report.
+pub fn scan_record_batch(
+    detector: &Detector,
+    batch: &RecordBatch,
+    column: &str,
+) -> PIIResult<RecordBatch> {
+    let schema = batch.schema();
+    let idx = schema
+        .index_of(column)
+        .map_err(|_| PIIError::InvalidBatch(format!("No column named '{}'", column)))?;
+    let array = batch.column(idx);
+    let texts: Vec<Option<&str>> = match array.data_type() {
+        DataType::Utf8 => array.as_string::<i32>().iter().collect(),
+        DataType::LargeUtf8 => array.as_string::<i64>().iter().collect(),
+        DataType::Utf8View => array.as_string_view().iter().collect(),
+        other => {
+            return Err(PIIError::InvalidBatch(format!(
+                "Column '{}' has type {}, expected a string column",
+                column, other
+            )))
+        }
+    };
+
+    let types: Vec<PIIType> = detector.enabled_types().into_iter().collect();
+    let mut type_counts = vec![Vec::with_capacity(texts.len()); types.len()];
+    let mut totals = Vec::with_capacity(texts.len());
+    let mut masked = Vec::with_capacity(texts.len());
+    let mut memo = MaskMemo::new();
+
+    for text in texts {
+        let (value, detections) = match text {
+            Some(text) => {
+                let (value, detections) = detector.detect_and_mask(text, &mut memo);
+                (Some(value.into_owned()), detections)
+            }
+            None => (None, BTreeMap::new()),
+        };
+
+        for (counts, pii_type) in type_counts.iter_mut().zip(&types) {
+            counts.push(
+                detections
+                    .get(pii_type)
+                    .map_or(0, |items| items.len() as u32),
+            );
+        }
+        totals.push(detections.values().map(Vec::len).sum::<usize>() as u32);
+        masked.push(value);
+    }
+
+    let masked: ArrayRef = match array.data_type() {
+        DataType::Utf8 => Arc::new(StringArray::from(masked)),
+        DataType::LargeUtf8 => Arc::new(LargeStringArray::from(masked)),
+        _ => Arc::new(StringViewArray::from(masked)),
+    };
+
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    let mut columns = batch.columns().to_vec();
+    columns[idx] = masked;
+
+    fields.push(Field::new(
+        format!("{}_pii_count", column),
+        DataType::UInt32,
+        false,
+    ));
+    columns.push(Arc::new(UInt32Array::from(totals)));
+    for (pii_type, counts) in types.iter().zip(type_counts) {
+        fields.push(Field::new(
+            format!("{}_{}_count", column, pii_type.as_str()),
+            DataType::UInt32,
+            false,
+        ));
+        columns.push(Arc::new(UInt32Array::from(counts)));
+    }
+
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|e| PIIError::InvalidBatch(e.to_string()))
+}
+
+/// Import a record batch from any object implementing `__arrow_c_array__`
+pub fn import_batch(obj: &Bound<'_, PyAny>) -> PyResult<RecordBatch> {
+    if !obj.hasattr("__arrow_c_array__")? {
+        return Err(PyTypeError::new_err(
+            "Expected an Arrow record batch implementing __arrow_c_array__ (e.g. pyarrow.RecordBatch)",
+        ));
+    }
+    let (schema, array): (Bound<'_, PyCapsule>, Bound<'_, PyCapsule>) =
+        obj.call_method0("__arrow_c_array__")?.extract()?;
+    let schema = schema
+        .pointer_checked(Some(c"arrow_schema"))?
+        .cast::<FFI_ArrowSchema>();
+    let array = array
+        .pointer_checked(Some(c"arrow_array"))?
+        .cast::<FFI_ArrowArray>();
+
+    // SAFETY: the capsule names guarantee Arrow C data interface structs.
+    // `from_raw` moves the array out and marks the producer's copy released,
+    // so its capsule destructor will not free the buffers a second time.
+    let data = unsafe {
+        let array = FFI_ArrowArray::from_raw(array.as_ptr());
+        from_ffi(array, schema.as_ref())
+    }
+    .map_err(|e| PIIError::InvalidBatch(e.to_string()))?;
+
+    if !matches!(data.data_type(), DataType::Struct(_)) {
+        return Err(PIIError::InvalidBatch(format!(
+            "Expected a record batch (struct array), got {}",
+            data.data_type()
+        ))
+        .into());
+    }
+    Ok(RecordBatch::from(StructArray::from(data)))
+}
+
+/// Record batch returned to Python
+///
+/// Implements the Arrow PyCapsule interface, so it converts without copying:
+/// ```python
+/// import pyarrow as pa
+///
+/// masked = pa.record_batch(detector.scan_arrow(batch, "notes"))
+/// ```
+#[pyclass(name = "ArrowBatch", module = "plugins_rust", frozen)]
+pub struct ArrowBatch {
+    batch: RecordBatch,
+}
+
+impl ArrowBatch {
+    pub fn new(batch: RecordBatch) -> Self {
+        Self { batch }
+    }
+}
+
+#[pymethods]
+impl ArrowBatch {
+    /// Export as (schema, array) capsules; `requested_schema` is not supported
+    /// and the batch is always exported with its own schema
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyTuple>> {
+        let _ = requested_schema;
+        let data = StructArray::from(self.batch.clone()).into_data();
+        let (array, schema) = to_ffi(&data).map_err(|e| PIIError::InvalidBatch(e.to_string()))?;
+
+        let schema = PyCapsule::new(py, schema, Some(c"arrow_schema".to_owned()))?;
+        let array = PyCapsule::new(py, array, Some(c"arrow_array".to_owned()))?;
+        PyTuple::new(py, [schema, array])
+    }
+
+    /// Export the schema as an `arrow_schema` capsule
+    fn __arrow_c_schema__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+        let schema = FFI_ArrowSchema::try_from(self.batch.schema().as_ref())
+            .map_err(|e| PIIError::InvalidBatch(e.to_string()))?;
+        PyCapsule::new(py, schema, Some(c"arrow_schema".to_owned()))
+    }
+
+    #[getter]
+    fn num_rows(&self) -> usize {
+        self.batch.num_rows()
+    }
+
+    #[getter]
+    fn column_names(&self) -> Vec<String> {
+        self.batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.batch.num_rows()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::PIIConfig;
+    use arrow_array::Int64Array;
+
+    fn detector() -> Detector {
+        Detector::from_config(PIIConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_scan_record_batch() {
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "notes",
+                Arc::new(StringArray::from(vec![
+                    Some("SSN 123-45-6789, mail a@example.com"),
+                    None,
+                    Some("nothing here"),
+                ])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let scanned = scan_record_batch(&detector(), &batch, "notes").unwrap();
+        let notes = scanned.column_by_name("notes").unwrap().as_string::<i32>();
+        assert!(!notes.value(0).contains("123-45-6789"));
+        assert!(notes.is_null(1));
+        assert_eq!(notes.value(2), "nothing here");
+
+        let totals = scanned
+            .column_by_name("notes_pii_count")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        assert_eq!(totals.values(), &[2, 0, 0]);
+
+        let ssn = scanned
+            .column_by_name("notes_ssn_count")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        assert_eq!(ssn.values(), &[1, 0, 0]);
+        assert_eq!(scanned.schema().field(0).name(), "id");
+    }
+
+    #[test]
+    fn test_scan_record_batch_rejects_non_string_column() {
+        let batch =
+            RecordBatch::try_from_iter([("id", Arc::new(Int64Array::from(vec![1])) as ArrayRef)])
+                .unwrap();
+
+        assert!(matches!(
+            scan_record_batch(&detector(), &batch, "id"),
+            Err(PIIError::InvalidBatch(_))
+        ));
+        assert!(matches!(
+            scan_record_batch(&detector(), &batch, "missing"),
+            Err(PIIError::InvalidBatch(_))
+        ));
+    }
+}
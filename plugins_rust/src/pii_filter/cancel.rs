@@ -0,0 +1,178 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Cooperative cancellation of long scans
+//
+// Scans check for cancellation between patterns, scan windows and payload
+// nodes: a `CancelToken` set from another thread, or a pending signal such as
+// Ctrl-C (`KeyboardInterrupt`) on the calling thread. A cancelled call stops
+// at the next check and raises instead of returning partial results.
+
+use pyo3::prelude::*;
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use super::error::PIIError;
+
+/// How often the calling thread polls Python for pending signals
+pub const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Flag that cancels the scans it is passed to
+///
+/// ```python
+/// from plugins_rust import CancelToken
+///
+/// token = CancelToken()
+/// future = executor.submit(detector.process_json, payload, cancel=token)
+/// ...
+/// token.cancel()  # the scan raises ScanCancelledError at its next check
+/// ```
+///
+/// A token stays cancelled; create a new one for the next call.
+#[pyclass(name = "CancelToken", module = "plugins_rust", frozen)]
+#[derive(Debug, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl CancelToken {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every scan holding this token to stop
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    #[getter]
+    pub fn cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+/// Exception raised by a signal handler
+///
+/// Held type-erased (it is a `PyErr`) and polled through a function pointer,
+/// so code that never enables signal polling, like the unit tests, doesn't
+/// link against libpython.
+type Interrupt = Box<dyn Any + Send>;
+
+/// Polls for signals, returning the handler's exception
+type SignalPoll = fn() -> Result<(), Interrupt>;
+
+/// Runs Python's signal handlers for the calling thread
+fn poll_signals() -> Result<(), Interrupt> {
+    Python::attach(|py| py.check_signals()).map_err(|err| Box::new(err) as Interrupt)
+}
+
+/// Cancellation state of one call, shared by the threads scanning for it
+#[derive(Debug)]
+pub struct Cancellation {
+    token: Option<Arc<AtomicBool>>,
+    /// Thread whose signals are polled and how, `None` to not poll
+    signals_of: Option<(ThreadId, SignalPoll)>,
+    started: Instant,
+    /// Microseconds after `started` of the last signal poll
+    polled_us: AtomicU64,
+    stopped: AtomicBool,
+    /// Exception raised by a signal handler, re-raised by `check()`
+    interrupt: Mutex<Option<Interrupt>>,
+}
+
+impl Cancellation {
+    /// Cancellation by `token`
+    pub fn new(token: Option<&CancelToken>) -> Self {
+        Self {
+            token: token.map(|token| Arc::clone(&token.flag)),
+            signals_of: None,
+            started: Instant::now(),
+            polled_us: AtomicU64::new(0),
+            stopped: AtomicBool::new(false),
+            interrupt: Mutex::new(None),
+        }
+    }
+
+    /// Also cancel on signals delivered to the calling thread
+    pub fn with_signals(mut self) -> Self {
+        self.signals_of = Some((thread::current().id(), poll_signals));
+        self
+    }
+
+    /// Whether the scan should stop; cheap enough to call per pattern
+    pub fn is_cancelled(&self) -> bool {
+        if self.stopped.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self
+            .token
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            self.stopped.store(true, Ordering::Relaxed);
+            return true;
+        }
+        if let Some((_, poll)) = self
+            .signals_of
+            .filter(|(thread, _)| *thread == thread::current().id())
+        {
+            let now = self.started.elapsed().as_micros() as u64;
+            let last = self.polled_us.load(Ordering::Relaxed);
+            if now - last >= SIGNAL_POLL_INTERVAL.as_micros() as u64 {
+                self.polled_us.store(now, Ordering::Relaxed);
+                if let Err(err) = poll() {
+                    *self
+                        .interrupt
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner) = Some(err);
+                    self.stopped.store(true, Ordering::Relaxed);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Error for a scan that stopped early: the signal handler's exception
+    /// (e.g. `KeyboardInterrupt`), else `ScanCancelledError`
+    pub fn error(&self) -> PyErr {
+        self.interrupt
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .and_then(|err| err.downcast::<PyErr>().ok())
+            .map_or_else(|| PIIError::Cancelled.into(), |err| *err)
+    }
+
+    /// `Err` once the scan was cancelled
+    pub fn check(&self) -> PyResult<()> {
+        if self.stopped.load(Ordering::Relaxed) {
+            Err(self.error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_cancels() {
+        let token = CancelToken::new();
+        let cancellation = Cancellation::new(Some(&token));
+        assert!(!cancellation.is_cancelled());
+
+        token.cancel();
+        assert!(token.cancelled());
+        assert!(cancellation.is_cancelled());
+        assert!(cancellation.stopped.load(Ordering::Relaxed));
+        assert!(!Cancellation::new(None).is_cancelled());
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Redaction certificates for PII Filter
+//
+// A certificate is a signed proof-of-processing record: it binds a digest of
+// a payload, the hash of the masked output and the hash of the configuration
+// that produced it, so compliance can later show that a specific payload
+// version was scrubbed under a specific policy without storing either text.
+// The payload digest is keyed with the certificate key: a plain hash of a
+// short input (an SSN, an email) could be confirmed by hashing guesses.
+
+use hmac::{Hmac, Mac};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
+
+use super::config::PIIType;
+use super::detector::Detection;
+use super::error::{PIIError, PIIResult};
+
+/// Certificate format version, bumped if the signed fields change
+const VERSION: u32 = 2;
+
+/// Signed fields of a certificate, serialized in declaration order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Certificate {
+    pub version: u32,
+    /// HMAC-SHA256 (hex) of the input under the certificate key
+    pub input_hmac: String,
+    pub output_sha256: String,
+    pub config_sha256: String,
+    /// Detections per PII type name
+    pub counts: BTreeMap<String, usize>,
+    /// Unix time the certificate was issued, in seconds
+    pub issued_at: u64,
+}
+
+impl Certificate {
+    pub fn new(
+        key: &str,
+        input: &str,
+        output: &str,
+        config_sha256: String,
+        detections: &BTreeMap<PIIType, Vec<Detection>>,
+    ) -> Self {
+        Self {
+            version: VERSION,
+            input_hmac: hmac_hex(key, input.as_bytes()),
+            output_sha256: sha256_hex(output),
+            config_sha256,
+            counts: detections
+                .iter()
+                .map(|(pii_type, items)| (pii_type.as_str().to_string(), items.len()))
+                .collect(),
+            issued_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        }
+    }
+
+    /// HMAC-SHA256 (hex) of the certificate's canonical JSON
+    pub fn sign(&self, key: &str) -> String {
+        hmac_hex(key, self.canonical().as_bytes())
+    }
+
+    /// Check `signature` in constant time
+    pub fn verify(&self, key: &str, signature: &str) -> bool {
+        let Some(signature) = decode_hex(signature) else {
+            return false;
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("any key length");
+        mac.update(self.canonical().as_bytes());
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    fn canonical(&self) -> String {
+        serde_json::to_string(self).expect("certificate serializes")
+    }
+
+    /// Convert to a Python dict including `signature`
+    pub fn to_py_dict<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("version", self.version)?;
+        py_dict.set_item("input_hmac", &self.input_hmac)?;
+        py_dict.set_item("output_sha256", &self.output_sha256)?;
+        py_dict.set_item("config_sha256", &self.config_sha256)?;
+        py_dict.set_item("counts", &self.counts)?;
+        py_dict.set_item("issued_at", self.issued_at)?;
+        py_dict.set_item("signature", self.sign(key))?;
+        Ok(py_dict)
+    }
+
+    /// Read a certificate produced by `to_py_dict`, returning it and its signature
+    pub fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<(Self, String)> {
+        let field = |name: &'static str| -> PyResult<Bound<'_, PyAny>> {
+            Ok(dict.get_item(name)?.ok_or(PIIError::MissingField(name))?)
+        };
+        let certificate = Self {
+            version: field("version")?.extract()?,
+            input_hmac: field("input_hmac")?.extract()?,
+            output_sha256: field("output_sha256")?.extract()?,
+            config_sha256: field("config_sha256")?.extract()?,
+            counts: field("counts")?.extract()?,
+            issued_at: field("issued_at")?.extract()?,
+        };
+        Ok((certificate, field("signature")?.extract()?))
+    }
+}
+
+/// Key for signing certificates, or an error when none is configured
+pub fn require_key(key: &str) -> PIIResult<&str> {
+    if key.is_empty() {
+        return Err(PIIError::MissingField("certificate_key"));
+    }
+    Ok(key)
+}
+
+/// Compare hex digests in constant time
+pub fn digests_match(a: &str, b: &str) -> bool {
+    a.as_bytes().ct_eq(b.as_bytes()).into()
+}
+
+/// HMAC-SHA256 (hex) of `data` under `key`
+pub fn hmac_hex(key: &str, data: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("any key length");
+    mac.update(data);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+pub fn sha256_hex(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+pub(crate) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::MaskingStrategy;
+
+    fn certificate() -> Certificate {
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::Ssn,
+            vec![Detection::new(
+                "123-45-6789",
+                4,
+                15,
+                MaskingStrategy::default(),
+            )],
+        );
+        Certificate::new(
+            "secret",
+            "SSN 123-45-6789",
+            "SSN [REDACTED]",
+            "abc".to_string(),
+            &detections,
+        )
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let certificate = certificate();
+        let signature = certificate.sign("secret");
+
+        assert_eq!(certificate.counts["ssn"], 1);
+        assert_eq!(
+            certificate.input_hmac,
+            hmac_hex("secret", b"SSN 123-45-6789")
+        );
+        assert_ne!(certificate.input_hmac, sha256_hex("SSN 123-45-6789"));
+        assert!(certificate.verify("secret", &signature));
+        assert!(!certificate.verify("other", &signature));
+        assert!(!certificate.verify("secret", "not hex"));
+        assert!(digests_match(&sha256_hex("a"), &sha256_hex("a")));
+        assert!(!digests_match(&sha256_hex("a"), &sha256_hex("b")));
+        assert!(!digests_match("ab", "abc"));
+    }
+
+    #[test]
+    fn test_tampered_certificate_fails() {
+        let certificate = certificate();
+        let signature = certificate.sign("secret");
+
+        let mut tampered = certificate.clone();
+        tampered.counts.insert("ssn".to_string(), 0);
+        assert!(!tampered.verify("secret", &signature));
+    }
+}
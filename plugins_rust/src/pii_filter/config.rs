@@ -4,11 +4,23 @@
 // Configuration types for PII Filter
 
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use zeroize::Zeroize;
+
+use super::cancel::Cancellation;
+use super::error::{PIIError, PIIResult};
+use super::progress::Progress;
+use super::routing::CallbackBatch;
 
 /// PII types that can be detected
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PIIType {
     Ssn,
@@ -23,6 +35,13 @@ pub enum PIIType {
     MedicalRecord,
     AwsKey,
     ApiKey,
+    PostalCode,
+    SocialHandle,
+    LicensePlate,
+    ConnectionString,
+    AuthCredential,
+    CloudCredential,
+    HighEntropyString,
     Custom,
 }
 
@@ -42,13 +61,55 @@ impl PIIType {
             PIIType::MedicalRecord => "medical_record",
             PIIType::AwsKey => "aws_key",
             PIIType::ApiKey => "api_key",
+            PIIType::PostalCode => "postal_code",
+            PIIType::SocialHandle => "social_handle",
+            PIIType::LicensePlate => "license_plate",
+            PIIType::ConnectionString => "connection_string",
+            PIIType::AuthCredential => "auth_credential",
+            PIIType::CloudCredential => "cloud_credential",
+            PIIType::HighEntropyString => "high_entropy_string",
             PIIType::Custom => "custom",
         }
     }
 }
 
+impl FromStr for PIIType {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ssn" => Ok(PIIType::Ssn),
+            "credit_card" => Ok(PIIType::CreditCard),
+            "email" => Ok(PIIType::Email),
+            "phone" => Ok(PIIType::Phone),
+            "ip_address" => Ok(PIIType::IpAddress),
+            "date_of_birth" => Ok(PIIType::DateOfBirth),
+            "passport" => Ok(PIIType::Passport),
+            "driver_license" => Ok(PIIType::DriverLicense),
+            "bank_account" => Ok(PIIType::BankAccount),
+            "medical_record" => Ok(PIIType::MedicalRecord),
+            "aws_key" => Ok(PIIType::AwsKey),
+            "api_key" => Ok(PIIType::ApiKey),
+            "postal_code" => Ok(PIIType::PostalCode),
+            "social_handle" => Ok(PIIType::SocialHandle),
+            "license_plate" => Ok(PIIType::LicensePlate),
+            "connection_string" => Ok(PIIType::ConnectionString),
+            "auth_credential" => Ok(PIIType::AuthCredential),
+            "cloud_credential" => Ok(PIIType::CloudCredential),
+            "high_entropy_string" => Ok(PIIType::HighEntropyString),
+            "custom" => Ok(PIIType::Custom),
+            _ => Err(PIIError::InvalidValue {
+                field: "pii_type",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
 /// Masking strategies for detected PII
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum MaskingStrategy {
     #[default]
@@ -59,6 +120,505 @@ pub enum MaskingStrategy {
     Remove,   // Remove entirely
 }
 
+impl FromStr for MaskingStrategy {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "redact" => Ok(MaskingStrategy::Redact),
+            "partial" => Ok(MaskingStrategy::Partial),
+            "hash" => Ok(MaskingStrategy::Hash),
+            "tokenize" => Ok(MaskingStrategy::Tokenize),
+            "remove" => Ok(MaskingStrategy::Remove),
+            _ => Err(PIIError::InvalidStrategy(s.to_string())),
+        }
+    }
+}
+
+/// Action taken when a single pattern exceeds `max_matches_per_pattern` in one
+/// scan window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchLimitAction {
+    #[default]
+    Truncate, // Keep the first `max_matches_per_pattern` matches of each window
+    Disable, // Keep those too, and skip the pattern in the text's remaining windows
+}
+
+impl FromStr for MatchLimitAction {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disable" => Ok(MatchLimitAction::Disable),
+            "truncate" => Ok(MatchLimitAction::Truncate),
+            _ => Err(PIIError::InvalidValue {
+                field: "match_limit_action",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Deepest `max_depth` accepted
+pub const MAX_DEPTH: usize = 512;
+
+/// Action taken when `process_nested` exceeds `max_depth` or `max_nodes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NestingLimitAction {
+    #[default]
+    Error, // Raise `PayloadLimitError`
+    Flag, // Leave the rest unscanned and count the trip in `stats()`
+}
+
+impl FromStr for NestingLimitAction {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flag" => Ok(NestingLimitAction::Flag),
+            "error" => Ok(NestingLimitAction::Error),
+            _ => Err(PIIError::InvalidValue {
+                field: "nesting_limit_action",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// How strictly a bare date must look like a date of birth before it is
+/// reported as `date_of_birth`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DobMode {
+    #[default]
+    Strict, // Require a DOB context keyword ("DOB", "born", ...) and a plausible birth date
+    Lenient, // Accept a context keyword or an age-plausible date
+}
+
+impl FromStr for DobMode {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(DobMode::Strict),
+            "lenient" => Ok(DobMode::Lenient),
+            _ => Err(PIIError::InvalidValue {
+                field: "dob_mode",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Generalization applied to dates of birth in place of the masking strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DobGeneralization {
+    #[default]
+    None, // Mask with the configured strategy
+    Year,     // Keep only the year: "1985"
+    AgeRange, // Bucket into a ten-year age range: "30-39"
+}
+
+impl FromStr for DobGeneralization {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(DobGeneralization::None),
+            "year" => Ok(DobGeneralization::Year),
+            "age_range" => Ok(DobGeneralization::AgeRange),
+            _ => Err(PIIError::InvalidValue {
+                field: "dob_generalization",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Anonymization applied to IP addresses in place of the masking strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IpAnonymization {
+    #[default]
+    None, // Mask with the configured strategy
+    Truncate,         // Zero the last IPv4 octet / last 80 IPv6 bits
+    PrefixPreserving, // Keyed CryptoPAN-style mapping that preserves shared prefixes
+}
+
+impl FromStr for IpAnonymization {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(IpAnonymization::None),
+            "truncate" => Ok(IpAnonymization::Truncate),
+            "prefix_preserving" => Ok(IpAnonymization::PrefixPreserving),
+            _ => Err(PIIError::InvalidValue {
+                field: "ip_anonymization",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Which way a payload flows through the gateway
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Request,      // Client to server (tool arguments, prompts)
+    Response,     // Server to client (tool results, resources)
+    Notification, // Server-initiated messages
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Request => "request",
+            Direction::Response => "response",
+            Direction::Notification => "notification",
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "request" => Ok(Direction::Request),
+            "response" => Ok(Direction::Response),
+            "notification" => Ok(Direction::Notification),
+            _ => Err(PIIError::InvalidValue {
+                field: "direction",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Policy of one direction, on top of the base configuration
+///
+/// Settings given here win over the base ones for payloads flowing that
+/// way; per-call overrides still win over both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DirectionPolicy {
+    /// Only these types are detected, when set
+    pub enabled_types: Option<BTreeSet<PIIType>>,
+    pub disabled_types: BTreeSet<PIIType>,
+    pub mask_strategy: Option<MaskingStrategy>,
+    pub block_on_detection: Option<bool>,
+    pub block_overrides: HashMap<PIIType, bool>,
+}
+
+impl DirectionPolicy {
+    fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let types = |value: &Bound<'_, PyAny>| -> PyResult<BTreeSet<PIIType>> {
+            let mut types = BTreeSet::new();
+            for type_str in value.extract::<Vec<String>>()? {
+                types.insert(type_str.parse()?);
+            }
+            Ok(types)
+        };
+
+        let mut policy = Self::default();
+        for (key, value) in dict.iter() {
+            let key: String = key.extract()?;
+            match key.as_str() {
+                "enabled_types" => policy.enabled_types = Some(types(&value)?),
+                "disabled_types" => policy.disabled_types = types(&value)?,
+                "mask_strategy" => policy.mask_strategy = Some(value.extract::<String>()?.parse()?),
+                "block_on_detection" => policy.block_on_detection = Some(value.extract()?),
+                "block_overrides" => {
+                    for (type_str, block) in value.extract::<HashMap<String, bool>>()? {
+                        policy.block_overrides.insert(type_str.parse()?, block);
+                    }
+                }
+                _ => {
+                    return Err(PIIError::InvalidValue {
+                        field: "directions",
+                        value: key,
+                    }
+                    .into())
+                }
+            }
+        }
+        Ok(policy)
+    }
+}
+
+/// Jurisdictions whose vehicle license plate formats are detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlateJurisdiction {
+    Us,
+    Uk,
+    Eu,
+}
+
+impl FromStr for PlateJurisdiction {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "us" => Ok(PlateJurisdiction::Us),
+            "uk" => Ok(PlateJurisdiction::Uk),
+            "eu" => Ok(PlateJurisdiction::Eu),
+            _ => Err(PIIError::InvalidValue {
+                field: "license_plate_jurisdictions",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// How license plates are matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PlateMode {
+    #[default]
+    Strict, // Only exact jurisdiction formats, no context needed
+    Lenient, // Also generic alphanumeric plates; every match needs a "plate"/"vehicle" keyword
+}
+
+impl FromStr for PlateMode {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(PlateMode::Strict),
+            "lenient" => Ok(PlateMode::Lenient),
+            _ => Err(PIIError::InvalidValue {
+                field: "license_plate_mode",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Characters that make up a candidate token for entropy scanning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EntropyCharset {
+    #[default]
+    Base64, // A-Z a-z 0-9 + / _ - with trailing = padding
+    Hex,          // 0-9 a-f
+    Alphanumeric, // A-Z a-z 0-9
+}
+
+impl EntropyCharset {
+    /// Regex matching candidate tokens of at least `min_length` characters
+    pub fn token_pattern(&self, min_length: usize) -> String {
+        match self {
+            EntropyCharset::Base64 => format!(r"[A-Za-z0-9+/_-]{{{},}}={{0,2}}", min_length),
+            EntropyCharset::Hex => format!(r"\b[0-9a-fA-F]{{{},}}\b", min_length),
+            EntropyCharset::Alphanumeric => format!(r"\b[A-Za-z0-9]{{{},}}\b", min_length),
+        }
+    }
+
+    /// Highest entropy a token can reach, in bits/char (log2 of the
+    /// alphabet size)
+    pub fn max_entropy(&self) -> f64 {
+        match self {
+            EntropyCharset::Base64 => 6.0,
+            EntropyCharset::Hex => 4.0,
+            EntropyCharset::Alphanumeric => 62f64.log2(),
+        }
+    }
+
+    /// `entropy_threshold` when none is configured; random hex tokens score
+    /// around 3.5-3.8, so the base64 threshold of 4.0 would never match them
+    pub fn default_threshold(&self) -> f64 {
+        match self {
+            EntropyCharset::Base64 | EntropyCharset::Alphanumeric => 4.0,
+            EntropyCharset::Hex => 3.0,
+        }
+    }
+}
+
+impl FromStr for EntropyCharset {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "base64" => Ok(EntropyCharset::Base64),
+            "hex" => Ok(EntropyCharset::Hex),
+            "alphanumeric" => Ok(EntropyCharset::Alphanumeric),
+            _ => Err(PIIError::InvalidValue {
+                field: "entropy_charset",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// How bare 40-character tokens are confirmed as AWS secret access keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AwsSecretMode {
+    #[default]
+    Strict, // Require a key name ("aws_secret_access_key", "SecretAccessKey=") nearby
+    Lenient, // Key name nearby, or high entropy (rules out git SHAs and other hex)
+}
+
+impl FromStr for AwsSecretMode {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(AwsSecretMode::Strict),
+            "lenient" => Ok(AwsSecretMode::Lenient),
+            _ => Err(PIIError::InvalidValue {
+                field: "aws_secret_mode",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// What counts as a word boundary (`\b`) in detection patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryMode {
+    #[default]
+    Unicode, // Boundaries between Unicode word and non-word characters
+    Ascii, // Every non-ASCII character is a boundary (values inside CJK text, next to fullwidth punctuation)
+}
+
+impl FromStr for BoundaryMode {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unicode" => Ok(BoundaryMode::Unicode),
+            "ascii" => Ok(BoundaryMode::Ascii),
+            _ => Err(PIIError::InvalidValue {
+                field: "boundary_mode",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Engine picking which patterns run on a text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RegexBackend {
+    #[default]
+    Meta, // `regex` RegexSet, fastest, with up to 2 MiB of lazy DFA cache per pattern and thread
+    LazyDfa, // `regex-automata` lazy DFA over the whole set, with small bounded caches
+}
+
+impl RegexBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegexBackend::Meta => "meta",
+            RegexBackend::LazyDfa => "lazy_dfa",
+        }
+    }
+}
+
+impl FromStr for RegexBackend {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "meta" => Ok(RegexBackend::Meta),
+            "lazy_dfa" => Ok(RegexBackend::LazyDfa),
+            _ => Err(PIIError::InvalidValue {
+                field: "regex_backend",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Unicode normalization applied to a shadow copy of the input before matching
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UnicodeNormalization {
+    #[default]
+    None,
+    Nfc,  // Compose decomposed forms (e + U+0301 -> é)
+    Nfkc, // Also fold compatibility forms (fullwidth digits, ligatures, superscripts)
+}
+
+impl FromStr for UnicodeNormalization {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(UnicodeNormalization::None),
+            "nfc" => Ok(UnicodeNormalization::Nfc),
+            "nfkc" => Ok(UnicodeNormalization::Nfkc),
+            _ => Err(PIIError::InvalidValue {
+                field: "unicode_normalization",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Restricts the fields of `process_nested` payloads matching `field` to
+/// the patterns of `types`
+///
+/// `field` is a glob over dotted paths: `*` matches within one segment (list
+/// indices included, so `*.email` covers `users[3].email`) and `**` any
+/// number of segments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldRule {
+    pub field: String,
+    pub types: Vec<PIIType>,
+}
+
+impl FieldRule {
+    pub fn matches(&self, path: &str) -> bool {
+        let glob: Vec<&str> = self.field.split('.').collect();
+        let path: Vec<&str> = path.split('.').collect();
+        segments_match(&glob, &path)
+    }
+}
+
+fn segments_match(glob: &[&str], path: &[&str]) -> bool {
+    match glob.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(first, path_rest)| {
+            wildcard_match(segment.as_bytes(), first.as_bytes()) && segments_match(rest, path_rest)
+        }),
+    }
+}
+
+/// `*` matches any run of bytes
+fn wildcard_match(glob: &[u8], text: &[u8]) -> bool {
+    match glob.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| wildcard_match(rest, &text[skip..])),
+        Some((c, rest)) => text
+            .split_first()
+            .is_some_and(|(first, text_rest)| first == c && wildcard_match(rest, text_rest)),
+    }
+}
+
+/// Configuration used for the calls of MCP tools whose name matches
+/// `tools`, a glob where `*` matches any run of characters (`db_query*`)
+///
+/// `config` is the complete effective configuration: the base one with the
+/// overlay's keys on top.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolOverlay {
+    pub tools: String,
+    pub config: PIIConfig,
+}
+
+impl ToolOverlay {
+    pub fn matches(&self, tool_name: &str) -> bool {
+        wildcard_match(self.tools.as_bytes(), tool_name.as_bytes())
+    }
+}
+
 /// Custom pattern definition from Python
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomPattern {
@@ -67,14 +627,138 @@ pub struct CustomPattern {
     pub mask_strategy: MaskingStrategy,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Overlap priority against other patterns (higher wins, default 0)
+    #[serde(default)]
+    pub priority: i32,
+    /// Capture group (name or index) holding the sensitive part; only that
+    /// span is detected and masked, keeping the rest of the match readable
+    #[serde(default)]
+    pub secret_group: Option<String>,
+    /// `^` and `$` match at line breaks as well as at the ends of the text
+    #[serde(default)]
+    pub multiline: bool,
+    /// `.` also matches `\n`, for values spanning lines (PEM blocks, addresses)
+    #[serde(default)]
+    pub dot_matches_newline: bool,
+}
+
+/// Matches the serde defaults, so struct literals only spell out what differs
+impl Default for CustomPattern {
+    fn default() -> Self {
+        Self {
+            pattern: String::new(),
+            description: String::new(),
+            mask_strategy: MaskingStrategy::default(),
+            enabled: default_enabled(),
+            case_sensitive: false,
+            priority: 0,
+            secret_group: None,
+            multiline: false,
+            dot_matches_newline: false,
+        }
+    }
+}
+
+impl CustomPattern {
+    /// Inline flag group (`(?ims)`) for the pattern's options, empty if none
+    pub fn inline_flags(&self) -> String {
+        let flags: String = [
+            (!self.case_sensitive, 'i'),
+            (self.multiline, 'm'),
+            (self.dot_matches_newline, 's'),
+        ]
+        .iter()
+        .filter_map(|&(set, flag)| set.then_some(flag))
+        .collect();
+        if flags.is_empty() {
+            flags
+        } else {
+            format!("(?{})", flags)
+        }
+    }
 }
 
 fn default_enabled() -> bool {
     true
 }
 
+/// Bytes of context on each side of a detection by default
+pub const DEFAULT_WHITELIST_WINDOW: usize = 64;
+
+/// Whitelist rule matched against a detection and the text around it
+///
+/// The rule whitelists a detection when its pattern matches anywhere in the
+/// window. With a `value` capture group, that group must match exactly the
+/// detected value: `e\.g\.\s*(?P<value>123-45-6789)` whitelists the example
+/// SSN only where it follows "e.g.".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextWhitelist {
+    pub pattern: String,
+    /// Bytes of context on each side of the detection
+    #[serde(default = "default_whitelist_window")]
+    pub window: usize,
+    /// Types the rule applies to, all when empty
+    #[serde(default)]
+    pub types: Vec<PIIType>,
+}
+
+fn default_whitelist_window() -> usize {
+    DEFAULT_WHITELIST_WINDOW
+}
+
+/// Key material from the configuration, never shown by `Debug` and wiped
+/// from memory when dropped
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SecretKey(String);
+
+impl SecretKey {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl From<String> for SecretKey {
+    fn from(key: String) -> Self {
+        Self(key)
+    }
+}
+
+impl From<&str> for SecretKey {
+    fn from(key: &str) -> Self {
+        Self(key.to_string())
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            f.write_str("SecretKey(<unset>)")
+        } else {
+            f.write_str("SecretKey(<redacted>)")
+        }
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 /// Configuration for PII Filter
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PIIConfig {
     // Detection flags
     pub detect_ssn: bool,
@@ -88,16 +772,125 @@ pub struct PIIConfig {
     pub detect_bank_account: bool,
     pub detect_medical_record: bool,
     pub detect_aws_keys: bool,
+    pub aws_secret_mode: AwsSecretMode,
     pub detect_api_keys: bool,
+    pub detect_connection_strings: bool,
+    pub detect_auth_credentials: bool,
+    pub detect_cloud_credentials: bool,
+
+    // Generic high-entropy secret scanning
+    pub detect_high_entropy: bool,
+    pub entropy_min_length: usize,
+    /// Minimum Shannon entropy in bits per character (the charset's
+    /// `default_threshold()` when unset)
+    pub entropy_threshold: Option<f64>,
+    pub entropy_charset: EntropyCharset,
+    /// Nested paths (and their children) scanned for entropy; empty = all
+    pub entropy_paths: Vec<String>,
+    /// Per-field pattern sets for `process_nested`; the first matching rule
+    /// applies and unmatched fields are scanned for every type
+    pub field_rules: Vec<FieldRule>,
+    /// Scan integers in `process_nested` payloads as their decimal digits,
+    /// when they have at least `number_min_digits` (SSNs, accounts stored as ints)
+    pub scan_numbers: bool,
+    pub number_min_digits: usize,
+    /// Scan and mask dict keys in `process_nested` payloads, not just values
+    pub scan_keys: bool,
+    /// Traversal guards for `process_nested` against adversarial payloads
+    /// (0 disables a limit); the top-level container is depth 1
+    pub max_depth: usize,
+    pub max_nodes: usize,
+    pub nesting_limit_action: NestingLimitAction,
+    /// `process_json` documents with an array of at least this many elements
+    /// are scanned in parallel (0 = never)
+    pub parallel_array_min_len: usize,
+    pub detect_postal_code: bool,
+    /// Only report postal codes with address context nearby
+    pub postal_code_require_context: bool,
+    pub detect_social_handle: bool,
+    /// Only report profile URLs, not bare @mentions
+    pub social_handle_urls_only: bool,
+    pub detect_license_plate: bool,
+    pub license_plate_jurisdictions: Vec<PlateJurisdiction>,
+    pub license_plate_mode: PlateMode,
 
-    // Masking configuration
+    // Masking configuration; the default strategy applies to high-entropy
+    // tokens, which no pattern assigns one
     pub default_mask_strategy: MaskingStrategy,
     pub redaction_text: String,
 
     // Behavior configuration
     pub block_on_detection: bool,
+    /// Per-type overrides of `block_on_detection`
+    pub block_overrides: HashMap<PIIType, bool>,
+    /// Policy differences per direction (see `DirectionPolicy`)
+    #[serde(default)]
+    pub directions: HashMap<Direction, DirectionPolicy>,
+    /// Per-type overrides of each pattern family's default case sensitivity
+    pub case_sensitive_overrides: HashMap<PIIType, bool>,
+    /// Per-type overlap priority (higher wins, default 0); among equal
+    /// priorities built-in families come first in `PIIType` registration order
+    pub pattern_priorities: HashMap<PIIType, i32>,
+    pub boundary_mode: BoundaryMode,
+    /// Fold non-ASCII decimal digits (Arabic-Indic, Devanagari, ...) to ASCII before matching
+    pub normalize_digits: bool,
+    pub unicode_normalization: UnicodeNormalization,
+    /// Log an INFO summary (types and counts) of each scan with detections
+    /// to the `plugins_rust.pii_filter.log_bridge` Python logger
     pub log_detections: bool,
+    /// Return values and offsets of detections to Python; counts only when false
     pub include_detection_details: bool,
+    /// Detection events kept for `drain_events()` at most (0 disables the queue)
+    pub event_queue_capacity: usize,
+    /// Leave values already masked by an earlier pass (`[REDACTED]`,
+    /// `[HASH:..]`, `***-**-1234`, ...) alone instead of masking them again
+    pub skip_masked_values: bool,
+    /// Score re-identification risk from quasi-identifiers (birth date, ZIP
+    /// code, gender) co-occurring in a payload
+    pub risk_analysis: bool,
+
+    // Match explosion guard, per pattern and scan window (0 disables the limit)
+    pub max_matches_per_pattern: usize,
+    pub match_limit_action: MatchLimitAction,
+
+    // Texts longer than the window are scanned in overlapping windows so no
+    // pattern runs over a multi-megabyte haystack (0 scans in one pass). The
+    // overlap is the longest value guaranteed to be found across a seam.
+    pub scan_window_bytes: usize,
+    pub scan_window_overlap: usize,
+
+    // On-disk cache of the pattern set DFA, keyed by policy hash (None
+    // disables it). Sets whose DFA would exceed the byte budget are not cached.
+    pub pattern_cache_dir: Option<String>,
+    pub pattern_cache_max_bytes: usize,
+
+    // Matching engine, and the lazy DFA cache each pattern may grow per
+    // thread under the lazy_dfa backend
+    pub regex_backend: RegexBackend,
+    pub lazy_dfa_cache_bytes: usize,
+
+    // Date of birth disambiguation
+    pub dob_mode: DobMode,
+    pub dob_generalization: DobGeneralization,
+
+    // IP address anonymization
+    pub ip_anonymization: IpAnonymization,
+    pub ip_anonymization_key: SecretKey,
+
+    // HMAC key signing redaction certificates (empty disables them)
+    pub certificate_key: SecretKey,
+
+    // Credit card partial masking keeps the BIN (first 6 digits)
+    pub preserve_bin: bool,
+
+    // Deterministic tokenize output for tests and snapshots (never in production:
+    // seeded tokens are predictable)
+    pub token_seed: Option<u64>,
+
+    // Email domain handling
+    pub email_preserve_domain: bool,
+    pub email_exempt_domains: Vec<String>,
+    pub email_always_mask_domains: Vec<String>,
 
     // Custom patterns
     #[serde(default)]
@@ -105,6 +898,13 @@ pub struct PIIConfig {
 
     // Whitelist patterns (regex strings)
     pub whitelist_patterns: Vec<String>,
+    // Whitelist rules matched against the text around a detection
+    #[serde(default)]
+    pub whitelist_context: Vec<ContextWhitelist>,
+
+    // Configurations for specific MCP tools, the first matching one wins
+    #[serde(default)]
+    pub tool_overlays: Vec<ToolOverlay>,
 }
 
 impl Default for PIIConfig {
@@ -122,26 +922,236 @@ impl Default for PIIConfig {
             detect_bank_account: true,
             detect_medical_record: true,
             detect_aws_keys: true,
+            aws_secret_mode: AwsSecretMode::Strict,
             detect_api_keys: true,
 
+            // Opt-in: newer families change what existing deployments mask
+            detect_connection_strings: false,
+            detect_auth_credentials: false,
+            detect_cloud_credentials: false,
+
+            // Opt-in: entropy scanning is noisy on arbitrary text
+            detect_high_entropy: false,
+            entropy_min_length: 20,
+            entropy_threshold: None,
+            entropy_charset: EntropyCharset::Base64,
+            entropy_paths: Vec::new(),
+            field_rules: Vec::new(),
+            scan_numbers: false,
+            number_min_digits: 9,
+            scan_keys: false,
+            max_depth: 128,
+            max_nodes: 1_000_000,
+            nesting_limit_action: NestingLimitAction::Error,
+            parallel_array_min_len: 1024,
+            // Opt-in: bare 5-digit numbers are too common to flag by default
+            detect_postal_code: false,
+            postal_code_require_context: true,
+            // Opt-in: @mentions are noisy in chat and code
+            detect_social_handle: false,
+            social_handle_urls_only: false,
+            detect_license_plate: false,
+            license_plate_jurisdictions: vec![
+                PlateJurisdiction::Us,
+                PlateJurisdiction::Uk,
+                PlateJurisdiction::Eu,
+            ],
+            license_plate_mode: PlateMode::Strict,
+
             // Default masking
             default_mask_strategy: MaskingStrategy::Redact,
             redaction_text: "[REDACTED]".to_string(),
 
             // Default behavior
             block_on_detection: false,
+            block_overrides: HashMap::new(),
+            directions: HashMap::new(),
+            case_sensitive_overrides: HashMap::new(),
+            pattern_priorities: HashMap::new(),
+            boundary_mode: BoundaryMode::Unicode,
+            normalize_digits: true,
+            unicode_normalization: UnicodeNormalization::None,
             log_detections: true,
             include_detection_details: true,
+            event_queue_capacity: 0,
+            skip_masked_values: false,
+            risk_analysis: false,
+
+            // Match explosion guard
+            max_matches_per_pattern: 10_000,
+            match_limit_action: MatchLimitAction::Truncate,
+
+            // Long enough for PEM private keys and connection strings
+            scan_window_bytes: 1 << 20,
+            scan_window_overlap: 4096,
+
+            pattern_cache_dir: None,
+            pattern_cache_max_bytes: 8 << 20,
+
+            regex_backend: RegexBackend::Meta,
+            lazy_dfa_cache_bytes: 256 << 10,
+
+            // Date of birth disambiguation
+            dob_mode: DobMode::Strict,
+            dob_generalization: DobGeneralization::None,
+
+            // IP address anonymization
+            ip_anonymization: IpAnonymization::None,
+            ip_anonymization_key: SecretKey::default(),
+
+            certificate_key: SecretKey::default(),
+
+            preserve_bin: false,
+
+            token_seed: None,
+
+            // Email domain handling
+            email_preserve_domain: false,
+            email_exempt_domains: Vec::new(),
+            email_always_mask_domains: Vec::new(),
 
             // Custom patterns
             custom_patterns: Vec::new(),
 
             whitelist_patterns: Vec::new(),
+            whitelist_context: Vec::new(),
+
+            tool_overlays: Vec::new(),
         }
     }
 }
 
 impl PIIConfig {
+    /// Whether detections of `pii_type` should block the content
+    pub fn blocks(&self, pii_type: PIIType) -> bool {
+        self.block_overrides
+            .get(&pii_type)
+            .copied()
+            .unwrap_or(self.block_on_detection)
+    }
+
+    /// Whether detections of `pii_type` should block content flowing in
+    /// `direction`
+    pub fn blocks_in(&self, pii_type: PIIType, direction: Option<Direction>) -> bool {
+        let Some(policy) = direction.and_then(|direction| self.directions.get(&direction)) else {
+            return self.blocks(pii_type);
+        };
+        policy
+            .block_overrides
+            .get(&pii_type)
+            .copied()
+            .or(policy.block_on_detection)
+            .unwrap_or_else(|| self.blocks(pii_type))
+    }
+
+    /// Check settings that depend on each other
+    pub fn validate(&self) -> PIIResult<()> {
+        if self.ip_anonymization == IpAnonymization::PrefixPreserving
+            && self.ip_anonymization_key.is_empty()
+        {
+            return Err(PIIError::MissingField("ip_anonymization_key"));
+        }
+        // Payloads are walked recursively, so nesting is always bounded
+        if !(1..=MAX_DEPTH).contains(&self.max_depth) {
+            return Err(PIIError::InvalidValue {
+                field: "max_depth",
+                value: self.max_depth.to_string(),
+            });
+        }
+        // A threshold above the charset's maximum could never match
+        if let Some(threshold) = self.entropy_threshold {
+            if !(0.0..=self.entropy_charset.max_entropy()).contains(&threshold) {
+                return Err(PIIError::InvalidValue {
+                    field: "entropy_threshold",
+                    value: threshold.to_string(),
+                });
+            }
+        }
+        if self.scan_window_bytes > 0 && self.scan_window_overlap * 2 > self.scan_window_bytes {
+            return Err(PIIError::InvalidValue {
+                field: "scan_window_overlap",
+                value: self.scan_window_overlap.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Minimum entropy (bits/char) of a high-entropy token
+    pub fn entropy_threshold(&self) -> f64 {
+        self.entropy_threshold
+            .unwrap_or_else(|| self.entropy_charset.default_threshold())
+    }
+
+    /// SHA-256 (hex) identifying the effective policy
+    ///
+    /// Covers the crate version, which fixes the built-in patterns, and the
+    /// configuration with secrets left out. Keys are serialized in sorted
+    /// order, so equal configurations hash equally however they were built.
+    pub fn policy_hash(&self) -> String {
+        let mut canonical = serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "config": self.public(),
+        });
+        canonical.sort_all_objects();
+        let canonical = canonical.to_string();
+        format!("{:x}", Sha256::digest(canonical.as_bytes()))
+    }
+
+    /// The configuration without secrets and the settings that don't change
+    /// what is detected or how it is masked
+    fn public(&self) -> Self {
+        let mut public = self.clone();
+        public.ip_anonymization_key.clear();
+        public.certificate_key.clear();
+        // Where the pattern cache lives doesn't change what is detected
+        public.pattern_cache_dir = None;
+        public.pattern_cache_max_bytes = 0;
+        // Nor which engine runs the patterns
+        public.regex_backend = RegexBackend::Meta;
+        public.lazy_dfa_cache_bytes = 0;
+        // Nor whether detections are also queued as events
+        public.event_queue_capacity = 0;
+        for overlay in &mut public.tool_overlays {
+            overlay.config = overlay.config.public();
+        }
+        public
+    }
+
+    /// The overlay configuration for calls of `tool_name`, if any
+    pub fn tool_overlay(&self, tool_name: &str) -> Option<&ToolOverlay> {
+        self.tool_overlays
+            .iter()
+            .find(|overlay| overlay.matches(tool_name))
+    }
+
+    /// Whether entropy scanning applies to the nested field at `path`
+    ///
+    /// A configured path matches itself and everything below it
+    /// (`"headers"` covers `"headers.x-api-key"` and `"headers[0]"`).
+    pub fn entropy_applies_to(&self, path: &str) -> bool {
+        self.entropy_paths.is_empty()
+            || self.entropy_paths.iter().any(|scope| {
+                path == scope
+                    || path
+                        .strip_prefix(scope.as_str())
+                        .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+            })
+    }
+
+    /// Types scanned for in the nested field at `path`, when a field rule
+    /// restricts them
+    pub fn field_types(&self, path: &str) -> Option<&[PIIType]> {
+        self.field_rules
+            .iter()
+            .find(|rule| rule.matches(path))
+            .map(|rule| rule.types.as_slice())
+    }
+
+    /// Whether an integer with decimal representation `repr` is scanned
+    pub fn scans_number(&self, repr: &str) -> bool {
+        self.scan_numbers && repr.trim_start_matches('-').len() >= self.number_min_digits
+    }
+
     /// Extract configuration from Python dict
     pub fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
         let mut config = Self::default();
@@ -168,63 +1178,204 @@ impl PIIConfig {
         extract_bool!(detect_medical_record);
         extract_bool!(detect_aws_keys);
         extract_bool!(detect_api_keys);
+        extract_bool!(detect_connection_strings);
+        extract_bool!(detect_auth_credentials);
+        extract_bool!(detect_cloud_credentials);
+        extract_bool!(detect_high_entropy);
+        extract_bool!(detect_postal_code);
+        extract_bool!(postal_code_require_context);
+        extract_bool!(detect_social_handle);
+        extract_bool!(social_handle_urls_only);
+        extract_bool!(detect_license_plate);
         extract_bool!(block_on_detection);
         extract_bool!(log_detections);
         extract_bool!(include_detection_details);
+        extract_bool!(skip_masked_values);
+        extract_bool!(risk_analysis);
+        extract_bool!(normalize_digits);
+        extract_bool!(preserve_bin);
+        extract_bool!(email_preserve_domain);
 
-        // Extract string values
+        // Extract string values (with ${ENV_VAR} interpolation)
         if let Some(value) = dict.get_item("redaction_text")? {
-            config.redaction_text = value.extract()?;
+            config.redaction_text = interpolate_env("redaction_text", &value.extract::<String>()?)?;
+        }
+
+        if let Some(value) = dict.get_item("ip_anonymization_key")? {
+            config.ip_anonymization_key =
+                interpolate_env("ip_anonymization_key", &value.extract::<String>()?)?.into();
+        }
+        if let Some(value) = dict.get_item("certificate_key")? {
+            config.certificate_key =
+                interpolate_env("certificate_key", &value.extract::<String>()?)?.into();
+        }
+
+        if let Some(value) = dict.get_item("token_seed")? {
+            config.token_seed = value.extract()?;
+        }
+
+        // Extract match explosion guard settings
+        if let Some(value) = dict.get_item("max_matches_per_pattern")? {
+            config.max_matches_per_pattern = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("match_limit_action")? {
+            config.match_limit_action = value.extract::<String>()?.parse()?;
+        }
+        if let Some(value) = dict.get_item("scan_window_bytes")? {
+            config.scan_window_bytes = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("scan_window_overlap")? {
+            config.scan_window_overlap = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("pattern_cache_dir")? {
+            config.pattern_cache_dir = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("pattern_cache_max_bytes")? {
+            config.pattern_cache_max_bytes = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("regex_backend")? {
+            config.regex_backend = value.extract::<String>()?.parse()?;
+        }
+        if let Some(value) = dict.get_item("lazy_dfa_cache_bytes")? {
+            config.lazy_dfa_cache_bytes = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("event_queue_capacity")? {
+            config.event_queue_capacity = value.extract()?;
+        }
+
+        // Extract date of birth disambiguation mode
+        if let Some(value) = dict.get_item("dob_mode")? {
+            config.dob_mode = value.extract::<String>()?.parse()?;
+        }
+        if let Some(value) = dict.get_item("dob_generalization")? {
+            config.dob_generalization = value.extract::<String>()?.parse()?;
+        }
+
+        // Extract IP anonymization mode
+        if let Some(value) = dict.get_item("ip_anonymization")? {
+            config.ip_anonymization = value.extract::<String>()?.parse()?;
+        }
+
+        // Extract AWS secret confirmation mode
+        if let Some(value) = dict.get_item("aws_secret_mode")? {
+            config.aws_secret_mode = value.extract::<String>()?.parse()?;
+        }
+
+        // Extract entropy scanner settings
+        if let Some(value) = dict.get_item("entropy_min_length")? {
+            config.entropy_min_length = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("entropy_threshold")? {
+            config.entropy_threshold = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("entropy_charset")? {
+            config.entropy_charset = value.extract::<String>()?.parse()?;
+        }
+        if let Some(value) = dict.get_item("entropy_paths")? {
+            config.entropy_paths = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("scan_numbers")? {
+            config.scan_numbers = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("number_min_digits")? {
+            config.number_min_digits = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("scan_keys")? {
+            config.scan_keys = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("max_depth")? {
+            config.max_depth = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("max_nodes")? {
+            config.max_nodes = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("nesting_limit_action")? {
+            config.nesting_limit_action = value.extract::<String>()?.parse()?;
+        }
+        if let Some(value) = dict.get_item("parallel_array_min_len")? {
+            config.parallel_array_min_len = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("field_rules")? {
+            for item in value.cast::<PyList>()?.iter() {
+                let rule = item.cast::<PyDict>()?;
+                let field: String = rule
+                    .get_item("field")?
+                    .ok_or(PIIError::MissingField("field"))?
+                    .extract()?;
+                let types: Vec<String> = rule
+                    .get_item("types")?
+                    .ok_or(PIIError::MissingField("types"))?
+                    .extract()?;
+                config.field_rules.push(FieldRule {
+                    field,
+                    types: types.iter().map(|s| s.parse()).collect::<PIIResult<_>>()?,
+                });
+            }
+        }
+
+        // Extract license plate settings
+        if let Some(value) = dict.get_item("license_plate_jurisdictions")? {
+            let jurisdictions: Vec<String> = value.extract()?;
+            config.license_plate_jurisdictions = jurisdictions
+                .iter()
+                .map(|s| s.parse())
+                .collect::<PIIResult<_>>()?;
+        }
+        if let Some(value) = dict.get_item("license_plate_mode")? {
+            config.license_plate_mode = value.extract::<String>()?.parse()?;
         }
 
         // Extract mask strategy
         if let Some(value) = dict.get_item("default_mask_strategy")? {
-            let strategy_str: String = value.extract()?;
-            config.default_mask_strategy = match strategy_str.as_str() {
-                "redact" => MaskingStrategy::Redact,
-                "partial" => MaskingStrategy::Partial,
-                "hash" => MaskingStrategy::Hash,
-                "tokenize" => MaskingStrategy::Tokenize,
-                "remove" => MaskingStrategy::Remove,
-                _ => MaskingStrategy::Redact,
-            };
+            config.default_mask_strategy = value.extract::<String>()?.parse()?;
         }
 
         // Extract custom patterns
         if let Some(value) = dict.get_item("custom_patterns")? {
-            if let Ok(py_list) = value.downcast::<pyo3::types::PyList>() {
+            if let Ok(py_list) = value.cast::<PyList>() {
                 for item in py_list.iter() {
-                    if let Ok(py_dict) = item.downcast::<PyDict>() {
+                    if let Ok(py_dict) = item.cast::<PyDict>() {
                         let pattern: String = py_dict
                             .get_item("pattern")?
-                            .ok_or_else(|| {
-                                pyo3::exceptions::PyValueError::new_err("Missing 'pattern' field")
-                            })?
+                            .ok_or(PIIError::MissingField("pattern"))?
                             .extract()?;
                         let description: String = py_dict
                             .get_item("description")?
-                            .ok_or_else(|| {
-                                pyo3::exceptions::PyValueError::new_err(
-                                    "Missing 'description' field",
-                                )
-                            })?
+                            .ok_or(PIIError::MissingField("description"))?
                             .extract()?;
-                        let mask_strategy_str: String = match py_dict.get_item("mask_strategy")? {
-                            Some(val) => val.extract()?,
-                            None => "redact".to_string(),
+                        let mask_strategy = match py_dict.get_item("mask_strategy")? {
+                            Some(val) => val.extract::<String>()?.parse()?,
+                            None => MaskingStrategy::Redact,
                         };
                         let enabled: bool = match py_dict.get_item("enabled")? {
                             Some(val) => val.extract()?,
                             None => true,
                         };
-
-                        let mask_strategy = match mask_strategy_str.as_str() {
-                            "redact" => MaskingStrategy::Redact,
-                            "partial" => MaskingStrategy::Partial,
-                            "hash" => MaskingStrategy::Hash,
-                            "tokenize" => MaskingStrategy::Tokenize,
-                            "remove" => MaskingStrategy::Remove,
-                            _ => MaskingStrategy::Redact,
+                        let case_sensitive: bool = match py_dict.get_item("case_sensitive")? {
+                            Some(val) => val.extract()?,
+                            None => false,
+                        };
+                        let multiline: bool = match py_dict.get_item("multiline")? {
+                            Some(val) => val.extract()?,
+                            None => false,
+                        };
+                        let dot_matches_newline: bool =
+                            match py_dict.get_item("dot_matches_newline")? {
+                                Some(val) => val.extract()?,
+                                None => false,
+                            };
+                        let priority: i32 = match py_dict.get_item("priority")? {
+                            Some(val) => val.extract()?,
+                            None => 0,
+                        };
+                        // Group names are strings, indexes may be given as int
+                        let secret_group = match py_dict.get_item("secret_group")? {
+                            Some(val) if val.is_none() => None,
+                            Some(val) => Some(match val.extract::<usize>() {
+                                Ok(index) => index.to_string(),
+                                Err(_) => val.extract()?,
+                            }),
+                            None => None,
                         };
 
                         config.custom_patterns.push(CustomPattern {
@@ -232,21 +1383,304 @@ impl PIIConfig {
                             description,
                             mask_strategy,
                             enabled,
+                            case_sensitive,
+                            priority,
+                            secret_group,
+                            multiline,
+                            dot_matches_newline,
                         });
                     }
                 }
             }
         }
 
+        // Extract per-type block overrides
+        if let Some(value) = dict.get_item("block_overrides")? {
+            let overrides: HashMap<String, bool> = value.extract()?;
+            for (type_str, block) in overrides {
+                config.block_overrides.insert(type_str.parse()?, block);
+            }
+        }
+
+        // Extract per-direction policies
+        if let Some(value) = dict.get_item("directions")? {
+            for (direction, policy) in value.cast::<PyDict>()?.iter() {
+                config.directions.insert(
+                    direction.extract::<String>()?.parse()?,
+                    DirectionPolicy::from_py_dict(policy.cast::<PyDict>()?)?,
+                );
+            }
+        }
+
+        // Extract per-type case sensitivity overrides
+        if let Some(value) = dict.get_item("case_sensitive")? {
+            let overrides: HashMap<String, bool> = value.extract()?;
+            for (type_str, sensitive) in overrides {
+                config
+                    .case_sensitive_overrides
+                    .insert(type_str.parse()?, sensitive);
+            }
+        }
+
+        // Extract per-type overlap priorities
+        if let Some(value) = dict.get_item("pattern_priority")? {
+            let priorities: HashMap<String, i32> = value.extract()?;
+            for (type_str, priority) in priorities {
+                config
+                    .pattern_priorities
+                    .insert(type_str.parse()?, priority);
+            }
+        }
+
+        // Extract word boundary mode
+        if let Some(value) = dict.get_item("boundary_mode")? {
+            config.boundary_mode = value.extract::<String>()?.parse()?;
+        }
+
+        // Extract Unicode normalization form
+        if let Some(value) = dict.get_item("unicode_normalization")? {
+            config.unicode_normalization = value.extract::<String>()?.to_lowercase().parse()?;
+        }
+
+        // Extract email domain lists
+        if let Some(value) = dict.get_item("email_exempt_domains")? {
+            config.email_exempt_domains = value.extract()?;
+        }
+        if let Some(value) = dict.get_item("email_always_mask_domains")? {
+            config.email_always_mask_domains = value.extract()?;
+        }
+
         // Extract whitelist patterns
         if let Some(value) = dict.get_item("whitelist_patterns")? {
             config.whitelist_patterns = value.extract()?;
         }
+        if let Some(value) = dict.get_item("whitelist_context")? {
+            for item in value.cast::<PyList>()?.iter() {
+                let py_dict = item.cast::<PyDict>()?;
+                let pattern: String = py_dict
+                    .get_item("pattern")?
+                    .ok_or(PIIError::MissingField("pattern"))?
+                    .extract()?;
+                let window = match py_dict.get_item("window")? {
+                    Some(val) => val.extract()?,
+                    None => DEFAULT_WHITELIST_WINDOW,
+                };
+                let types = match py_dict.get_item("types")? {
+                    Some(val) => val
+                        .extract::<Vec<String>>()?
+                        .iter()
+                        .map(|name| name.parse())
+                        .collect::<PIIResult<_>>()?,
+                    None => Vec::new(),
+                };
+                config.whitelist_context.push(ContextWhitelist {
+                    pattern,
+                    window,
+                    types,
+                });
+            }
+        }
+
+        // Extract per-tool overlays, each parsed as this dict with the
+        // overlay's keys on top
+        if let Some(value) = dict.get_item("tool_overlays")? {
+            for item in value.cast::<PyList>()?.iter() {
+                let py_dict = item.cast::<PyDict>()?;
+                let tools: String = py_dict
+                    .get_item("tools")?
+                    .ok_or(PIIError::MissingField("tools"))?
+                    .extract()?;
+                let overlay = py_dict
+                    .get_item("config")?
+                    .ok_or(PIIError::MissingField("config"))?;
+                let overlay = overlay.cast::<PyDict>()?;
+                if overlay.contains("tool_overlays")? {
+                    return Err(PIIError::InvalidValue {
+                        field: "tool_overlays",
+                        value: tools,
+                    }
+                    .into());
+                }
+                let merged = dict.copy()?;
+                merged.del_item("tool_overlays")?;
+                merged.update(overlay.as_mapping())?;
+                config.tool_overlays.push(ToolOverlay {
+                    tools,
+                    config: Self::from_py_dict(&merged)?,
+                });
+            }
+        }
 
         Ok(config)
     }
 }
 
+/// Expand `${VAR}` and `${VAR:-default}` references from the environment
+///
+/// `$${` produces a literal `${`. Used for config fields that may carry
+/// secrets so keys never have to appear literally in gateway YAML.
+pub fn interpolate_env(field: &'static str, value: &str) -> PIIResult<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if let Some(stripped) = rest.strip_prefix("$${") {
+            result.push_str("${");
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("${") {
+            let end = stripped
+                .find('}')
+                .ok_or(PIIError::InvalidInterpolation(field))?;
+            let (var, default) = match stripped[..end].split_once(":-") {
+                Some((var, default)) => (var, Some(default)),
+                None => (&stripped[..end], None),
+            };
+            match (std::env::var(var), default) {
+                (Ok(val), _) => result.push_str(&val),
+                (Err(_), Some(default)) => result.push_str(default),
+                (Err(_), None) => {
+                    return Err(PIIError::MissingEnvVar {
+                        field,
+                        var: var.to_string(),
+                    })
+                }
+            }
+            rest = &stripped[end + 1..];
+        } else {
+            result.push('$');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+/// Per-call overrides applied on top of a detector's compiled config
+///
+/// Only settings that don't require recompiling patterns can be overridden.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    pub redaction_text: Option<String>,
+    pub mask_strategy: Option<MaskingStrategy>,
+    pub disabled_types: HashSet<PIIType>,
+    /// Only these types are detected, when set
+    pub enabled_types: Option<HashSet<PIIType>>,
+    /// Stops the scan early when cancelled
+    pub cancel: Option<Arc<Cancellation>>,
+    /// Receives the bytes scanned and detections found as the scan goes
+    pub progress: Option<Arc<Progress>>,
+    /// Direction of the payload, selecting a `DirectionPolicy`
+    pub direction: Option<Direction>,
+    /// Tenant the scan's shared result cache entries are charged to
+    pub tenant: Option<Arc<str>>,
+    /// Synchronous `on_detection()` callbacks of the call, run once the
+    /// call drops its overrides
+    pub callbacks: Arc<CallbackBatch>,
+}
+
+impl ConfigOverrides {
+    /// Extract overrides from an optional Python dict
+    ///
+    /// Unknown keys are rejected so typos don't silently fall back to the
+    /// detector's policy.
+    pub fn from_py_dict(dict: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut overrides = Self::default();
+        let Some(dict) = dict else {
+            return Ok(overrides);
+        };
+
+        for (key, value) in dict.iter() {
+            let key: String = key.extract()?;
+            match key.as_str() {
+                "redaction_text" => overrides.redaction_text = Some(value.extract()?),
+                "mask_strategy" => {
+                    overrides.mask_strategy = Some(value.extract::<String>()?.parse()?)
+                }
+                "disabled_types" => {
+                    for type_str in value.extract::<Vec<String>>()? {
+                        overrides.disabled_types.insert(type_str.parse()?);
+                    }
+                }
+                "direction" => overrides.direction = Some(value.extract::<String>()?.parse()?),
+                "tenant" => overrides.tenant = Some(value.extract::<String>()?.into()),
+                _ => {
+                    return Err(PIIError::InvalidValue {
+                        field: "override",
+                        value: key,
+                    }
+                    .into())
+                }
+            }
+        }
+
+        Ok(overrides)
+    }
+
+    /// Fold in the policy of `direction` from `config`, under the settings
+    /// of the call itself
+    pub fn directed(mut self, config: &PIIConfig) -> Self {
+        let Some(policy) = self
+            .direction
+            .and_then(|direction| config.directions.get(&direction))
+        else {
+            return self;
+        };
+        self.disabled_types.extend(&policy.disabled_types);
+        if let Some(types) = &policy.enabled_types {
+            self.enabled_types = Some(match self.enabled_types.take() {
+                Some(enabled) => enabled.into_iter().filter(|t| types.contains(t)).collect(),
+                None => types.iter().copied().collect(),
+            });
+        }
+        self.mask_strategy = self.mask_strategy.or(policy.mask_strategy);
+        self
+    }
+
+    /// Whether detections of `pii_type` are suppressed for this call
+    pub fn is_disabled(&self, pii_type: PIIType) -> bool {
+        self.disabled_types.contains(&pii_type)
+            || self
+                .enabled_types
+                .as_ref()
+                .is_some_and(|enabled| !enabled.contains(&pii_type))
+    }
+
+    /// Whether the call was cancelled, or its progress callback failed,
+    /// and the scan should stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.is_cancelled())
+            || self
+                .progress
+                .as_ref()
+                .is_some_and(|progress| progress.is_stopped())
+    }
+
+    /// Report a scanned stretch of text to the progress callback, if any
+    pub fn advance(&self, bytes: usize, detections: usize) {
+        if let Some(progress) = &self.progress {
+            progress.advance(bytes, detections);
+        }
+    }
+
+    /// Config to use for masking, borrowing the base config when nothing changes
+    pub fn apply<'a>(&self, config: &'a PIIConfig) -> Cow<'a, PIIConfig> {
+        match &self.redaction_text {
+            Some(text) => {
+                let mut config = config.clone();
+                config.redaction_text = text.clone();
+                Cow::Owned(config)
+            }
+            None => Cow::Borrowed(config),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,5 +1699,220 @@ mod tests {
         assert!(config.detect_email);
         assert_eq!(config.redaction_text, "[REDACTED]");
         assert_eq!(config.default_mask_strategy, MaskingStrategy::Redact);
+        assert_eq!(config.max_matches_per_pattern, 10_000);
+        assert_eq!(config.match_limit_action, MatchLimitAction::Truncate);
+        assert_eq!(config.dob_mode, DobMode::Strict);
+    }
+
+    #[test]
+    fn test_validate_ip_anonymization_key() {
+        let mut config = PIIConfig {
+            ip_anonymization: IpAnonymization::PrefixPreserving,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(PIIError::MissingField("ip_anonymization_key"))
+        ));
+
+        config.ip_anonymization_key = "secret".into();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_policy_hash_is_stable_and_ignores_secrets() {
+        let mut a = PIIConfig::default();
+        a.block_overrides.insert(PIIType::Ssn, true);
+        a.block_overrides.insert(PIIType::Email, false);
+        let mut b = PIIConfig::default();
+        b.block_overrides.insert(PIIType::Email, false);
+        b.block_overrides.insert(PIIType::Ssn, true);
+        b.certificate_key = "secret".into();
+        assert_eq!(a.policy_hash(), b.policy_hash());
+
+        b.detect_email = false;
+        assert_ne!(a.policy_hash(), b.policy_hash());
+    }
+
+    #[test]
+    fn test_debug_redacts_keys() {
+        let config = PIIConfig {
+            certificate_key: "hunter2".into(),
+            ..Default::default()
+        };
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("certificate_key: SecretKey(<redacted>)"));
+        assert!(debug.contains("ip_anonymization_key: SecretKey(<unset>)"));
+        assert_eq!(config.certificate_key.expose(), "hunter2");
+    }
+
+    #[test]
+    fn test_entropy_path_scoping() {
+        let mut config = PIIConfig::default();
+        assert!(config.entropy_applies_to("anything"));
+
+        config.entropy_paths = vec!["headers".to_string()];
+        assert!(config.entropy_applies_to("headers"));
+        assert!(config.entropy_applies_to("headers.authorization"));
+        assert!(config.entropy_applies_to("headers[0]"));
+        assert!(!config.entropy_applies_to("headers_raw"));
+        assert!(!config.entropy_applies_to("body"));
+    }
+
+    #[test]
+    fn test_field_rules() {
+        let rule = |field: &str, types: Vec<PIIType>| FieldRule {
+            field: field.to_string(),
+            types,
+        };
+        let config = PIIConfig {
+            field_rules: vec![
+                rule("*.email", vec![PIIType::Email]),
+                rule("billing.**.card*", vec![PIIType::CreditCard]),
+                rule("notes", vec![]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.field_types("user.email"),
+            Some(&[PIIType::Email][..])
+        );
+        assert_eq!(
+            config.field_types("users[3].email"),
+            Some(&[PIIType::Email][..])
+        );
+        assert_eq!(config.field_types("a.b.email"), None);
+        assert_eq!(config.field_types("email"), None);
+        assert_eq!(
+            config.field_types("billing.card_number"),
+            Some(&[PIIType::CreditCard][..])
+        );
+        assert_eq!(
+            config.field_types("billing.methods[0].cards[1]"),
+            Some(&[PIIType::CreditCard][..])
+        );
+        assert_eq!(config.field_types("notes"), Some(&[][..]));
+        assert_eq!(config.field_types("notes.body"), None);
+    }
+
+    #[test]
+    fn test_scans_number() {
+        let mut config = PIIConfig::default();
+        assert!(!config.scans_number("123456789"));
+
+        config.scan_numbers = true;
+        assert!(config.scans_number("123456789"));
+        assert!(config.scans_number("-123456789"));
+        assert!(!config.scans_number("12345678"));
+        config.number_min_digits = 4;
+        assert!(config.scans_number("1234"));
+    }
+
+    #[test]
+    fn test_block_overrides() {
+        let mut config = PIIConfig::default();
+        config.block_overrides.insert(PIIType::Ssn, true);
+        assert!(config.blocks(PIIType::Ssn));
+        assert!(!config.blocks(PIIType::Email));
+
+        config.block_on_detection = true;
+        config.block_overrides.insert(PIIType::Email, false);
+        assert!(!config.blocks(PIIType::Email));
+        assert!(config.blocks(PIIType::Phone));
+    }
+
+    #[test]
+    fn test_direction_policies() {
+        let mut config = PIIConfig::default();
+        let mut request = DirectionPolicy {
+            block_on_detection: Some(false),
+            ..Default::default()
+        };
+        request.block_overrides.insert(PIIType::AwsKey, true);
+        config.directions.insert(Direction::Request, request);
+        config.directions.insert(
+            Direction::Response,
+            DirectionPolicy {
+                enabled_types: Some([PIIType::Email, PIIType::Ssn].into_iter().collect()),
+                mask_strategy: Some(MaskingStrategy::Redact),
+                ..Default::default()
+            },
+        );
+
+        assert!(config.blocks_in(PIIType::AwsKey, Some(Direction::Request)));
+        assert!(!config.blocks_in(PIIType::AwsKey, Some(Direction::Response)));
+        assert!(!config.blocks_in(PIIType::AwsKey, None));
+
+        let overrides = ConfigOverrides {
+            direction: Some(Direction::Response),
+            disabled_types: [PIIType::Ssn].into_iter().collect(),
+            ..Default::default()
+        }
+        .directed(&config);
+        assert!(!overrides.is_disabled(PIIType::Email));
+        assert!(overrides.is_disabled(PIIType::Ssn));
+        assert!(overrides.is_disabled(PIIType::Phone));
+        assert_eq!(overrides.mask_strategy, Some(MaskingStrategy::Redact));
+
+        let overrides = ConfigOverrides {
+            direction: Some(Direction::Notification),
+            ..Default::default()
+        }
+        .directed(&config);
+        assert!(!overrides.is_disabled(PIIType::Phone));
+    }
+
+    #[test]
+    fn test_parse_mask_strategy() {
+        assert_eq!(
+            "tokenize".parse::<MaskingStrategy>().unwrap(),
+            MaskingStrategy::Tokenize
+        );
+        assert!(matches!(
+            "scramble".parse::<MaskingStrategy>(),
+            Err(PIIError::InvalidStrategy(_))
+        ));
+    }
+
+    #[test]
+    fn test_overrides_apply() {
+        let config = PIIConfig::default();
+        let overrides = ConfigOverrides::default();
+        assert!(matches!(overrides.apply(&config), Cow::Borrowed(_)));
+
+        let overrides = ConfigOverrides {
+            redaction_text: Some("[X]".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(overrides.apply(&config).redaction_text, "[X]");
+        assert_eq!(config.redaction_text, "[REDACTED]");
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        std::env::set_var("PII_TEST_INTERPOLATE_KEY", "s3cret");
+
+        assert_eq!(
+            interpolate_env("key", "k=${PII_TEST_INTERPOLATE_KEY}!").unwrap(),
+            "k=s3cret!"
+        );
+        assert_eq!(
+            interpolate_env("key", "${PII_TEST_UNSET_VAR:-fallback}").unwrap(),
+            "fallback"
+        );
+        assert_eq!(
+            interpolate_env("key", "$${literal} $5").unwrap(),
+            "${literal} $5"
+        );
+        assert!(matches!(
+            interpolate_env("key", "${PII_TEST_UNSET_VAR}"),
+            Err(PIIError::MissingEnvVar { .. })
+        ));
+        assert!(matches!(
+            interpolate_env("key", "${OPEN"),
+            Err(PIIError::InvalidInterpolation("key"))
+        ));
     }
 }
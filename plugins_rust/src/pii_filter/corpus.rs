@@ -0,0 +1,261 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Corpus-based regression testing for PII Filter
+//
+// A corpus is a YAML file with an optional config block and a list of cases:
+//
+// ```yaml
+// config:
+//   detect_ip_address: false
+// cases:
+//   - name: dashed ssn
+//     text: "My SSN is 123-45-6789"
+//     detections:
+//       - type: ssn
+//         value: "123-45-6789"
+//     masked: "My SSN is ***-**-6789"
+// ```
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use super::config::PIIConfig;
+use super::detector::Detector;
+use super::error::{PIIError, PIIResult};
+use super::masking::mask_pii;
+
+/// Expected detection in a corpus case
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+pub struct ExpectedDetection {
+    #[serde(rename = "type")]
+    pub pii_type: String,
+    pub value: String,
+}
+
+/// Single test vector
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorpusCase {
+    #[serde(default)]
+    pub name: String,
+    pub text: String,
+    /// Exact set of expected detections (unchecked when omitted)
+    pub detections: Option<Vec<ExpectedDetection>>,
+    /// Expected masked output (unchecked when omitted)
+    pub masked: Option<String>,
+}
+
+/// Corpus file contents
+#[derive(Debug, Clone, Deserialize)]
+pub struct Corpus {
+    #[serde(default)]
+    pub config: PIIConfig,
+    pub cases: Vec<CorpusCase>,
+}
+
+/// Mismatch between a case and the engine output
+#[derive(Debug, Clone)]
+pub struct CaseFailure {
+    pub name: String,
+    pub missing: Vec<ExpectedDetection>,
+    pub unexpected: Vec<ExpectedDetection>,
+    pub expected_masked: Option<String>,
+    pub actual_masked: String,
+}
+
+/// Result of running a corpus
+#[derive(Debug, Clone, Default)]
+pub struct CorpusReport {
+    pub total: usize,
+    pub failures: Vec<CaseFailure>,
+}
+
+impl CorpusReport {
+    pub fn passed(&self) -> usize {
+        self.total - self.failures.len()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Load a corpus from a YAML file
+pub fn load_corpus(path: &Path) -> PIIResult<Corpus> {
+    let contents = std::fs::read_to_string(path).map_err(|e| PIIError::ReadFile {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    serde_yaml::from_str(&contents).map_err(|e| PIIError::ParseFile {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Run every case of a corpus through detection and masking
+pub fn run_corpus(corpus: &Corpus) -> PIIResult<CorpusReport> {
+    let detector = Detector::from_config(corpus.config.clone())?;
+    let mut report = CorpusReport {
+        total: corpus.cases.len(),
+        ..Default::default()
+    };
+
+    for (idx, case) in corpus.cases.iter().enumerate() {
+        let detections = detector.detect_internal(&case.text);
+        let actual_masked = mask_pii(&case.text, &detections, &corpus.config).into_owned();
+
+        let actual: BTreeSet<ExpectedDetection> = detections
+            .iter()
+            .flat_map(|(pii_type, items)| {
+                items.iter().map(move |d| ExpectedDetection {
+                    pii_type: pii_type.as_str().to_string(),
+                    value: d.value.clone(),
+                })
+            })
+            .collect();
+
+        let (missing, unexpected) = match &case.detections {
+            Some(expected) => {
+                let expected: BTreeSet<ExpectedDetection> = expected.iter().cloned().collect();
+                (
+                    expected.difference(&actual).cloned().collect(),
+                    actual.difference(&expected).cloned().collect(),
+                )
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+        let mask_mismatch = case
+            .masked
+            .as_ref()
+            .is_some_and(|expected| *expected != actual_masked);
+
+        if !missing.is_empty() || !unexpected.is_empty() || mask_mismatch {
+            report.failures.push(CaseFailure {
+                name: if case.name.is_empty() {
+                    format!("case[{}]", idx)
+                } else {
+                    case.name.clone()
+                },
+                missing,
+                unexpected,
+                expected_masked: case.masked.clone(),
+                actual_masked,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Verify a YAML corpus of test vectors against the engine
+///
+/// # Arguments
+/// * `path` - Path to the corpus YAML file
+///
+/// # Returns
+/// Dictionary with `total`, `passed`, `failed` and a `failures` list; each
+/// failure carries `name`, `missing`, `unexpected`, `expected_masked` and
+/// `actual_masked`
+///
+/// # Raises
+/// `PIIError` when the file cannot be read or parsed, `ConfigError` for an
+/// invalid `config` block
+#[pyfunction]
+pub fn verify_corpus(py: Python, path: &str) -> PyResult<Py<PyAny>> {
+    let corpus = load_corpus(Path::new(path))?;
+    let report = run_corpus(&corpus)?;
+
+    let detections_to_py = |items: &[ExpectedDetection]| -> PyResult<Bound<'_, PyList>> {
+        let py_list = PyList::empty(py);
+        for item in items {
+            let item_dict = PyDict::new(py);
+            item_dict.set_item("type", &item.pii_type)?;
+            item_dict.set_item("value", &item.value)?;
+            py_list.append(item_dict)?;
+        }
+        Ok(py_list)
+    };
+
+    let failures = PyList::empty(py);
+    for failure in &report.failures {
+        let item_dict = PyDict::new(py);
+        item_dict.set_item("name", &failure.name)?;
+        item_dict.set_item("missing", detections_to_py(&failure.missing)?)?;
+        item_dict.set_item("unexpected", detections_to_py(&failure.unexpected)?)?;
+        item_dict.set_item("expected_masked", &failure.expected_masked)?;
+        item_dict.set_item("actual_masked", &failure.actual_masked)?;
+        failures.append(item_dict)?;
+    }
+
+    let py_dict = PyDict::new(py);
+    py_dict.set_item("total", report.total)?;
+    py_dict.set_item("passed", report.passed())?;
+    py_dict.set_item("failed", report.failures.len())?;
+    py_dict.set_item("failures", failures)?;
+    Ok(py_dict.into_any().unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_corpus() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+        let mut total = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "yaml") {
+                continue;
+            }
+            let report = run_corpus(&load_corpus(&path).unwrap()).unwrap();
+
+            assert!(
+                report.is_success(),
+                "{} of {} cases in {} failed: {:#?}",
+                report.failures.len(),
+                report.total,
+                path.display(),
+                report.failures
+            );
+            total += report.total;
+        }
+        assert!(total >= 200, "bundled corpus shrank to {} cases", total);
+    }
+
+    #[test]
+    fn test_load_corpus_errors() {
+        let missing = load_corpus(Path::new("/nonexistent/corpus.yaml"));
+        assert!(matches!(missing, Err(PIIError::ReadFile { .. })));
+
+        let path = std::env::temp_dir().join(format!("corpus-{}.yaml", std::process::id()));
+        std::fs::write(&path, "cases: not-a-list").unwrap();
+        let invalid = load_corpus(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(invalid, Err(PIIError::ParseFile { .. })));
+    }
+
+    #[test]
+    fn test_corpus_reports_mismatch() {
+        let corpus: Corpus = serde_yaml::from_str(
+            r#"
+cases:
+  - name: wrong expectation
+    text: "Contact john@example.com"
+    detections:
+      - type: ssn
+        value: "123-45-6789"
+"#,
+        )
+        .unwrap();
+
+        let report = run_corpus(&corpus).unwrap();
+
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].missing[0].pii_type, "ssn");
+        assert_eq!(report.failures[0].unexpected[0].pii_type, "email");
+    }
+}
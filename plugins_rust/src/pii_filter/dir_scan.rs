@@ -0,0 +1,227 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Directory tree scanning for PII Filter
+//
+// Files are found with the `ignore` crate's parallel walker, so `.gitignore`,
+// `.ignore` and hidden-file rules apply exactly as they do for ripgrep, and
+// each file is scanned on the walker thread that found it. Progress and
+// cancellation come in through the `ConfigOverrides` of the scan; once it is
+// cancelled no further files are started.
+
+use ignore::overrides::OverrideBuilder;
+use ignore::{WalkBuilder, WalkState};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::config::{ConfigOverrides, PIIType};
+use super::detector::{Detection, Detector};
+use super::error::{PIIError, PIIResult};
+
+/// Leading bytes checked for NUL to tell binary files from text
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Detections found in one file
+pub struct FileReport {
+    pub path: PathBuf,
+    pub bytes: usize,
+    pub detections: BTreeMap<PIIType, Vec<Detection>>,
+}
+
+/// Aggregated results of a directory scan
+#[derive(Default)]
+pub struct DirReport {
+    pub files_scanned: usize,
+    /// Files with at least one detection, sorted by path
+    pub files: Vec<FileReport>,
+    /// Files that could not be scanned, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl DirReport {
+    /// Detection counts per type across all files
+    pub fn totals(&self) -> BTreeMap<PIIType, usize> {
+        let mut totals = BTreeMap::new();
+        for file in &self.files {
+            for (pii_type, items) in &file.detections {
+                *totals.entry(*pii_type).or_default() += items.len();
+            }
+        }
+        totals
+    }
+}
+
+/// Scan a single file; binary files are reported as an error
+///
+/// Invalid UTF-8 is replaced before scanning, so offsets refer to the
+/// lossily decoded text.
+pub fn scan_file(
+    detector: &Detector,
+    path: &Path,
+    overrides: &ConfigOverrides,
+) -> Result<FileReport, String> {
+    let bytes = fs::read(path).map_err(|err| err.to_string())?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return Err("binary file".to_string());
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(FileReport {
+        path: path.to_path_buf(),
+        bytes: bytes.len(),
+        detections: detector.detect_with_overrides(&text, overrides),
+    })
+}
+
+/// Scan every file under `root` matching `globs` (all files when empty)
+///
+/// Globs use gitignore syntax; a leading `!` excludes matches.
+pub fn scan_dir(
+    detector: &Detector,
+    root: &Path,
+    globs: &[String],
+    overrides: &ConfigOverrides,
+) -> PIIResult<DirReport> {
+    if !root.is_dir() {
+        return Err(PIIError::InvalidValue {
+            field: "path",
+            value: root.display().to_string(),
+        });
+    }
+
+    let mut filters = OverrideBuilder::new(root);
+    for glob in globs {
+        filters.add(glob).map_err(|_| PIIError::InvalidValue {
+            field: "globs",
+            value: glob.clone(),
+        })?;
+    }
+    let filters = filters.build().map_err(|err| PIIError::InvalidValue {
+        field: "globs",
+        value: err.to_string(),
+    })?;
+
+    let report = Mutex::new(DirReport::default());
+    WalkBuilder::new(root)
+        .overrides(filters)
+        .build_parallel()
+        .run(|| {
+            Box::new(|entry| {
+                if overrides.is_cancelled() {
+                    return WalkState::Quit;
+                }
+                let outcome = match entry {
+                    Ok(entry) if !entry.file_type().is_some_and(|t| t.is_file()) => {
+                        return WalkState::Continue
+                    }
+                    Ok(entry) => scan_file(detector, entry.path(), overrides)
+                        .map_err(|err| (entry.path().to_path_buf(), err)),
+                    Err(err) => Err((root.to_path_buf(), err.to_string())),
+                };
+
+                let mut report = report.lock().unwrap_or_else(|e| e.into_inner());
+                match outcome {
+                    Ok(file) => {
+                        report.files_scanned += 1;
+                        if !file.detections.is_empty() {
+                            report.files.push(file);
+                        }
+                    }
+                    Err(skipped) => report.skipped.push(skipped),
+                }
+                WalkState::Continue
+            })
+        });
+
+    let mut report = report.into_inner().unwrap_or_else(|e| e.into_inner());
+    report.files.sort_by(|a, b| a.path.cmp(&b.path));
+    report.skipped.sort();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::PIIConfig;
+    use crate::pii_filter::progress::Progress;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_scan_dir_filters_and_aggregates() {
+        let root = std::env::temp_dir().join(format!("pii_scan_dir_{}", std::process::id()));
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("a.txt"), "mail john@example.com").unwrap();
+        fs::write(
+            root.join("nested/b.txt"),
+            "SSN 123-45-6789, jane@example.com",
+        )
+        .unwrap();
+        fs::write(root.join("nested/c.log"), "SSN 987-65-4321").unwrap();
+        fs::write(root.join("clean.txt"), "nothing here").unwrap();
+        fs::write(root.join("blob.txt"), b"\x00\x01john@example.com").unwrap();
+
+        let detector = Detector::from_config(PIIConfig::default()).unwrap();
+        let report = scan_dir(
+            &detector,
+            &root,
+            &["*.txt".to_string()],
+            &ConfigOverrides::default(),
+        )
+        .unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(report.files_scanned, 3);
+        let paths: Vec<_> = report
+            .files
+            .iter()
+            .map(|file| file.path.strip_prefix(&root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("a.txt"), PathBuf::from("nested/b.txt")]
+        );
+        assert_eq!(report.totals()[&PIIType::Email], 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].1, "binary file");
+    }
+
+    #[test]
+    fn test_scan_file_reports_progress() {
+        let path = std::env::temp_dir().join(format!("pii_scan_progress_{}", std::process::id()));
+        fs::write(&path, "mail john@example.com ".repeat(100)).unwrap();
+
+        let detector = Detector::from_config(PIIConfig {
+            scan_window_bytes: 512,
+            scan_window_overlap: 64,
+            ..Default::default()
+        })
+        .unwrap();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&calls);
+        let progress = Progress::new(1000, move |bytes, detections| {
+            seen.lock().unwrap().push((bytes, detections));
+            true
+        })
+        .unwrap();
+        let overrides = ConfigOverrides {
+            progress: Some(Arc::new(progress)),
+            ..Default::default()
+        };
+        let report = scan_file(&detector, &path, &overrides).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].0 >= 1000 && calls[0].0 < 2200);
+        assert_eq!(calls[1], (2200, 100));
+        assert_eq!(report.detections[&PIIType::Email].len(), 100);
+    }
+
+    #[test]
+    fn test_scan_dir_rejects_missing_root() {
+        let detector = Detector::from_config(PIIConfig::default()).unwrap();
+        let missing = Path::new("/nonexistent/pii_scan_dir");
+        assert!(scan_dir(&detector, missing, &[], &ConfigOverrides::default()).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Error types for PII Filter
+
+use pyo3::PyErr;
+use thiserror::Error;
+
+/// Python exception hierarchy exposed as `plugins_rust.*`
+///
+/// ```text
+/// PIIError(Exception)
+/// ├── ConfigError
+/// │   ├── PatternCompileError
+/// │   └── SignatureError
+/// ├── BlockedContentError
+/// │   └── PayloadLimitError
+/// ├── PoolOverloadedError
+/// └── ScanCancelledError
+/// ```
+pub mod exceptions {
+    use pyo3::create_exception;
+    use pyo3::exceptions::PyException;
+
+    create_exception!(
+        plugins_rust,
+        PIIError,
+        PyException,
+        "Base class for all plugins_rust PII errors."
+    );
+    create_exception!(
+        plugins_rust,
+        ConfigError,
+        PIIError,
+        "Invalid detector configuration."
+    );
+    create_exception!(
+        plugins_rust,
+        PatternCompileError,
+        ConfigError,
+        "A detection, custom or whitelist pattern failed to compile."
+    );
+    create_exception!(
+        plugins_rust,
+        SignatureError,
+        ConfigError,
+        "A pattern pack or config file was unsigned or its signature did not verify."
+    );
+    create_exception!(
+        plugins_rust,
+        BlockedContentError,
+        PIIError,
+        "Content was blocked because it contains PII configured to block."
+    );
+    create_exception!(
+        plugins_rust,
+        PayloadLimitError,
+        BlockedContentError,
+        "Payload rejected by the payload guard for exceeding a size limit."
+    );
+    create_exception!(
+        plugins_rust,
+        PoolOverloadedError,
+        PIIError,
+        "A detector pool queue was full and the submission was rejected or shed."
+    );
+    create_exception!(
+        plugins_rust,
+        ScanCancelledError,
+        PIIError,
+        "A scan was stopped early through its CancelToken."
+    );
+}
+
+/// Errors raised while building or running a PII detector
+#[derive(Debug, Error)]
+pub enum PIIError {
+    /// A detection or custom pattern failed to compile
+    #[error("Failed to compile pattern '{pattern}': {source}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    /// A whitelist pattern failed to compile
+    #[error("Invalid whitelist pattern '{pattern}': {source}")]
+    InvalidWhitelist {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    /// Unknown masking strategy name
+    #[error("Invalid mask strategy '{0}'")]
+    InvalidStrategy(String),
+
+    /// Unknown value for an enumerated config option
+    #[error("Invalid {field} '{value}'")]
+    InvalidValue { field: &'static str, value: String },
+
+    /// `${VAR}` reference to an unset environment variable
+    #[error("Environment variable '{var}' referenced by '{field}' is not set")]
+    MissingEnvVar { field: &'static str, var: String },
+
+    /// Malformed `${...}` reference in a config value
+    #[error("Unterminated '${{' in '{0}'")]
+    InvalidInterpolation(&'static str),
+
+    /// Required field absent from a config entry or detection
+    #[error("Missing '{0}' field")]
+    MissingField(&'static str),
+
+    /// Detection span cannot be applied to the text
+    #[error("{0}")]
+    InvalidDetection(String),
+
+    /// Arrow record batch input that cannot be scanned
+    #[error("Invalid record batch: {0}")]
+    InvalidBatch(String),
+
+    /// `mask_into` buffer that cannot be masked in place
+    #[error("Invalid buffer: {0}")]
+    InvalidBuffer(String),
+
+    /// `process_json` input that is not valid JSON
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(String),
+
+    /// Detection results could not be encoded
+    #[error("Failed to serialize detections: {0}")]
+    Serialization(String),
+
+    /// Payload field that is not valid text under the strict encoding policy
+    #[error("Field '{path}' is not valid UTF-8 or BOM-marked UTF-16")]
+    InvalidEncoding { path: String },
+
+    /// Masked output breaks a masking invariant
+    #[error("Masking invariant violated: {0}")]
+    InvariantViolation(String),
+
+    /// Detector pool queue at capacity when a submission arrived
+    #[error("Detector pool queue is full ({capacity} pending)")]
+    QueueFull { capacity: usize },
+
+    /// Queued submission dropped to make room for a newer one
+    #[error("Shed from a full detector pool queue")]
+    Shed,
+
+    /// Scan stopped early through its cancellation token or a signal
+    #[error("Scan cancelled")]
+    Cancelled,
+
+    /// Pattern pack or config file without a valid signature
+    #[error("Signature rejected: {0}")]
+    InvalidSignature(String),
+
+    /// Policy file could not be read
+    #[error("Failed to read '{path}': {message}")]
+    ReadFile { path: String, message: String },
+
+    /// File contents that do not parse as the expected format
+    #[error("Failed to parse '{path}': {message}")]
+    ParseFile { path: String, message: String },
+
+    /// Pattern pack could not be downloaded
+    #[error("Failed to fetch pattern pack: {0}")]
+    PackFetch(String),
+
+    /// Nested payload deeper or larger than `max_depth` / `max_nodes`
+    #[error("Payload rejected: {limit} exceeded at '{path}' (max {max})")]
+    NestingLimit {
+        limit: &'static str,
+        max: usize,
+        path: String,
+    },
+}
+
+pub type PIIResult<T> = Result<T, PIIError>;
+
+impl From<PIIError> for PyErr {
+    fn from(err: PIIError) -> PyErr {
+        match err {
+            PIIError::InvalidPattern { .. } | PIIError::InvalidWhitelist { .. } => {
+                exceptions::PatternCompileError::new_err(err.to_string())
+            }
+            PIIError::InvalidStrategy(_)
+            | PIIError::InvalidValue { .. }
+            | PIIError::MissingEnvVar { .. }
+            | PIIError::InvalidInterpolation(_)
+            | PIIError::MissingField(_) => exceptions::ConfigError::new_err(err.to_string()),
+            PIIError::InvalidDetection(_)
+            | PIIError::InvalidBatch(_)
+            | PIIError::InvalidJson(_)
+            | PIIError::InvalidBuffer(_)
+            | PIIError::Serialization(_)
+            | PIIError::InvalidEncoding { .. }
+            | PIIError::InvariantViolation(_)
+            | PIIError::ReadFile { .. }
+            | PIIError::ParseFile { .. }
+            | PIIError::PackFetch(_) => exceptions::PIIError::new_err(err.to_string()),
+            PIIError::InvalidSignature(_) => exceptions::SignatureError::new_err(err.to_string()),
+            PIIError::QueueFull { .. } | PIIError::Shed => {
+                exceptions::PoolOverloadedError::new_err(err.to_string())
+            }
+            PIIError::NestingLimit { .. } => {
+                exceptions::PayloadLimitError::new_err(err.to_string())
+            }
+            PIIError::Cancelled => exceptions::ScanCancelledError::new_err(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_messages() {
+        let pattern = "(".to_string();
+        let source = regex::Regex::new(&pattern).unwrap_err();
+        let err = PIIError::InvalidPattern { pattern, source };
+        assert!(err.to_string().starts_with("Failed to compile pattern '('"));
+
+        let err = PIIError::InvalidStrategy("scramble".to_string());
+        assert_eq!(err.to_string(), "Invalid mask strategy 'scramble'");
+    }
+}
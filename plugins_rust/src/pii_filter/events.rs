@@ -0,0 +1,149 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Detection event queue for PII Filter
+//
+// Every detection can be recorded as an event (type, field path, masked
+// value, time; never the raw value) on a bounded channel that scanning threads
+// feed without blocking. A background task drains it in batches and ships the
+// events to a SIEM, off the request path. A full queue drops new events and
+// counts them.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::config::PIIType;
+use super::detector::Detection;
+use super::masking::MaskEdit;
+
+/// One detection, as shipped to a SIEM
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionEvent {
+    pub pii_type: PIIType,
+    pub subtype: Option<String>,
+    /// Field path within a payload, empty for plain texts
+    pub path: String,
+    /// The value as masked output shows it
+    pub masked: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: f64,
+}
+
+/// Bounded multi-producer queue of detection events
+pub struct EventQueue {
+    sender: SyncSender<DetectionEvent>,
+    receiver: Mutex<Receiver<DetectionEvent>>,
+    dropped: AtomicU64,
+}
+
+impl EventQueue {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        Self {
+            sender,
+            receiver: Mutex::new(receiver),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue an event per detection of one scan
+    ///
+    /// `edits` is the masking planned for the scan (in text order), so each
+    /// event carries the replacement the masked output shows for its value,
+    /// with the `{index}` or token of a memo shared across fields and items.
+    /// Detections no edit covers were not masked and are not recorded.
+    pub fn record(
+        &self,
+        detections: &BTreeMap<PIIType, Vec<Detection>>,
+        path: &str,
+        edits: &[MaskEdit],
+    ) {
+        if detections.is_empty() {
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64());
+        for (pii_type, items) in detections {
+            for detection in items {
+                // An overlapped detection shows as the edit that covers it
+                let next = edits.partition_point(|edit| edit.end <= detection.start);
+                let Some(edit) = edits
+                    .get(next)
+                    .filter(|edit| edit.start <= detection.start && detection.end <= edit.end)
+                else {
+                    continue;
+                };
+                let event = DetectionEvent {
+                    pii_type: *pii_type,
+                    subtype: detection.subtype.clone(),
+                    path: path.to_string(),
+                    masked: edit.replacement.clone(),
+                    timestamp,
+                };
+                if let Err(TrySendError::Full(_)) = self.sender.try_send(event) {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Take up to `max` queued events (all when `None`), oldest first
+    pub fn drain(&self, max: Option<usize>) -> Vec<DetectionEvent> {
+        let receiver = self.receiver.lock().unwrap_or_else(PoisonError::into_inner);
+        let events = receiver.try_iter();
+        match max {
+            Some(max) => events.take(max).collect(),
+            None => events.collect(),
+        }
+    }
+
+    /// Events dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::{MaskingStrategy, PIIConfig};
+    use crate::pii_filter::masking::{plan_mask_with_memo, MaskMemo};
+
+    #[test]
+    fn test_queue_is_bounded() {
+        let queue = EventQueue::new(2);
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::Email,
+            vec![
+                Detection::new("a@example.com", 0, 13, MaskingStrategy::Partial),
+                Detection::new("b@example.com", 20, 33, MaskingStrategy::Partial),
+                Detection::new("c@example.com", 40, 53, MaskingStrategy::Partial),
+            ],
+        );
+        let text = format!(
+            "{:<20}{:<20}c@example.com",
+            "a@example.com", "b@example.com"
+        );
+        let edits = plan_mask_with_memo(
+            &text,
+            &detections,
+            &PIIConfig::default(),
+            &mut MaskMemo::new(),
+        );
+        queue.record(&detections, "users[0].email", &edits);
+
+        assert_eq!(queue.dropped(), 1);
+        let first = queue.drain(Some(1));
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].pii_type, PIIType::Email);
+        assert_eq!(first[0].path, "users[0].email");
+        assert_eq!(first[0].masked, "***@example.com");
+        assert_eq!(queue.drain(None).len(), 1);
+        assert!(queue.drain(None).is_empty());
+    }
+}
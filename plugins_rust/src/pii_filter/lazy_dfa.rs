@@ -0,0 +1,117 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Lazy DFA pattern set backend for PII Filter
+//
+// The default `RegexSet` and per-pattern `Regex` each keep a lazy DFA cache of
+// up to 2 MiB per thread, so a detector with 60 patterns on a busy thread pool
+// can hold hundreds of MiB of transition tables. The `lazy_dfa` backend picks
+// candidate patterns with one `regex-automata` hybrid DFA over the whole set
+// and caps every cache at `lazy_dfa_cache_bytes`; caches are cleared and
+// rebuilt when full, which costs a little per-scan time instead of memory.
+
+use regex_automata::hybrid::dfa::{Cache, DFA};
+use regex_automata::util::pool::Pool;
+use regex_automata::{Input, MatchKind, PatternSet};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::error::{PIIError, PIIResult};
+
+type CacheFn = Box<dyn Fn() -> Cache + Send + Sync + UnwindSafe + RefUnwindSafe>;
+
+/// Lazy DFA over the whole pattern set, reporting which patterns match
+pub struct LazySet {
+    dfa: DFA,
+    caches: Pool<Cache, CacheFn>,
+    cache_capacity: usize,
+    /// Largest cache seen after a search, in bytes
+    peak_cache_bytes: AtomicUsize,
+}
+
+impl LazySet {
+    /// Build the set DFA for `patterns`, with caches capped at `cache_capacity`
+    pub fn build(patterns: &[String], cache_capacity: usize) -> PIIResult<Self> {
+        let dfa = DFA::builder()
+            .configure(
+                DFA::config()
+                    .match_kind(MatchKind::All)
+                    .unicode_word_boundary(true)
+                    .cache_capacity(cache_capacity)
+                    // Huge sets get the minimum cache instead of failing
+                    .skip_cache_capacity_check(true),
+            )
+            .build_many(patterns)
+            .map_err(|err| PIIError::InvalidValue {
+                field: "regex_backend",
+                value: format!("lazy_dfa ({})", err),
+            })?;
+        let template = dfa.clone();
+        let caches = Pool::new(Box::new(move || template.create_cache()) as CacheFn);
+        Ok(Self {
+            dfa,
+            caches,
+            cache_capacity,
+            peak_cache_bytes: AtomicUsize::new(0),
+        })
+    }
+
+    /// Indices of the patterns matching anywhere in `text`, or `None` when
+    /// the DFA gives up (non-ASCII text next to a Unicode `\b`)
+    pub fn matches(&self, text: &str) -> Option<Vec<usize>> {
+        let mut set = PatternSet::new(self.dfa.pattern_len());
+        let mut cache = self.caches.get();
+        let result =
+            self.dfa
+                .try_which_overlapping_matches(&mut cache, &Input::new(text), &mut set);
+        self.peak_cache_bytes
+            .fetch_max(cache.memory_usage(), Ordering::Relaxed);
+        result.ok()?;
+        Some(set.iter().map(|id| id.as_usize()).collect())
+    }
+
+    /// Heap held by the DFA and its NFA, excluding caches
+    pub fn memory_usage(&self) -> usize {
+        self.dfa.memory_usage() + self.dfa.get_nfa().memory_usage()
+    }
+
+    pub fn cache_capacity(&self) -> usize {
+        self.cache_capacity
+    }
+
+    pub fn peak_cache_bytes(&self) -> usize {
+        self.peak_cache_bytes.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_set_matches() {
+        let patterns = vec![
+            r"\bEMP-\d{6}\b".to_string(),
+            r"(?i)\bghp_[0-9a-z]{36}\b".to_string(),
+            r"\d{3}-\d{2}-\d{4}".to_string(),
+        ];
+        let set = LazySet::build(&patterns, 64 << 10).unwrap();
+
+        let text = "id EMP-123456 token GHP_abcdefghijklmnopqrstuvwxyz0123456789";
+        assert_eq!(set.matches(text), Some(vec![0, 1]));
+        assert_eq!(set.matches("ssn 123-45-6789"), Some(vec![2]));
+        assert_eq!(set.matches("nothing here"), Some(vec![]));
+        // Unicode \b next to non-ASCII text falls back to the RegexSet
+        assert_eq!(set.matches("é EMP-123456"), None);
+        assert!(set.peak_cache_bytes() > 0);
+        assert!(set.peak_cache_bytes() <= set.cache_capacity() * 2);
+
+        assert!(matches!(
+            LazySet::build(&["(".to_string()], 64 << 10),
+            Err(PIIError::InvalidValue {
+                field: "regex_backend",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Bridge from Rust `log` records to Python's `logging` module
+//
+// Records go to the logger named after their target with `::` replaced by
+// `.` (`plugins_rust::pii_filter::detector` logs to
+// `plugins_rust.pii_filter.detector`), so handlers and levels configured on
+// `plugins_rust.pii_filter` apply. Key-values attached to a record are passed
+// as `extra` fields. Forwarding takes the GIL, so records are rate limited
+// and the Rust-side level follows the effective level of the `plugins_rust`
+// logger as of the last `init_logging()` call (made once on import).
+
+use log::kv::{self, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use super::config::PIIType;
+use super::detector::Detection;
+
+/// Records forwarded per second by default, with a burst of the same size
+pub const DEFAULT_RATE: u32 = 100;
+
+static BRIDGE: Lazy<PyLogBridge> = Lazy::new(|| PyLogBridge {
+    bucket: Mutex::new(TokenBucket::new(DEFAULT_RATE, Instant::now())),
+});
+
+/// Whether the bridge is the process's logger; another logger installed
+/// first (e.g. by a host embedding this crate) keeps the records
+static INSTALLED: Lazy<bool> = Lazy::new(|| log::set_logger(&*BRIDGE).is_ok());
+
+thread_local! {
+    /// Set while a record is being handed to Python, so records raised by
+    /// Python handlers calling back into Rust are dropped, not recursed on
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Token bucket refilled at `rate` tokens per second, holding at most `rate`
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: u32,
+    tokens: f64,
+    last: Instant,
+    /// Records dropped since the last one let through
+    dropped: u64,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u32, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: f64::from(rate),
+            last: now,
+            dropped: 0,
+        }
+    }
+
+    /// Take a token at `now`; `Some(dropped)` lets the record through along
+    /// with the number of records dropped before it, `None` drops it
+    pub fn take(&mut self, now: Instant) -> Option<u64> {
+        let refill = now.duration_since(self.last).as_secs_f64() * f64::from(self.rate);
+        self.tokens = (self.tokens + refill).min(f64::from(self.rate));
+        self.last = now;
+        if self.tokens < 1.0 {
+            self.dropped += 1;
+            return None;
+        }
+        self.tokens -= 1.0;
+        Some(std::mem::take(&mut self.dropped))
+    }
+}
+
+struct PyLogBridge {
+    bucket: Mutex<TokenBucket>,
+}
+
+impl Log for PyLogBridge {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) || FORWARDING.with(Cell::get) {
+            return;
+        }
+        let Some(dropped) = self
+            .bucket
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take(Instant::now())
+        else {
+            return;
+        };
+
+        FORWARDING.with(|flag| flag.set(true));
+        // Nothing to report to when the interpreter is shutting down, and a
+        // failing handler must not take the scan down with it
+        Python::try_attach(|py| {
+            let logger = logger_name(record.target());
+            if dropped > 0 {
+                let message = format!("{} log records dropped by rate limiting", dropped);
+                let _ = forward(py, &logger, Level::Warn, &message, None);
+            }
+            let _ = forward(
+                py,
+                &logger,
+                record.level(),
+                &record.args().to_string(),
+                Some(record.key_values()),
+            );
+        });
+        FORWARDING.with(|flag| flag.set(false));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Python logger name for a Rust log target
+pub fn logger_name(target: &str) -> String {
+    target.replace("::", ".")
+}
+
+/// Python `logging` level number for a Rust level
+pub fn python_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => 5,
+    }
+}
+
+/// Most verbose Rust level a Python effective level lets through
+pub fn level_filter(python_level: i64) -> LevelFilter {
+    match python_level {
+        level if level > 40 => LevelFilter::Off,
+        level if level > 30 => LevelFilter::Error,
+        level if level > 20 => LevelFilter::Warn,
+        level if level > 10 => LevelFilter::Info,
+        level if level > 5 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+fn forward(
+    py: Python,
+    logger: &str,
+    level: Level,
+    message: &str,
+    key_values: Option<&dyn kv::Source>,
+) -> PyResult<()> {
+    let logger = py.import("logging")?.call_method1("getLogger", (logger,))?;
+    let kwargs = PyDict::new(py);
+    if let Some(source) = key_values {
+        let extra = PyDict::new(py);
+        let _ = source.visit(&mut ExtraVisitor(&extra));
+        kwargs.set_item("extra", extra)?;
+    }
+    logger.call_method("log", (python_level(level), message), Some(&kwargs))?;
+    Ok(())
+}
+
+/// Copies record key-values into a Python `extra` dict
+struct ExtraVisitor<'a, 'py>(&'a Bound<'py, PyDict>);
+
+impl<'kvs> VisitSource<'kvs> for ExtraVisitor<'_, '_> {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: kv::Value<'kvs>) -> Result<(), kv::Error> {
+        let result = if let Some(number) = value.to_u64() {
+            self.0.set_item(key.as_str(), number)
+        } else if let Some(number) = value.to_i64() {
+            self.0.set_item(key.as_str(), number)
+        } else if let Some(number) = value.to_f64() {
+            self.0.set_item(key.as_str(), number)
+        } else if let Some(flag) = value.to_bool() {
+            self.0.set_item(key.as_str(), flag)
+        } else {
+            self.0.set_item(key.as_str(), value.to_string())
+        };
+        result.map_err(|_| kv::Error::msg("extra field not settable"))
+    }
+}
+
+/// Install the bridge (first call only) and sync it with Python's logging
+///
+/// The Rust-side level is read from the effective level of the
+/// `plugins_rust` logger, so call again after changing that level.
+pub fn install(py: Python, max_records_per_sec: u32) -> PyResult<()> {
+    if !*INSTALLED {
+        return Ok(());
+    }
+    let level: i64 = py
+        .import("logging")?
+        .call_method1("getLogger", ("plugins_rust",))?
+        .call_method0("getEffectiveLevel")?
+        .extract()?;
+    log::set_max_level(level_filter(level));
+    *BRIDGE.bucket.lock().unwrap_or_else(PoisonError::into_inner) =
+        TokenBucket::new(max_records_per_sec.max(1), Instant::now());
+    Ok(())
+}
+
+/// Emit an INFO summary of a scan's detections (types and counts, never values)
+pub fn log_detections(
+    detections: &BTreeMap<PIIType, Vec<Detection>>,
+    bytes: usize,
+    elapsed: Duration,
+) {
+    if !log::log_enabled!(Level::Info) {
+        return;
+    }
+    let counts: Vec<String> = detections
+        .iter()
+        .map(|(pii_type, items)| format!("{}={}", pii_type.as_str(), items.len()))
+        .collect();
+    let total: usize = detections.values().map(Vec::len).sum();
+    let counts = counts.join(",");
+    log::info!(
+        pii_counts = counts.as_str(),
+        pii_total = total,
+        bytes = bytes,
+        elapsed_us = elapsed.as_micros() as u64;
+        "Detected {} PII values ({})",
+        total,
+        counts
+    );
+}
+
+/// Route Rust log records to Python's `logging` module
+///
+/// Called on import; call again after changing the level of the
+/// `plugins_rust` logger so Rust stops formatting records that would be
+/// filtered out (or starts emitting newly enabled ones).
+///
+/// # Arguments
+/// * `max_records_per_sec` - Records forwarded per second before the rest
+///   are dropped (a WARNING reports how many)
+#[pyfunction]
+#[pyo3(signature = (max_records_per_sec=DEFAULT_RATE))]
+pub fn init_logging(py: Python, max_records_per_sec: u32) -> PyResult<()> {
+    install(py, max_records_per_sec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_mapping() {
+        assert_eq!(
+            logger_name("plugins_rust::pii_filter::detector"),
+            "plugins_rust.pii_filter.detector"
+        );
+        assert_eq!(python_level(Level::Warn), 30);
+        assert_eq!(level_filter(30), LevelFilter::Warn);
+        assert_eq!(level_filter(20), LevelFilter::Info);
+        assert_eq!(level_filter(0), LevelFilter::Trace);
+        assert_eq!(level_filter(50), LevelFilter::Off);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, start);
+        assert_eq!(bucket.take(start), Some(0));
+        assert_eq!(bucket.take(start), Some(0));
+        assert_eq!(bucket.take(start), None);
+        assert_eq!(bucket.take(start), None);
+        // Half a second refills one token; the drops are reported with it
+        assert_eq!(bucket.take(start + Duration::from_millis(500)), Some(2));
+        assert_eq!(bucket.take(start + Duration::from_millis(500)), None);
+    }
+}
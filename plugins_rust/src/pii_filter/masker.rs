@@ -0,0 +1,82 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Callable masker for per-value use from data-science code
+//
+// `Series.map(masker)` calls the masker once per cell, so per-call overhead
+// dominates: the detector is built once and owned by the callable, and
+// `__call__` is a direct slot call rather than a bound-method lookup.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+use std::borrow::Cow;
+
+use super::config::PIIConfig;
+use super::detector::Detector;
+use super::masking::MaskMemo;
+
+/// Callable that masks a single value
+///
+/// Strings are returned masked (the same object when nothing was found);
+/// anything else, e.g. `None` or `NaN` cells, is returned unchanged.
+#[pyclass(name = "Masker", module = "plugins_rust", frozen)]
+pub struct Masker {
+    detector: Detector,
+}
+
+impl Masker {
+    /// Mask `text`, borrowing it when no PII is found
+    pub fn mask_str<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        self.detector.detect_and_mask(text, &mut MaskMemo::new()).0
+    }
+}
+
+#[pymethods]
+impl Masker {
+    fn __call__(&self, py: Python, value: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let Ok(text) = value.cast::<PyString>() else {
+            return Ok(value.clone().unbind());
+        };
+        let text = text.to_cow()?;
+
+        let masked = py.detach(|| match self.mask_str(&text) {
+            Cow::Borrowed(_) => None,
+            Cow::Owned(masked) => Some(masked),
+        });
+        Ok(match masked {
+            Some(masked) => PyString::new(py, &masked).into_any().unbind(),
+            None => value.clone().unbind(),
+        })
+    }
+}
+
+/// Build a masker callable from a detector configuration
+///
+/// ```python
+/// from plugins_rust import make_masker
+///
+/// masker = make_masker({"detect_ssn": True, "detect_email": True})
+/// df["notes"] = df["notes"].map(masker)
+/// ```
+#[pyfunction]
+pub fn make_masker(config: &Bound<'_, PyDict>) -> PyResult<Masker> {
+    Ok(Masker {
+        detector: Detector::from_config(PIIConfig::from_py_dict(config)?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::PIIConfig;
+
+    #[test]
+    fn test_mask_str_borrows_clean_text() {
+        let masker = Masker {
+            detector: Detector::from_config(PIIConfig::default()).unwrap(),
+        };
+
+        assert!(matches!(masker.mask_str("nothing here"), Cow::Borrowed(_)));
+        assert!(!masker.mask_str("SSN 123-45-6789").contains("123-45-6789"));
+    }
+}
@@ -8,9 +8,50 @@
 // - Copy-on-write strings for zero-copy operations
 // - Zero-copy JSON traversal with serde_json
 
+#[cfg(feature = "arrow")]
+pub mod arrow_batch;
+pub mod benchmark;
+pub mod cancel;
+pub mod certificate;
 pub mod config;
+#[cfg(feature = "yaml")]
+pub mod corpus;
 pub mod detector;
+#[cfg(feature = "differential")]
+pub mod differential;
+#[cfg(feature = "scan_dir")]
+pub mod dir_scan;
+pub mod error;
+pub mod events;
+pub mod invariants;
+pub mod lazy_dfa;
+pub mod log_bridge;
+pub mod masker;
 pub mod masking;
+pub mod normalize;
+#[cfg(feature = "pattern_packs")]
+pub mod pack_fetcher;
+pub mod pattern_cache;
+#[cfg(feature = "ed25519")]
+pub mod pattern_pack;
 pub mod patterns;
+pub mod plugin;
+pub mod pool;
+pub mod presidio;
+pub mod progress;
+pub mod result_cache;
+pub mod risk;
+pub mod routing;
+#[cfg(any(feature = "yaml", feature = "toml"))]
+pub mod rule_import;
+pub mod samples;
+pub mod shadow;
+pub mod siem;
+pub mod simulation;
+pub mod stats;
+pub mod telemetry;
+pub mod triage;
+pub mod validation;
 
-pub use detector::PIIDetectorRust;
+pub use detector::{Detector, PIIDetectorRust};
+pub use error::PIIError;
@@ -0,0 +1,310 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Canonical forms of detected values for downstream dedup and correlation,
+// and the shadow buffer patterns are matched against: Unicode normalized
+// and digit folded so number-bearing patterns see non-Latin numerals as ASCII
+
+use std::collections::BTreeMap;
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::{is_nfc, is_nfkc, UnicodeNormalization as _};
+use zeroize::Zeroize;
+
+use super::config::{PIIType, UnicodeNormalization};
+use super::detector::Detection;
+
+/// Code points of `0` for Unicode decimal digit runs folded to ASCII
+const DIGIT_ZEROS: &[u32] = &[
+    0x0660, // Arabic-Indic
+    0x06F0, // Extended Arabic-Indic (Persian, Urdu)
+    0x07C0, // NKo
+    0x0966, // Devanagari
+    0x09E6, // Bengali
+    0x0A66, // Gurmukhi
+    0x0AE6, // Gujarati
+    0x0B66, // Oriya
+    0x0BE6, // Tamil
+    0x0C66, // Telugu
+    0x0CE6, // Kannada
+    0x0D66, // Malayalam
+    0x0E50, // Thai
+    0x0ED0, // Lao
+    0x0F20, // Tibetan
+    0x1040, // Myanmar
+    0x17E0, // Khmer
+    0x1810, // Mongolian
+    0xFF10, // Fullwidth
+];
+
+/// ASCII digit for a decimal digit in any supported script
+pub fn ascii_digit(c: char) -> Option<char> {
+    if c.is_ascii_digit() {
+        return Some(c);
+    }
+    let code = c as u32;
+    DIGIT_ZEROS
+        .iter()
+        .find(|&&zero| (zero..zero + 10).contains(&code))
+        .and_then(|zero| char::from_digit(code - zero, 10))
+}
+
+/// Normalized and/or digit-folded copy of a text, plus the byte offset map
+/// back to the original
+///
+/// The text is transformed one cluster (a starter and the combining marks
+/// after it) at a time, so every shadow byte maps back to the cluster it
+/// came from. A detection that starts or ends inside the output of a
+/// cluster, e.g. within the "fi" of a NFKC-expanded "ﬁ" ligature, is widened
+/// to the whole cluster in the original. Compositions spanning two starters
+/// (conjoining Hangul jamo) are not applied.
+pub struct ShadowText {
+    pub text: String,
+    /// Original byte range of the cluster each shadow byte came from
+    spans: Vec<(usize, usize)>,
+    original_len: usize,
+}
+
+impl Drop for ShadowText {
+    // The shadow is a full copy of the scanned text
+    fn drop(&mut self) {
+        self.text.zeroize();
+    }
+}
+
+impl ShadowText {
+    /// Transform `text`, or `None` when the shadow would equal the original
+    pub fn new(text: &str, normalization: UnicodeNormalization, fold_digits: bool) -> Option<Self> {
+        if text.is_ascii() {
+            return None;
+        }
+        let normalize = match normalization {
+            UnicodeNormalization::None => false,
+            UnicodeNormalization::Nfc => !is_nfc(text),
+            UnicodeNormalization::Nfkc => !is_nfkc(text),
+        };
+        let fold = fold_digits
+            && text
+                .chars()
+                .any(|c| !c.is_ascii() && ascii_digit(c).is_some());
+        if !normalize && !fold {
+            return None;
+        }
+
+        let mut shadow = String::with_capacity(text.len());
+        let mut spans = Vec::with_capacity(text.len());
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let mut end = start + c.len_utf8();
+            while let Some(&(idx, next)) = chars.peek() {
+                if canonical_combining_class(next) == 0 {
+                    break;
+                }
+                end = idx + next.len_utf8();
+                chars.next();
+            }
+
+            let cluster = &text[start..end];
+            let before = shadow.len();
+            let mut push =
+                |c: char| shadow.push(if fold { ascii_digit(c).unwrap_or(c) } else { c });
+            match normalization {
+                _ if !normalize => cluster.chars().for_each(&mut push),
+                UnicodeNormalization::Nfc => cluster.nfc().for_each(&mut push),
+                UnicodeNormalization::Nfkc => cluster.nfkc().for_each(&mut push),
+                UnicodeNormalization::None => cluster.chars().for_each(&mut push),
+            }
+            spans.extend(std::iter::repeat_n((start, end), shadow.len() - before));
+        }
+
+        Some(Self {
+            text: shadow,
+            spans,
+            original_len: text.len(),
+        })
+    }
+
+    /// Original offset of a detection starting at shadow offset `pos`
+    fn original_start(&self, pos: usize) -> usize {
+        self.spans.get(pos).map_or(self.original_len, |span| span.0)
+    }
+
+    /// Original offset of a detection ending at shadow offset `pos`
+    fn original_end(&self, pos: usize) -> usize {
+        if pos == 0 {
+            return 0;
+        }
+        let previous = self.spans[pos - 1];
+        match self.spans.get(pos) {
+            Some(span) if *span != previous => span.0,
+            Some(_) => previous.1,
+            None => self.original_len,
+        }
+    }
+
+    /// Map detections found in the shadow text back onto `original`
+    pub fn restore(&self, original: &str, detections: &mut BTreeMap<PIIType, Vec<Detection>>) {
+        for detection in detections.values_mut().flatten() {
+            detection.start = self.original_start(detection.start);
+            detection.end = self.original_end(detection.end);
+            detection.value = original[detection.start..detection.end].to_string();
+        }
+    }
+}
+
+/// Canonical form of a detected value, so equal values written differently
+/// deduplicate (`123 45 6789` and `123-45-6789`, `John@X.com` and `john@x.com`)
+///
+/// Identifiers drop separators and fold digits and case; emails and handles
+/// are lowercased; phones use E.164 where possible; IP addresses use their
+/// standard textual form. Secrets, dates and custom values are kept verbatim.
+pub fn canonical_value(pii_type: PIIType, value: &str) -> String {
+    let compact = || -> String {
+        value
+            .chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '.' | '/'))
+            .map(|c| ascii_digit(c).unwrap_or(c))
+            .flat_map(char::to_uppercase)
+            .collect()
+    };
+
+    match pii_type {
+        PIIType::Email | PIIType::SocialHandle => value.trim().to_lowercase(),
+        PIIType::Phone => {
+            phone_e164(value).unwrap_or_else(|| value.chars().filter_map(ascii_digit).collect())
+        }
+        PIIType::IpAddress => value
+            .parse::<std::net::IpAddr>()
+            .map_or_else(|_| value.to_string(), |ip| ip.to_string()),
+        PIIType::Ssn
+        | PIIType::CreditCard
+        | PIIType::BankAccount
+        | PIIType::Passport
+        | PIIType::DriverLicense
+        | PIIType::LicensePlate
+        | PIIType::PostalCode => compact(),
+        _ => value.to_string(),
+    }
+}
+
+/// Normalize a detected phone number to E.164 (`+<country><number>`)
+///
+/// Numbers written with a leading `+` keep their country code; bare 10-digit
+/// numbers and 11-digit numbers starting with `1` are treated as NANP (US).
+/// Returns `None` when no E.164 form can be derived.
+pub fn phone_e164(value: &str) -> Option<String> {
+    let digits: String = value.chars().filter_map(ascii_digit).collect();
+    let international = value.trim_start().starts_with('+');
+
+    match digits.len() {
+        8..=15 if international && !digits.starts_with('0') => Some(format!("+{}", digits)),
+        10 if !international => Some(format!("+1{}", digits)),
+        11 if digits.starts_with('1') => Some(format!("+{}", digits)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::MaskingStrategy;
+
+    #[test]
+    fn test_canonical_value() {
+        assert_eq!(canonical_value(PIIType::Ssn, "123 45 6789"), "123456789");
+        assert_eq!(canonical_value(PIIType::Ssn, "١٢٣-٤٥-٦٧٨٩"), "123456789");
+        assert_eq!(
+            canonical_value(PIIType::Email, "John.Doe@Example.com"),
+            "john.doe@example.com"
+        );
+        assert_eq!(
+            canonical_value(PIIType::Phone, "(555) 123-4567"),
+            "+15551234567"
+        );
+        assert_eq!(
+            canonical_value(PIIType::IpAddress, "2001:DB8:0:0::1"),
+            "2001:db8::1"
+        );
+        assert_eq!(canonical_value(PIIType::PostalCode, "sw1a 1aa"), "SW1A1AA");
+        assert_eq!(canonical_value(PIIType::ApiKey, "sk-AbC"), "sk-AbC");
+    }
+
+    #[test]
+    fn test_phone_e164() {
+        assert_eq!(
+            phone_e164("(555) 123-4567"),
+            Some("+15551234567".to_string())
+        );
+        assert_eq!(
+            phone_e164("1-555-123-4567"),
+            Some("+15551234567".to_string())
+        );
+        assert_eq!(
+            phone_e164("+1 555.123.4567"),
+            Some("+15551234567".to_string())
+        );
+        assert_eq!(
+            phone_e164("+442071234567"),
+            Some("+442071234567".to_string())
+        );
+        assert_eq!(phone_e164("123-4567"), None);
+        assert_eq!(
+            phone_e164("(٥٥٥) ١٢٣-٤٥٦٧"),
+            Some("+15551234567".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fold_digits_maps_offsets() {
+        assert!(ShadowText::new("SSN 123-45-6789", UnicodeNormalization::None, true).is_none());
+
+        let original = "رقم ١٢٣-٤٥-٦٧٨٩ ok";
+        let folded = ShadowText::new(original, UnicodeNormalization::None, true).unwrap();
+        assert_eq!(folded.text, "رقم 123-45-6789 ok");
+
+        let start = folded.text.find('1').unwrap();
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::Ssn,
+            vec![Detection::new(
+                "123-45-6789",
+                start,
+                start + 11,
+                MaskingStrategy::default(),
+            )],
+        );
+        folded.restore(original, &mut detections);
+
+        let detection = &detections[&PIIType::Ssn][0];
+        assert_eq!(detection.value, "١٢٣-٤٥-٦٧٨٩");
+        assert_eq!(&original[detection.start..detection.end], detection.value);
+    }
+
+    #[test]
+    fn test_shadow_nfkc_maps_clusters() {
+        // Decomposed é (e + U+0301) and a ligature expanding to two letters
+        let original = "Jose\u{301} ﬁle";
+        assert!(ShadowText::new(original, UnicodeNormalization::None, true).is_none());
+        let shadow = ShadowText::new(original, UnicodeNormalization::Nfkc, true).unwrap();
+        assert_eq!(shadow.text, "José file");
+
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::Custom,
+            vec![
+                Detection::new("", 0, "José".len(), MaskingStrategy::default()),
+                // Ends inside the expanded ligature: widened to the whole "ﬁ"
+                Detection::new(
+                    "",
+                    shadow.text.find('f').unwrap(),
+                    shadow.text.find('i').unwrap(),
+                    MaskingStrategy::default(),
+                ),
+            ],
+        );
+        shadow.restore(original, &mut detections);
+
+        let restored = &detections[&PIIType::Custom];
+        assert_eq!(restored[0].value, "Jose\u{301}");
+        assert_eq!(restored[1].value, "ﬁ");
+    }
+}
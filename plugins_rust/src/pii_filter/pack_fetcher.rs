@@ -0,0 +1,575 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Remote pattern pack registry client for PII Filter
+//
+// A background thread polls an HTTPS endpoint for the current pattern pack
+// (see `pattern_pack`), asking only for changes (`If-None-Match` /
+// `If-Modified-Since`). A new pack is verified, compiled next to the live
+// patterns without blocking scans, and swapped into the detector, so pattern
+// updates reach a whole fleet without redeploying it. A pack that fails to
+// download, verify or compile, or whose version is not newer than the
+// installed one, leaves the detector as it was.
+//
+// Needs the `pattern_packs` feature.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use reqwest::blocking::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use std::cmp::Ordering;
+use std::io::Read;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::config::{interpolate_env, PIIConfig, SecretKey};
+use super::detector::PIIDetectorRust;
+use super::error::{PIIError, PIIResult};
+use super::pattern_pack::{compare_versions, PatternPack, Verifier};
+
+/// Response header carrying the pack signature
+pub const SIGNATURE_HEADER: &str = "X-Pack-Signature";
+
+/// Largest pack accepted
+pub const MAX_PACK_BYTES: usize = 16 << 20;
+
+/// Registry client settings
+#[derive(Debug, Clone)]
+pub struct FetchSettings {
+    pub url: String,
+    pub verifier: Verifier,
+    pub refresh: Duration,
+    pub timeout: Duration,
+}
+
+impl FetchSettings {
+    fn from_py_dict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let url: String = dict
+            .get_item("url")?
+            .ok_or(PIIError::MissingField("url"))?
+            .extract()?;
+        check_url(&url)?;
+        let verifier = match (dict.get_item("public_key")?, dict.get_item("signing_key")?) {
+            (Some(public_key), None) => Verifier::ed25519(&public_key.extract::<String>()?)?,
+            (None, Some(signing_key)) => Verifier::Hmac(SecretKey::from(interpolate_env(
+                "signing_key",
+                &signing_key.extract::<String>()?,
+            )?)),
+            (None, None) => return Err(PIIError::MissingField("public_key").into()),
+            (Some(_), Some(_)) => {
+                return Err(PIIError::InvalidValue {
+                    field: "signing_key",
+                    value: "set together with public_key".to_string(),
+                }
+                .into())
+            }
+        };
+        let seconds = |field: &'static str, default: f64| -> PyResult<Duration> {
+            let seconds: f64 = match dict.get_item(field)? {
+                Some(value) => value.extract()?,
+                None => default,
+            };
+            Ok(Duration::try_from_secs_f64(seconds)
+                .ok()
+                .filter(|duration| !duration.is_zero())
+                .ok_or_else(|| PIIError::InvalidValue {
+                    field,
+                    value: seconds.to_string(),
+                })?)
+        };
+        Ok(Self {
+            url,
+            verifier,
+            refresh: seconds("refresh_seconds", 300.0)?,
+            timeout: seconds("timeout_seconds", 10.0)?,
+        })
+    }
+}
+
+/// Packs come over HTTPS; plain HTTP only from the local host, for testing
+fn check_url(url: &str) -> PIIResult<()> {
+    let local = ["http://localhost", "http://127.0.0.1", "http://[::1]"]
+        .iter()
+        .any(|prefix| {
+            url.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '/']))
+        });
+    if url.starts_with("https://") || local {
+        Ok(())
+    } else {
+        Err(PIIError::InvalidValue {
+            field: "url",
+            value: url.to_string(),
+        })
+    }
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64())
+}
+
+/// What the registry client has seen so far
+#[derive(Debug, Default)]
+struct FetchState {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Name and version of the installed pack
+    pack: Option<(String, String)>,
+    last_checked: Option<f64>,
+    last_updated: Option<f64>,
+    updates: u64,
+    errors: u64,
+    last_error: Option<String>,
+}
+
+/// A verified pack and the validators to ask for the next change with
+struct Download {
+    pack: PatternPack,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Swaps a configuration into the detector being kept up to date
+type Install = Box<dyn Fn(PIIConfig) -> PIIResult<()> + Send + Sync>;
+
+struct Shared {
+    install: Install,
+    /// The detector's own configuration, which packs are added to
+    base: PIIConfig,
+    settings: FetchSettings,
+    client: Client,
+    state: Mutex<FetchState>,
+    stopped: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl Shared {
+    fn new(install: Install, base: PIIConfig, settings: FetchSettings) -> PIIResult<Self> {
+        let client = Client::builder()
+            .timeout(settings.timeout)
+            .user_agent(concat!("plugins_rust/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|err| PIIError::PackFetch(err.to_string()))?;
+        Ok(Self {
+            install,
+            base,
+            settings,
+            client,
+            state: Mutex::default(),
+            stopped: Mutex::new(false),
+            wake: Condvar::new(),
+        })
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, FetchState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Download the pack if it changed
+    fn download(&self) -> PIIResult<Option<Download>> {
+        let mut request = self.client.get(&self.settings.url);
+        {
+            let state = self.lock_state();
+            if let Some(etag) = &state.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &state.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let fetch_error = |err: reqwest::Error| PIIError::PackFetch(err.to_string());
+        let mut response = request.send().map_err(fetch_error)?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(PIIError::PackFetch(format!("HTTP {}", response.status())));
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_PACK_BYTES as u64)
+        {
+            return Err(PIIError::PackFetch("pack too large".to_string()));
+        }
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let (etag, last_modified) = (header(ETAG.as_str()), header(LAST_MODIFIED.as_str()));
+        let signature = header(SIGNATURE_HEADER);
+        // The length header may be missing or wrong; never read past the limit
+        let mut body = Vec::new();
+        (&mut response)
+            .take(MAX_PACK_BYTES as u64 + 1)
+            .read_to_end(&mut body)
+            .map_err(|err| PIIError::PackFetch(err.to_string()))?;
+        if body.len() > MAX_PACK_BYTES {
+            return Err(PIIError::PackFetch("pack too large".to_string()));
+        }
+        let pack = PatternPack::from_signed(&body, signature.as_deref(), &self.settings.verifier)?;
+        Ok(Some(Download {
+            pack,
+            etag,
+            last_modified,
+        }))
+    }
+
+    /// Fetch and install the current pack; false when it had not changed
+    fn refresh(&self) -> PIIResult<bool> {
+        let result = self.try_refresh();
+        let mut state = self.lock_state();
+        state.last_checked = Some(now());
+        match &result {
+            Ok(true) => {
+                state.updates += 1;
+                state.last_updated = state.last_checked;
+                state.last_error = None;
+            }
+            Ok(false) => state.last_error = None,
+            Err(err) => {
+                state.errors += 1;
+                state.last_error = Some(err.to_string());
+            }
+        }
+        result
+    }
+
+    fn try_refresh(&self) -> PIIResult<bool> {
+        let Some(Download {
+            pack,
+            etag,
+            last_modified,
+        }) = self.download()?
+        else {
+            return Ok(false);
+        };
+        // A validly signed but older pack must not roll the patterns back
+        if let Some((_, installed)) = &self.lock_state().pack {
+            if compare_versions(&pack.version, installed) != Ordering::Greater {
+                return Err(PIIError::PackFetch(format!(
+                    "pack version {} is not newer than the installed {}",
+                    pack.version, installed
+                )));
+            }
+        }
+        (self.install)(pack.apply(&self.base))?;
+
+        log::info!("Installed pattern pack {} {}", pack.name, pack.version);
+        let mut state = self.lock_state();
+        state.etag = etag;
+        state.last_modified = last_modified;
+        state.pack = Some((pack.name, pack.version));
+        Ok(true)
+    }
+
+    /// Refresh every interval until stopped
+    fn run(&self) {
+        let mut stopped = self.stopped.lock().unwrap_or_else(PoisonError::into_inner);
+        while !*stopped {
+            drop(stopped);
+            if let Err(err) = self.refresh() {
+                log::warn!("Pattern pack refresh failed: {}", err);
+            }
+            stopped = self.stopped.lock().unwrap_or_else(PoisonError::into_inner);
+            if *stopped {
+                break;
+            }
+            stopped = self
+                .wake
+                .wait_timeout(stopped, self.settings.refresh)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+}
+
+/// Keeps a detector's patterns in sync with a remote pattern pack
+///
+/// # Example (Python)
+/// ```python
+/// from plugins_rust import PIIDetectorRust, PatternPackFetcher
+///
+/// detector = PIIDetectorRust(config)
+/// fetcher = PatternPackFetcher(detector, {
+///     "url": "https://patterns.example.com/packs/fleet.json",
+///     "public_key": PACK_SIGNER_KEY,  # Ed25519, base64
+///     "refresh_seconds": 300,
+/// })
+/// fetcher.refresh()  # fail fast at startup
+/// fetcher.start()
+/// ```
+#[pyclass(name = "PatternPackFetcher", module = "plugins_rust")]
+pub struct PatternPackFetcher {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[pymethods]
+impl PatternPackFetcher {
+    /// Create a registry client for `detector`
+    ///
+    /// Packs are added to the detector's configuration as it is now; a
+    /// later `update_config()` is overwritten by the next pack.
+    ///
+    /// # Arguments
+    /// * `detector` - Detector whose patterns are kept up to date
+    /// * `config` - Dict:
+    ///   * `url` (str): Pack endpoint, `https://` (plain `http://` only for localhost)
+    ///   * `public_key` (str): Ed25519 public key (base64) of the pack signer;
+    ///     the base64 signature is sent in the `X-Pack-Signature` header
+    ///   * `signing_key` (str): Instead of `public_key`, the HMAC-SHA256 key packs
+    ///     are signed with, the signature sent hex-encoded; supports `${ENV_VAR}`
+    ///   * `refresh_seconds` (float): Interval between checks (default: 300)
+    ///   * `timeout_seconds` (float): Request timeout (default: 10)
+    #[new]
+    pub fn new(detector: Py<PIIDetectorRust>, config: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let settings = FetchSettings::from_py_dict(config)?;
+        let base = detector.get().current().config().clone();
+        // Scans running meanwhile finish on the patterns they started with
+        let install = Box::new(move |config| detector.get().reconfigure(config));
+        Ok(Self {
+            shared: Arc::new(Shared::new(install, base, settings)?),
+            thread: None,
+        })
+    }
+
+    /// Check for a new pack now and install it
+    ///
+    /// # Returns
+    /// True if a new pack was installed, False if it had not changed
+    ///
+    /// # Raises
+    /// `SignatureError` for unsigned or tampered packs, `ConfigError` for
+    /// packs that don't compile, `PIIError` when the download fails or the
+    /// pack's version is not newer than the installed one
+    pub fn refresh(&self, py: Python) -> PyResult<bool> {
+        let shared = Arc::clone(&self.shared);
+        Ok(py.detach(move || shared.refresh())?)
+    }
+
+    /// Check for new packs every `refresh_seconds` on a background thread,
+    /// starting now; failures are logged and the current patterns kept
+    pub fn start(&mut self) -> PyResult<()> {
+        if self.thread.is_some() {
+            return Ok(());
+        }
+        *self
+            .shared
+            .stopped
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = false;
+        let shared = Arc::clone(&self.shared);
+        let thread = thread::Builder::new()
+            .name("pattern-pack-fetcher".to_string())
+            .spawn(move || shared.run())
+            .map_err(|err| PIIError::PackFetch(err.to_string()))?;
+        self.thread = Some(thread);
+        Ok(())
+    }
+
+    /// Stop the background thread, waiting for a refresh in progress
+    pub fn stop(&mut self, py: Python) {
+        self.signal_stop();
+        if let Some(thread) = self.thread.take() {
+            // The thread needs the GIL to install a pack
+            py.detach(|| {
+                let _ = thread.join();
+            });
+        }
+    }
+
+    /// Registry client state
+    ///
+    /// # Returns
+    /// ```python
+    /// {"url": "https://...", "running": True, "name": "fleet", "version": "2026.10.16",
+    ///  "etag": "\"abc\"", "last_checked": 1767225600.0, "last_updated": 1767225600.0,
+    ///  "updates": 1, "errors": 0, "last_error": None}
+    /// ```
+    pub fn status(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let state = self.shared.lock_state();
+        let (name, version) = state.pack.clone().unzip();
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("url", &self.shared.settings.url)?;
+        py_dict.set_item("running", self.thread.is_some())?;
+        py_dict.set_item("name", name)?;
+        py_dict.set_item("version", version)?;
+        py_dict.set_item("etag", &state.etag)?;
+        py_dict.set_item("last_checked", state.last_checked)?;
+        py_dict.set_item("last_updated", state.last_updated)?;
+        py_dict.set_item("updates", state.updates)?;
+        py_dict.set_item("errors", state.errors)?;
+        py_dict.set_item("last_error", &state.last_error)?;
+        Ok(py_dict.unbind())
+    }
+}
+
+impl PatternPackFetcher {
+    fn signal_stop(&self) {
+        *self
+            .shared
+            .stopped
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = true;
+        self.shared.wake.notify_all();
+    }
+}
+
+impl Drop for PatternPackFetcher {
+    // Without joining: the thread may be waiting for the GIL held here
+    fn drop(&mut self) {
+        self.signal_stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::PIIType;
+    use crate::pii_filter::detector::Detector;
+    use crate::pii_filter::pattern_pack::sign;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    fn pack(version: &str, pattern: &str) -> Vec<u8> {
+        format!(
+            r#"{{"name": "fleet", "version": "{}", "custom_patterns":
+                [{{"pattern": "{}", "description": "Employee ID", "mask_strategy": "redact"}}]}}"#,
+            version, pattern
+        )
+        .into_bytes()
+    }
+
+    fn response(status: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+        let mut head = format!("HTTP/1.1 {}\r\nConnection: close\r\n", status);
+        for (name, value) in headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        let mut response = format!("{}\r\n", head).into_bytes();
+        response.extend_from_slice(body);
+        response
+    }
+
+    fn signed(version: &str, pattern: &str) -> Vec<u8> {
+        let body = pack(version, pattern);
+        let length = body.len().to_string();
+        let etag = format!("\"{}\"", version);
+        let signature = sign(&body, "secret");
+        response(
+            "200 OK",
+            &[
+                ("Content-Length", &length),
+                ("ETag", &etag),
+                (SIGNATURE_HEADER, &signature),
+            ],
+            &body,
+        )
+    }
+
+    /// Answer one connection per response, in order; returns the URL and
+    /// the request heads
+    fn serve(responses: Vec<Vec<u8>>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/fleet.json", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        match stream.read(&mut buf).unwrap() {
+                            0 => break,
+                            read => request.extend_from_slice(&buf[..read]),
+                        }
+                    }
+                    // The client hangs up on oversized packs
+                    let _ = stream.write_all(&response);
+                    String::from_utf8_lossy(&request).to_lowercase()
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_refresh_installs_only_newer_verified_packs() {
+        let mut tampered = signed("3", "EMP-\\\\d{6}");
+        let last = tampered.len() - 3;
+        tampered[last] = b'7';
+        let mut oversized = response("200 OK", &[], &vec![b' '; MAX_PACK_BYTES + 1]);
+        oversized.extend_from_slice(b"{}");
+        let (url, server) = serve(vec![
+            signed("2", "EMP-\\\\d{6}"),
+            response("304 Not Modified", &[("Content-Length", "0")], b""),
+            signed("1", "OLD-\\\\d{6}"),
+            tampered,
+            signed("3", "EMP-("),
+            oversized,
+            signed("3", "NEW-\\\\d{6}"),
+        ]);
+
+        let detector = Arc::new(PIIDetectorRust::from(
+            Detector::from_config(PIIConfig::default()).unwrap(),
+        ));
+        let target = Arc::clone(&detector);
+        let settings = FetchSettings {
+            url,
+            verifier: Verifier::Hmac("secret".into()),
+            refresh: Duration::from_secs(300),
+            timeout: Duration::from_secs(10),
+        };
+        let install: Install = Box::new(move |config| target.reconfigure(config));
+        let shared = Shared::new(install, PIIConfig::default(), settings).unwrap();
+        let detects = |text: &str| {
+            detector
+                .current()
+                .detect_internal(text)
+                .contains_key(&PIIType::Custom)
+        };
+
+        assert!(shared.refresh().unwrap());
+        assert!(detects("id EMP-123456"));
+        assert!(!shared.refresh().unwrap());
+        assert!(matches!(shared.refresh(), Err(PIIError::PackFetch(_))));
+        assert!(matches!(
+            shared.refresh(),
+            Err(PIIError::InvalidSignature(_))
+        ));
+        assert!(shared.refresh().is_err());
+        assert!(matches!(shared.refresh(), Err(PIIError::PackFetch(_))));
+        assert!(detects("id EMP-123456"));
+        assert!(!detects("id OLD-123456"));
+        assert_eq!(
+            shared.lock_state().pack,
+            Some(("fleet".to_string(), "2".to_string()))
+        );
+
+        assert!(shared.refresh().unwrap());
+        assert!(detects("id NEW-123456"));
+        assert!(!detects("id EMP-123456"));
+        let state = shared.lock_state();
+        assert_eq!((state.updates, state.errors), (2, 4));
+
+        let requests = server.join().unwrap();
+        assert!(requests[1].contains("if-none-match: \"2\""));
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("https://patterns.example.com/fleet.json").is_ok());
+        assert!(check_url("http://localhost:8080/fleet.json").is_ok());
+        assert!(check_url("http://127.0.0.1/fleet.json").is_ok());
+        assert!(check_url("http://localhost.example.com/fleet.json").is_err());
+        assert!(check_url("http://patterns.example.com/fleet.json").is_err());
+        assert!(check_url("ftp://patterns.example.com/fleet.json").is_err());
+    }
+}
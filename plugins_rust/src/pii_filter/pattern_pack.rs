@@ -0,0 +1,485 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Pattern packs and signed policy files for PII Filter
+//
+// A pattern pack is a signed JSON document of custom and whitelist patterns
+// maintained centrally and added on top of each detector's own config:
+//
+//   {"name": "fleet-secrets", "version": "2026.10.16",
+//    "custom_patterns": [...], "whitelist_patterns": [...],
+//    "whitelist_context": [...]}
+//
+// Versions are dot-separated, compared part by part, numerically where both
+// parts are numbers, so a fetcher only ever moves a detector forward.
+//
+// Signatures cover the exact bytes of a document, so it is verified before
+// it is parsed. They are either Ed25519 (base64, checked with the signer's
+// public key) or, for packs fetched from a registry, HMAC-SHA256 (hex, with
+// a shared key). Packs and detector config files on disk carry an Ed25519
+// signature in a `.sig` file next to them.
+//
+// Needs the `ed25519` feature, which `pattern_packs` enables.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use hmac::{Hmac, Mac};
+use pyo3::prelude::*;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::cmp::Ordering;
+use std::path::Path;
+
+use super::certificate::{decode_hex, hmac_hex};
+use super::config::{ContextWhitelist, CustomPattern, PIIConfig, SecretKey};
+use super::error::{PIIError, PIIResult};
+
+/// Patterns a pack adds to a configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatternPack {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub custom_patterns: Vec<CustomPattern>,
+    #[serde(default)]
+    pub whitelist_patterns: Vec<String>,
+    #[serde(default)]
+    pub whitelist_context: Vec<ContextWhitelist>,
+}
+
+impl PatternPack {
+    /// Parse a pack whose `signature` has been checked by `verifier`
+    pub fn from_signed(
+        body: &[u8],
+        signature: Option<&str>,
+        verifier: &Verifier,
+    ) -> PIIResult<Self> {
+        verifier.verify(body, signature)?;
+        Self::parse(body)
+    }
+
+    fn parse(body: &[u8]) -> PIIResult<Self> {
+        serde_json::from_slice(body).map_err(|err| PIIError::InvalidJson(err.to_string()))
+    }
+
+    /// `base` with the pack's patterns added, in its tool overlays too
+    pub fn apply(&self, base: &PIIConfig) -> PIIConfig {
+        let mut config = base.clone();
+        self.extend(&mut config);
+        for overlay in &mut config.tool_overlays {
+            self.extend(&mut overlay.config);
+        }
+        config
+    }
+
+    fn extend(&self, config: &mut PIIConfig) {
+        config
+            .custom_patterns
+            .extend(self.custom_patterns.iter().cloned());
+        config
+            .whitelist_patterns
+            .extend(self.whitelist_patterns.iter().cloned());
+        config
+            .whitelist_context
+            .extend(self.whitelist_context.iter().cloned());
+    }
+}
+
+/// How document signatures are checked
+#[derive(Debug, Clone)]
+pub enum Verifier {
+    /// HMAC-SHA256 with a shared key, hex signatures
+    Hmac(SecretKey),
+    /// Ed25519 with the signer's public key, base64 signatures
+    Ed25519(VerifyingKey),
+}
+
+impl Verifier {
+    /// Ed25519 verifier for a base64 public key
+    pub fn ed25519(public_key: &str) -> PIIResult<Self> {
+        let invalid = || PIIError::InvalidValue {
+            field: "public_key",
+            value: public_key.to_string(),
+        };
+        let bytes: [u8; 32] = STANDARD
+            .decode(public_key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(invalid)?;
+        VerifyingKey::from_bytes(&bytes)
+            .map(Verifier::Ed25519)
+            .map_err(|_| invalid())
+    }
+
+    /// Check `signature` over `body`; unsigned documents are rejected
+    pub fn verify(&self, body: &[u8], signature: Option<&str>) -> PIIResult<()> {
+        let signature = signature
+            .map(str::trim)
+            .ok_or_else(|| PIIError::InvalidSignature("unsigned".to_string()))?;
+        let malformed = || PIIError::InvalidSignature("malformed".to_string());
+        let mismatch = || PIIError::InvalidSignature("does not match".to_string());
+        match self {
+            Verifier::Hmac(key) => {
+                let signature = decode_hex(signature).ok_or_else(malformed)?;
+                let mut mac = Hmac::<Sha256>::new_from_slice(key.expose().as_bytes())
+                    .expect("any key length");
+                mac.update(body);
+                mac.verify_slice(&signature).map_err(|_| mismatch())
+            }
+            Verifier::Ed25519(public_key) => {
+                let signature = STANDARD.decode(signature).map_err(|_| malformed())?;
+                let signature = Signature::from_slice(&signature).map_err(|_| malformed())?;
+                public_key
+                    .verify_strict(body, &signature)
+                    .map_err(|_| mismatch())
+            }
+        }
+    }
+}
+
+/// HMAC-SHA256 (hex) of a pack document
+pub fn sign(body: &[u8], key: &str) -> String {
+    hmac_hex(key, body)
+}
+
+/// Order two pack versions, e.g. `2026.10.9` < `2026.10.16` < `2026.10.16.1`
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let numeric = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    let (mut a, mut b) = (a.trim().split('.'), b.trim().split('.'));
+    loop {
+        let order = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (Some(a), Some(b)) if numeric(a) && numeric(b) => {
+                let (a, b) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+                a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+            }
+            (Some(a), Some(b)) => a.cmp(b),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+}
+
+/// Read the file at `path` once its signature (in `signature_path`, by
+/// default `<path>.sig`) has been checked by `verifier`
+pub fn read_signed(
+    path: &str,
+    signature_path: Option<&str>,
+    verifier: &Verifier,
+) -> PIIResult<Vec<u8>> {
+    let read_error = |path: &str, err: std::io::Error| PIIError::ReadFile {
+        path: path.to_string(),
+        message: err.to_string(),
+    };
+    let body = std::fs::read(Path::new(path)).map_err(|err| read_error(path, err))?;
+    let signature_path = signature_path.map_or_else(|| format!("{}.sig", path), str::to_string);
+    let signature = match std::fs::read_to_string(&signature_path) {
+        Ok(signature) => Some(signature),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(read_error(&signature_path, err)),
+    };
+    verifier.verify(&body, signature.as_deref())?;
+    Ok(body)
+}
+
+/// Parse a verified config file as JSON, or TOML/YAML by extension
+fn parse_config_file(path: &str, body: &[u8]) -> PIIResult<serde_json::Value> {
+    let text = std::str::from_utf8(body)
+        .map_err(|_| PIIError::InvalidJson(format!("'{}' is not valid UTF-8", path)))?;
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        #[cfg(feature = "toml")]
+        "toml" => toml::from_str(text).map_err(|err| PIIError::InvalidJson(err.to_string())),
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => {
+            serde_yaml::from_str(text).map_err(|err| PIIError::InvalidJson(err.to_string()))
+        }
+        _ => serde_json::from_str(text).map_err(|err| PIIError::InvalidJson(err.to_string())),
+    }
+}
+
+fn json_to_py(py: Python, value: &serde_json::Value) -> PyResult<Py<PyAny>> {
+    Ok(py
+        .import("json")?
+        .call_method1("loads", (value.to_string(),))?
+        .unbind())
+}
+
+/// Load a pattern pack from disk after checking its Ed25519 signature
+///
+/// # Arguments
+/// * `path` - Pack JSON file
+/// * `public_key` - Signer's Ed25519 public key, base64
+/// * `signature_path` - Base64 signature file (default: `<path>.sig`)
+///
+/// # Returns
+/// The pack as a dict (`name`, `version`, `custom_patterns`, ...); add its
+/// patterns to a detector config
+///
+/// # Raises
+/// `SignatureError` for unsigned or tampered packs
+#[pyfunction]
+#[pyo3(signature = (path, public_key, signature_path=None))]
+pub fn load_pattern_pack(
+    py: Python,
+    path: &str,
+    public_key: &str,
+    signature_path: Option<&str>,
+) -> PyResult<Py<PyAny>> {
+    let body = read_signed(path, signature_path, &Verifier::ed25519(public_key)?)?;
+    PatternPack::parse(&body)?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|err| PIIError::InvalidJson(err.to_string()))?;
+    json_to_py(py, &value)
+}
+
+/// Load a detector config file after checking its Ed25519 signature
+///
+/// JSON, or TOML/YAML by file extension (they need the `toml` and `yaml`
+/// features).
+///
+/// # Arguments
+/// * `path` - Config file, same keys as `PIIDetectorRust`
+/// * `public_key` - Signer's Ed25519 public key, base64
+/// * `signature_path` - Base64 signature file (default: `<path>.sig`)
+///
+/// # Returns
+/// The config dict, e.g. for `PIIDetectorRust(load_signed_config(...))`
+///
+/// # Raises
+/// `SignatureError` for unsigned or tampered files
+#[pyfunction]
+#[pyo3(signature = (path, public_key, signature_path=None))]
+pub fn load_signed_config(
+    py: Python,
+    path: &str,
+    public_key: &str,
+    signature_path: Option<&str>,
+) -> PyResult<Py<PyAny>> {
+    let body = read_signed(path, signature_path, &Verifier::ed25519(public_key)?)?;
+    json_to_py(py, &parse_config_file(path, &body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    pub(super) const PACK: &[u8] = br#"{"name": "fleet", "version": "1",
+        "custom_patterns": [{"pattern": "EMP-\\d{6}", "description": "Employee ID",
+                             "mask_strategy": "redact"}],
+        "whitelist_patterns": ["EMP-000000"]}"#;
+
+    fn tampered() -> Vec<u8> {
+        let mut tampered = PACK.to_vec();
+        tampered[10] = b'x';
+        tampered
+    }
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    pub(super) fn public_key() -> String {
+        STANDARD.encode(signing_key().verifying_key().as_bytes())
+    }
+
+    /// Fresh directory for one test's files
+    pub(super) fn temp_dir(test: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("pattern-pack-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write `body` to `dir/name` with its signature in `dir/name.sig`
+    pub(super) fn write_signed(dir: &Path, name: &str, body: &[u8]) -> String {
+        let path = dir.join(name);
+        std::fs::write(&path, body).unwrap();
+        let signature = STANDARD.encode(signing_key().sign(body).to_bytes());
+        std::fs::write(dir.join(format!("{}.sig", name)), signature).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_hmac_signed_pack() {
+        let verifier = Verifier::Hmac("secret".into());
+        let signature = sign(PACK, "secret");
+
+        let pack = PatternPack::from_signed(PACK, Some(&signature), &verifier).unwrap();
+        assert_eq!(pack.custom_patterns[0].pattern, r"EMP-\d{6}");
+        let config = pack.apply(&PIIConfig::default());
+        assert_eq!(config.custom_patterns.len(), 1);
+        assert_eq!(config.whitelist_patterns, vec!["EMP-000000"]);
+
+        for (body, signature) in [
+            (&tampered()[..], Some(signature.as_str())),
+            (PACK, None),
+            (PACK, Some("zz")),
+        ] {
+            assert!(matches!(
+                PatternPack::from_signed(body, signature, &verifier),
+                Err(PIIError::InvalidSignature(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ed25519_signed_pack() {
+        let verifier = Verifier::ed25519(&public_key()).unwrap();
+        let signature = STANDARD.encode(signing_key().sign(PACK).to_bytes());
+
+        assert!(PatternPack::from_signed(PACK, Some(&signature), &verifier).is_ok());
+        let other = SigningKey::from_bytes(&[8; 32]);
+        let forged = STANDARD.encode(other.sign(PACK).to_bytes());
+        for (body, signature) in [
+            (&tampered()[..], Some(signature.as_str())),
+            (PACK, Some(forged.as_str())),
+            (PACK, None),
+        ] {
+            assert!(matches!(
+                verifier.verify(body, signature),
+                Err(PIIError::InvalidSignature(_))
+            ));
+        }
+        assert!(Verifier::ed25519("bm90IGEga2V5").is_err());
+    }
+
+    #[test]
+    fn test_read_signed_files() {
+        let dir = temp_dir("read");
+        let verifier = Verifier::ed25519(&public_key()).unwrap();
+        let path = write_signed(&dir, "pack.json", PACK);
+        assert_eq!(read_signed(&path, None, &verifier).unwrap(), PACK);
+
+        // Signature kept somewhere other than `<path>.sig`
+        let moved = dir.join("detached.sig");
+        std::fs::rename(format!("{}.sig", path), &moved).unwrap();
+        assert!(matches!(
+            read_signed(&path, None, &verifier),
+            Err(PIIError::InvalidSignature(reason)) if reason == "unsigned"
+        ));
+        assert!(read_signed(&path, moved.to_str(), &verifier).is_ok());
+
+        std::fs::write(&path, tampered()).unwrap();
+        assert!(matches!(
+            read_signed(&path, moved.to_str(), &verifier),
+            Err(PIIError::InvalidSignature(_))
+        ));
+        let missing = dir.join("missing.json");
+        assert!(matches!(
+            read_signed(missing.to_str().unwrap(), None, &verifier),
+            Err(PIIError::ReadFile { .. })
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_config_file_formats() {
+        let json = parse_config_file("policy.json", br#"{"detect_ssn": false}"#).unwrap();
+        assert_eq!(json, serde_json::json!({"detect_ssn": false}));
+        #[cfg(feature = "toml")]
+        {
+            let toml = parse_config_file("policy.TOML", b"detect_ssn = false\n").unwrap();
+            assert_eq!(toml, json);
+            assert!(parse_config_file("policy.toml", b"detect_ssn = ").is_err());
+        }
+        #[cfg(feature = "yaml")]
+        {
+            let yaml = parse_config_file("policy.yml", b"detect_ssn: false\n").unwrap();
+            assert_eq!(yaml, json);
+            assert!(parse_config_file("policy.yaml", b"detect_ssn: [").is_err());
+        }
+        assert!(matches!(
+            parse_config_file("policy.json", b"\xff"),
+            Err(PIIError::InvalidJson(_))
+        ));
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        for (older, newer) in [
+            ("2026.10.9", "2026.10.16"),
+            ("2026.10.16", "2026.10.16.1"),
+            ("1.9", "1.10"),
+            ("1.beta", "1.rc"),
+        ] {
+            assert_eq!(compare_versions(older, newer), Ordering::Less);
+            assert_eq!(compare_versions(newer, older), Ordering::Greater);
+        }
+        assert_eq!(compare_versions("1.02", "1.2"), Ordering::Equal);
+    }
+}
+
+/// Tests of the Python-facing loaders
+///
+/// They need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::tests::{temp_dir, write_signed};
+    use super::*;
+    use crate::pii_filter::error::exceptions::SignatureError;
+
+    #[test]
+    fn test_load_pattern_pack() {
+        let dir = temp_dir("load-pack");
+        let public_key = super::tests::public_key();
+        let path = write_signed(&dir, "pack.json", super::tests::PACK);
+        // Signed, but not a pack
+        let not_pack = write_signed(&dir, "other.json", br#"{"detect_ssn": false}"#);
+
+        Python::initialize();
+        Python::attach(|py| {
+            let pack = load_pattern_pack(py, &path, &public_key, None).unwrap();
+            let name: String = pack.bind(py).get_item("name").unwrap().extract().unwrap();
+            assert_eq!(name, "fleet");
+            assert!(load_pattern_pack(py, &not_pack, &public_key, None).is_err());
+
+            std::fs::remove_file(format!("{}.sig", path)).unwrap();
+            let unsigned = load_pattern_pack(py, &path, &public_key, None).unwrap_err();
+            assert!(unsigned.is_instance_of::<SignatureError>(py));
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_load_signed_config() {
+        let dir = temp_dir("load-config");
+        let public_key = super::tests::public_key();
+        let path = write_signed(
+            &dir,
+            "policy.toml",
+            b"detect_ssn = false\nredaction_text = \"[X]\"\n",
+        );
+
+        Python::initialize();
+        Python::attach(|py| {
+            let config = load_signed_config(py, &path, &public_key, None).unwrap();
+            let config = config.bind(py);
+            let detect_ssn: bool = config.get_item("detect_ssn").unwrap().extract().unwrap();
+            assert!(!detect_ssn);
+            let text: String = config
+                .get_item("redaction_text")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(text, "[X]");
+
+            std::fs::write(&path, "detect_ssn = true\n").unwrap();
+            let tampered = load_signed_config(py, &path, &public_key, None).unwrap_err();
+            assert!(tampered.is_instance_of::<SignatureError>(py));
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
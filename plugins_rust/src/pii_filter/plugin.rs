@@ -0,0 +1,104 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// GatewayPlugin implementation for the PII filter
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::config::Direction;
+use super::detector::PIIDetectorRust;
+use crate::plugin::{GatewayPlugin, Hook, HookContext};
+
+impl PIIDetectorRust {
+    pub const PLUGIN_NAME: &'static str = "pii_filter";
+    pub const HOOKS: &'static [Hook] = &[Hook::OnRequest, Hook::OnResponse, Hook::OnToolResult];
+
+    /// Mask PII anywhere in the context payload and record per-type counts
+    ///
+    /// Only counts (and the policy hash) are recorded as findings so
+    /// downstream plugins never see the detected values. Types configured to block raise
+    /// `BlockedContentError` instead. Values masked by an earlier hook are
+    /// counted under `already_masked` rather than as detections. The
+    /// `directions` policy of the hook's direction applies, and the
+    /// `tool_overlays` config of the context's tool replaces the base one.
+    /// With `risk_analysis`, the finding also carries the payload's
+    /// `risk` report, and is recorded whenever a quasi-identifier was seen.
+    fn mask_context(
+        &self,
+        py: Python,
+        context: &Bound<'_, HookContext>,
+        direction: Direction,
+    ) -> PyResult<()> {
+        let (payload, tenant, tool_name) = {
+            let context = context.borrow();
+            (
+                context.payload.clone_ref(py),
+                context.tenant.clone(),
+                context.tool_name.clone(),
+            )
+        };
+        let current = self.current();
+        let detector = tool_name.map_or(&*current, |tool_name| current.for_tool(&tool_name));
+        let ((modified, masked, detections), already_masked, risk) = detector
+            .process_nested_internal(
+                py,
+                payload.bind(py),
+                "",
+                Some(direction),
+                tenant.as_deref(),
+            )?;
+        let risk = risk.filter(|risk| !risk.is_empty());
+        if !modified && already_masked == 0 && risk.is_none() {
+            return Ok(());
+        }
+
+        detector.check_blocking(&detections, Some(direction))?;
+
+        let counts = PyDict::new(py);
+        for (pii_type, items) in &detections {
+            counts.set_item(pii_type.as_str(), items.len())?;
+        }
+        let finding = PyDict::new(py);
+        finding.set_item("detections", counts)?;
+        finding.set_item("already_masked", already_masked)?;
+        finding.set_item("policy_hash", detector.policy_hash())?;
+        if let Some(risk) = &risk {
+            finding.set_item("risk", risk.to_py_dict(py)?)?;
+        }
+
+        let mut context = context.borrow_mut();
+        context.add_finding(py, Self::PLUGIN_NAME, finding.into_any().unbind())?;
+        if modified {
+            context.payload = masked;
+        }
+        Ok(())
+    }
+}
+
+impl GatewayPlugin for PIIDetectorRust {
+    fn name(&self) -> &'static str {
+        Self::PLUGIN_NAME
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn hooks(&self) -> &'static [Hook] {
+        Self::HOOKS
+    }
+
+    fn on_request(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.mask_context(py, context, Direction::Request)
+    }
+
+    fn on_response(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.mask_context(py, context, Direction::Response)
+    }
+
+    // Tool results flow back to the client like any other response
+    fn on_tool_result(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.mask_context(py, context, Direction::Response)
+    }
+}
@@ -0,0 +1,520 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Shared detector pool for PII Filter
+//
+// A gateway process runs many async hooks; if each scans on threads of its
+// own, a burst of requests oversubscribes the CPU. The pool owns one bounded
+// Rayon pool (work stealing keeps long and short texts balanced) and one
+// detector per worker, so every hook shares the same CPU budget.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use super::config::{PIIConfig, PIIType};
+use super::detector::{Detection, Detector};
+use super::error::{PIIError, PIIResult};
+use super::stats::{percentile, push_latency};
+
+/// What `submit()` does when the queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    #[default]
+    Reject, // Raise `PoolOverloadedError` straight away
+    ShedOldest, // Fail the oldest queued submission and queue the new one
+    Block,      // Wait up to `block_timeout` for room, then reject
+}
+
+impl Overflow {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Overflow::Reject => "reject",
+            Overflow::ShedOldest => "shed_oldest",
+            Overflow::Block => "block",
+        }
+    }
+}
+
+impl FromStr for Overflow {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(Overflow::Reject),
+            "shed_oldest" => Ok(Overflow::ShedOldest),
+            "block" => Ok(Overflow::Block),
+            _ => Err(PIIError::InvalidValue {
+                field: "overflow",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Bound on queued submissions and what happens beyond it
+#[derive(Debug, Clone, Copy)]
+pub struct QueueLimits {
+    /// Maximum queued submissions; `None` for unbounded
+    pub max_queue: Option<usize>,
+    pub overflow: Overflow,
+    /// How long a `Block` submitter waits for room
+    pub block_timeout: Duration,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            max_queue: None,
+            overflow: Overflow::Reject,
+            block_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+struct QueueState<T> {
+    items: VecDeque<(T, Instant)>,
+    rejected: u64,
+    shed: u64,
+}
+
+/// FIFO of submissions waiting for a worker, bounded by `QueueLimits`
+pub struct BoundedQueue<T> {
+    state: Mutex<QueueState<T>>,
+    /// Signalled when a worker takes an item, waking `Block` submitters
+    space: Condvar,
+    limits: QueueLimits,
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(limits: QueueLimits) -> PIIResult<Self> {
+        if limits.max_queue == Some(0) {
+            return Err(PIIError::InvalidValue {
+                field: "max_queue",
+                value: "0".to_string(),
+            });
+        }
+        Ok(Self {
+            state: Mutex::new(QueueState {
+                items: VecDeque::new(),
+                rejected: 0,
+                shed: 0,
+            }),
+            space: Condvar::new(),
+            limits,
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue `item`, applying the overflow policy when full
+    ///
+    /// Returns the item shed to make room, if any.
+    pub fn push(&self, item: T) -> PIIResult<Option<T>> {
+        let mut state = self.lock();
+        if let Some(capacity) = self.limits.max_queue {
+            let deadline = Instant::now() + self.limits.block_timeout;
+            while state.items.len() >= capacity {
+                let now = Instant::now();
+                match self.limits.overflow {
+                    Overflow::ShedOldest => {
+                        let oldest = state.items.pop_front().map(|(oldest, _)| oldest);
+                        state.shed += 1;
+                        state.items.push_back((item, now));
+                        return Ok(oldest);
+                    }
+                    Overflow::Block if now < deadline => {
+                        state = self
+                            .space
+                            .wait_timeout(state, deadline - now)
+                            .unwrap_or_else(|e| e.into_inner())
+                            .0;
+                    }
+                    Overflow::Reject | Overflow::Block => {
+                        state.rejected += 1;
+                        return Err(PIIError::QueueFull { capacity });
+                    }
+                }
+            }
+        }
+        state.items.push_back((item, Instant::now()));
+        Ok(None)
+    }
+
+    /// Take the oldest item and how long it waited
+    pub fn pop(&self) -> Option<(T, Duration)> {
+        let popped = self.lock().items.pop_front();
+        self.space.notify_one();
+        popped.map(|(item, queued_at)| (item, queued_at.elapsed()))
+    }
+
+    pub fn depth(&self) -> usize {
+        self.lock().items.len()
+    }
+}
+
+/// Submission and latency counters for a pool
+#[derive(Debug, Default)]
+struct PoolStats {
+    submitted: u64,
+    completed: u64,
+    /// Futures cancelled before a worker picked them up
+    cancelled: u64,
+    /// Recent queue waits and scan times in microseconds (bounded windows)
+    wait_us: VecDeque<f64>,
+    scan_us: VecDeque<f64>,
+}
+
+/// Worker threads, each with its own detector
+pub struct Workers {
+    /// Indexed by Rayon thread index
+    detectors: Vec<Detector>,
+    pool: ThreadPool,
+}
+
+impl Workers {
+    pub fn new(config: PIIConfig, workers: usize) -> PIIResult<Self> {
+        if workers == 0 {
+            return Err(PIIError::InvalidValue {
+                field: "workers",
+                value: workers.to_string(),
+            });
+        }
+        let detectors = (0..workers)
+            .map(|_| Detector::from_config(config.clone()))
+            .collect::<PIIResult<Vec<_>>>()?;
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(workers)
+            .thread_name(|index| format!("pii-pool-{}", index))
+            .build()
+            .map_err(|err| PIIError::InvalidValue {
+                field: "workers",
+                value: err.to_string(),
+            })?;
+        Ok(Self { detectors, pool })
+    }
+
+    /// Detector owned by the calling worker thread
+    fn detector(&self) -> &Detector {
+        &self.detectors[rayon::current_thread_index().unwrap_or(0) % self.detectors.len()]
+    }
+
+    /// Detect PII in every text on the pool, results in input order
+    pub fn map(&self, texts: &[String]) -> Vec<BTreeMap<PIIType, Vec<Detection>>> {
+        self.pool.install(|| {
+            texts
+                .par_iter()
+                .map(|text| self.detector().detect_internal(text))
+                .collect()
+        })
+    }
+}
+
+/// `submit()` calls waiting for a worker, with their counters
+struct Submissions {
+    /// Text and its Python future
+    queue: BoundedQueue<(String, Py<PyAny>)>,
+    stats: Mutex<PoolStats>,
+}
+
+impl Submissions {
+    fn lock_stats(&self) -> MutexGuard<'_, PoolStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Scan the oldest queued submission and resolve its future
+    fn run_next(&self, workers: &Workers) {
+        // The submission this task was spawned for may have been shed
+        let Some(((text, future), waited)) = self.queue.pop() else {
+            return;
+        };
+        let running = Python::attach(|py| {
+            future
+                .call_method0(py, "set_running_or_notify_cancel")
+                .and_then(|running| running.extract::<bool>(py))
+                .unwrap_or(false)
+        });
+        if !running {
+            self.lock_stats().cancelled += 1;
+            return;
+        }
+
+        let started = Instant::now();
+        let detector = workers.detector();
+        let detections = detector.detect_internal(&text);
+        let scanned = started.elapsed();
+        Python::attach(|py| {
+            let outcome = match detector.rust_detections_to_py(py, &detections) {
+                Ok(result) => future.call_method1(py, "set_result", (result,)),
+                Err(err) => future.call_method1(py, "set_exception", (err.into_value(py),)),
+            };
+            // Only fails if the future was resolved elsewhere
+            drop(outcome);
+        });
+
+        let mut stats = self.lock_stats();
+        stats.completed += 1;
+        push_latency(&mut stats.wait_us, waited);
+        push_latency(&mut stats.scan_us, scanned);
+    }
+}
+
+/// Bounded worker pool of detectors sharing one configuration
+///
+/// `submit()` calls wait in a queue bounded by `max_queue`; when it is full
+/// the `overflow` policy applies, so a scan storm turns into
+/// `PoolOverloadedError`s the gateway can push back upstream rather than
+/// unbounded memory growth.
+///
+/// ```python
+/// import asyncio
+/// from plugins_rust import DetectorPool
+///
+/// pool = DetectorPool({"detect_ssn": True}, workers=4, max_queue=1000)
+/// detections = await asyncio.wrap_future(pool.submit(text))
+/// reports = pool.map(texts)
+/// ```
+#[pyclass(name = "DetectorPool", module = "plugins_rust", frozen)]
+pub struct DetectorPool {
+    workers: Arc<Workers>,
+    submissions: Arc<Submissions>,
+}
+
+#[pymethods]
+impl DetectorPool {
+    /// Create a pool
+    ///
+    /// # Arguments
+    /// * `config_dict` - Detector configuration (same keys as `PIIDetectorRust`)
+    /// * `workers` - Worker threads, each with its own detector (default: CPU count)
+    /// * `max_queue` - Maximum `submit()` calls waiting for a worker (default: unbounded)
+    /// * `overflow` - When the queue is full: "reject" raises `PoolOverloadedError`,
+    ///   "shed_oldest" fails the oldest waiting future with it instead, "block"
+    ///   waits up to `block_timeout` seconds for room, then rejects
+    /// * `block_timeout` - Seconds a "block" submitter waits (default: 1.0)
+    #[new]
+    #[pyo3(signature = (config_dict, workers=None, max_queue=None, overflow="reject", block_timeout=1.0))]
+    pub fn new(
+        config_dict: &Bound<'_, PyDict>,
+        workers: Option<usize>,
+        max_queue: Option<usize>,
+        overflow: &str,
+        block_timeout: f64,
+    ) -> PyResult<Self> {
+        let config = PIIConfig::from_py_dict(config_dict)?;
+        let workers = workers.unwrap_or_else(|| {
+            std::thread::available_parallelism().map_or(1, |workers| workers.get())
+        });
+        let limits = QueueLimits {
+            max_queue,
+            overflow: overflow.parse()?,
+            block_timeout: Duration::try_from_secs_f64(block_timeout).map_err(|_| {
+                PIIError::InvalidValue {
+                    field: "block_timeout",
+                    value: block_timeout.to_string(),
+                }
+            })?,
+        };
+        Ok(Self {
+            workers: Arc::new(Workers::new(config, workers)?),
+            submissions: Arc::new(Submissions {
+                queue: BoundedQueue::new(limits)?,
+                stats: Mutex::new(PoolStats::default()),
+            }),
+        })
+    }
+
+    /// Number of worker threads
+    #[getter]
+    pub fn workers(&self) -> usize {
+        self.workers.detectors.len()
+    }
+
+    /// Submissions waiting for a worker
+    #[getter]
+    pub fn queue_depth(&self) -> usize {
+        self.submissions.queue.depth()
+    }
+
+    /// Queue `text` for detection
+    ///
+    /// # Returns
+    /// A `concurrent.futures.Future` resolving to the same dict as
+    /// `PIIDetectorRust.detect()`; await it with `asyncio.wrap_future()`.
+    /// Cancelling the future before a worker picks it up skips the scan.
+    ///
+    /// # Raises
+    /// `PoolOverloadedError` when the queue is full and the overflow policy
+    /// rejects the submission
+    pub fn submit(&self, py: Python, text: String) -> PyResult<Py<PyAny>> {
+        let future = py
+            .import("concurrent.futures")?
+            .getattr("Future")?
+            .call0()?
+            .unbind();
+        let job = (text, future.clone_ref(py));
+
+        // Blocking submitters wait without holding the GIL
+        let shed = py.detach(|| self.submissions.queue.push(job))?;
+        if let Some((_, shed)) = shed {
+            shed.call_method1(
+                py,
+                "set_exception",
+                (PyErr::from(PIIError::Shed).into_value(py),),
+            )?;
+        }
+        self.submissions.lock_stats().submitted += 1;
+
+        let workers = Arc::clone(&self.workers);
+        let submissions = Arc::clone(&self.submissions);
+        self.workers
+            .pool
+            .spawn(move || submissions.run_next(&workers));
+        Ok(future)
+    }
+
+    /// Detect PII in every text, spread across the workers
+    ///
+    /// The caller waits for the whole batch, so `map()` is not queued and
+    /// not subject to `max_queue`.
+    ///
+    /// # Returns
+    /// List of `detect()` dicts in input order
+    pub fn map(&self, py: Python, texts: Vec<String>) -> PyResult<Py<PyAny>> {
+        let results = py.detach(|| self.workers.map(&texts));
+
+        let py_list = PyList::empty(py);
+        for detections in &results {
+            py_list.append(self.workers.detectors[0].rust_detections_to_py(py, detections)?)?;
+        }
+        Ok(py_list.into_any().unbind())
+    }
+
+    /// Queue and latency gauges
+    ///
+    /// # Returns
+    /// ```python
+    /// {
+    ///     "queue_depth": 12, "max_queue": 1000, "overflow": "reject",
+    ///     "submitted": 5000, "completed": 4988, "cancelled": 0,
+    ///     "rejected": 3, "shed": 0,
+    ///     "p50_queue_wait_us": 210.0, "p99_queue_wait_us": 4800.0,
+    ///     "p50_scan_us": 35.0, "p99_scan_us": 410.0,
+    /// }
+    /// ```
+    pub fn stats(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let (rejected, shed, depth) = {
+            let state = self.submissions.queue.lock();
+            (state.rejected, state.shed, state.items.len())
+        };
+        let limits = self.submissions.queue.limits;
+        let stats = self.submissions.lock_stats();
+
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("queue_depth", depth)?;
+        py_dict.set_item("max_queue", limits.max_queue)?;
+        py_dict.set_item("overflow", limits.overflow.as_str())?;
+        py_dict.set_item("submitted", stats.submitted)?;
+        py_dict.set_item("completed", stats.completed)?;
+        py_dict.set_item("cancelled", stats.cancelled)?;
+        py_dict.set_item("rejected", rejected)?;
+        py_dict.set_item("shed", shed)?;
+        py_dict.set_item("p50_queue_wait_us", percentile(&stats.wait_us, 0.50))?;
+        py_dict.set_item("p99_queue_wait_us", percentile(&stats.wait_us, 0.99))?;
+        py_dict.set_item("p50_scan_us", percentile(&stats.scan_us, 0.50))?;
+        py_dict.set_item("p99_scan_us", percentile(&stats.scan_us, 0.99))?;
+        Ok(py_dict.into_any().unbind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_matches_single_detector() {
+        let workers = Workers::new(PIIConfig::default(), 3).unwrap();
+        let detector = Detector::from_config(PIIConfig::default()).unwrap();
+        let texts: Vec<String> = (0..50)
+            .map(|i| format!("row {} SSN 123-45-{:04} mail u{}@example.com", i, i + 1, i))
+            .collect();
+
+        let results = workers.map(&texts);
+        assert_eq!(results.len(), texts.len());
+        for (text, detections) in texts.iter().zip(&results) {
+            assert_eq!(
+                format!("{:?}", detections),
+                format!("{:?}", detector.detect_internal(text))
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_workers_rejected() {
+        assert!(matches!(
+            Workers::new(PIIConfig::default(), 0),
+            Err(PIIError::InvalidValue {
+                field: "workers",
+                ..
+            })
+        ));
+    }
+
+    fn queue(overflow: Overflow, block_timeout: Duration) -> BoundedQueue<u32> {
+        BoundedQueue::new(QueueLimits {
+            max_queue: Some(2),
+            overflow,
+            block_timeout,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_overflow_reject_and_shed() {
+        let reject = queue(Overflow::Reject, Duration::ZERO);
+        reject.push(1).unwrap();
+        reject.push(2).unwrap();
+        assert!(matches!(
+            reject.push(3),
+            Err(PIIError::QueueFull { capacity: 2 })
+        ));
+        assert_eq!(reject.depth(), 2);
+
+        let shed = queue(Overflow::ShedOldest, Duration::ZERO);
+        shed.push(1).unwrap();
+        shed.push(2).unwrap();
+        assert_eq!(shed.push(3).unwrap(), Some(1));
+        assert_eq!(shed.pop().map(|(item, _)| item), Some(2));
+        assert_eq!(shed.lock().shed, 1);
+    }
+
+    #[test]
+    fn test_overflow_block_waits_for_room() {
+        let blocking = Arc::new(queue(Overflow::Block, Duration::from_secs(5)));
+        blocking.push(1).unwrap();
+        blocking.push(2).unwrap();
+
+        let worker = {
+            let blocking = Arc::clone(&blocking);
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                blocking.pop()
+            })
+        };
+        assert_eq!(blocking.push(3).unwrap(), None);
+        assert_eq!(worker.join().unwrap().map(|(item, _)| item), Some(1));
+
+        let timed_out = queue(Overflow::Block, Duration::from_millis(10));
+        timed_out.push(1).unwrap();
+        timed_out.push(2).unwrap();
+        assert!(timed_out.push(3).is_err());
+        assert_eq!(timed_out.lock().rejected, 1);
+    }
+}
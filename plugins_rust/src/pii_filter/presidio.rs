@@ -0,0 +1,148 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Microsoft Presidio compatibility for PII Filter
+//
+// Presidio's `AnalyzerEngine.analyze()` returns `RecognizerResult`s with an
+// entity name from its predefined vocabulary, character offsets and a score.
+// Producing the same shape lets consumers written against Presidio run on
+// this engine unchanged.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use super::config::PIIType;
+use super::detector::Detection;
+
+/// Recognizer name reported in `recognition_metadata`
+pub const RECOGNIZER_NAME: &str = "PIIDetectorRust";
+
+/// One result in Presidio's `RecognizerResult` shape
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecognizerResult {
+    pub entity_type: Cow<'static, str>,
+    /// Character (not byte) offsets, like Python string indices
+    pub start: usize,
+    pub end: usize,
+    pub score: f64,
+    pub pii_type: PIIType,
+}
+
+/// Presidio entity name for a detection
+///
+/// Types Presidio has no predefined entity for use the upper-cased type name
+/// (`AWS_KEY`, `POSTAL_CODE`, ...).
+pub fn entity_type(pii_type: PIIType, detection: &Detection) -> Cow<'static, str> {
+    Cow::Borrowed(match pii_type {
+        PIIType::Ssn => "US_SSN",
+        PIIType::CreditCard => "CREDIT_CARD",
+        PIIType::Email => "EMAIL_ADDRESS",
+        PIIType::Phone => "PHONE_NUMBER",
+        PIIType::IpAddress => "IP_ADDRESS",
+        PIIType::DateOfBirth => "DATE_TIME",
+        PIIType::Passport => "US_PASSPORT",
+        PIIType::DriverLicense => "US_DRIVER_LICENSE",
+        PIIType::BankAccount if is_iban(&detection.value) => "IBAN_CODE",
+        PIIType::BankAccount => "US_BANK_NUMBER",
+        PIIType::SocialHandle if detection.value.contains('/') => "URL",
+        _ => return Cow::Owned(pii_type.as_str().to_uppercase()),
+    })
+}
+
+/// Built-in type for a Presidio entity name, if there is one
+pub fn pii_type_for_entity(entity: &str) -> Option<PIIType> {
+    Some(match entity {
+        "US_SSN" => PIIType::Ssn,
+        "CREDIT_CARD" => PIIType::CreditCard,
+        "EMAIL_ADDRESS" => PIIType::Email,
+        "PHONE_NUMBER" => PIIType::Phone,
+        "IP_ADDRESS" => PIIType::IpAddress,
+        "DATE_TIME" => PIIType::DateOfBirth,
+        "US_PASSPORT" => PIIType::Passport,
+        "US_DRIVER_LICENSE" => PIIType::DriverLicense,
+        "US_BANK_NUMBER" | "IBAN_CODE" => PIIType::BankAccount,
+        _ => return entity.to_lowercase().parse().ok(),
+    })
+}
+
+/// Fixed confidence per type: strongly structured formats score 1.0, while
+/// families that also match ordinary numbers and codes score lower
+pub fn score(pii_type: PIIType) -> f64 {
+    match pii_type {
+        PIIType::BankAccount | PIIType::HighEntropyString => 0.5,
+        PIIType::Passport | PIIType::PostalCode | PIIType::LicensePlate => 0.6,
+        PIIType::Phone | PIIType::DateOfBirth | PIIType::DriverLicense => 0.85,
+        _ => 1.0,
+    }
+}
+
+fn is_iban(value: &str) -> bool {
+    value.len() >= 2 && value.as_bytes()[..2].iter().all(u8::is_ascii_alphabetic)
+}
+
+/// Detections as Presidio results, ordered by position
+pub fn recognizer_results(
+    text: &str,
+    detections: &BTreeMap<PIIType, Vec<Detection>>,
+) -> Vec<RecognizerResult> {
+    let mut results: Vec<RecognizerResult> = detections
+        .iter()
+        .flat_map(|(pii_type, items)| {
+            items.iter().map(|detection| RecognizerResult {
+                entity_type: entity_type(*pii_type, detection),
+                start: detection.start,
+                end: detection.end,
+                score: score(*pii_type),
+                pii_type: *pii_type,
+            })
+        })
+        .collect();
+    results.sort_by_key(|result| (result.start, result.end));
+
+    // Convert byte offsets to character offsets in one pass over the text
+    let mut offsets: Vec<usize> = results.iter().flat_map(|r| [r.start, r.end]).collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    let mut chars = BTreeMap::new();
+    let mut next = offsets.iter().peekable();
+    for (char_idx, (byte_idx, _)) in text.char_indices().chain([(text.len(), ' ')]).enumerate() {
+        while let Some(&offset) = next.next_if(|&&offset| offset <= byte_idx) {
+            chars.insert(offset, char_idx);
+        }
+    }
+    for result in &mut results {
+        result.start = chars.get(&result.start).copied().unwrap_or(result.start);
+        result.end = chars.get(&result.end).copied().unwrap_or(result.end);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::{MaskingStrategy, PIIConfig};
+    use crate::pii_filter::Detector;
+
+    #[test]
+    fn test_presidio_entities_and_char_offsets() {
+        let detector = Detector::from_config(PIIConfig::default()).unwrap();
+        let text = "DOB né le 01/15/1985, mail jean@example.com, SSN 123-45-6789";
+        let results = recognizer_results(text, &detector.detect_internal(text));
+
+        let entities: Vec<_> = results.iter().map(|r| r.entity_type.as_ref()).collect();
+        assert_eq!(entities, vec!["DATE_TIME", "EMAIL_ADDRESS", "US_SSN"]);
+
+        let chars: Vec<char> = text.chars().collect();
+        let ssn = &results[2];
+        let value: String = chars[ssn.start..ssn.end].iter().collect();
+        assert_eq!(value, "123-45-6789");
+        assert_eq!(ssn.score, 1.0);
+    }
+
+    #[test]
+    fn test_iban_entity() {
+        let detection = Detection::new("DE89370400440532013000", 0, 0, MaskingStrategy::default());
+        assert_eq!(entity_type(PIIType::BankAccount, &detection), "IBAN_CODE");
+        assert_eq!(entity_type(PIIType::AwsKey, &detection), "AWS_KEY");
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Progress reporting for long scans
+//
+// Scans report every scan window (every file for texts shorter than one) to
+// a `Progress`, which calls its callback each time another `every` bytes
+// have been processed, and once more when the scan is done. Reports are
+// serialized and monotonic, even when files are scanned in parallel.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use super::error::{PIIError, PIIResult};
+
+/// Bytes between progress reports by default
+pub const DEFAULT_PROGRESS_BYTES: usize = 64 << 20;
+
+/// Callback taking (bytes processed, detections so far); `false` stops the scan
+type Report = Box<dyn Fn(usize, usize) -> bool + Send + Sync>;
+
+#[derive(Debug, Default)]
+struct Totals {
+    bytes: usize,
+    detections: usize,
+    /// Bytes at which the next report is due
+    next: usize,
+    /// Bytes at the last report
+    reported: Option<usize>,
+}
+
+/// Running totals of one scan and the callback they are reported to
+pub struct Progress {
+    every: usize,
+    totals: Mutex<Totals>,
+    report: Report,
+    stopped: AtomicBool,
+}
+
+impl std::fmt::Debug for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress")
+            .field("every", &self.every)
+            .field("totals", &self.totals)
+            .field("stopped", &self.stopped)
+            .finish()
+    }
+}
+
+impl Progress {
+    pub fn new(
+        every: usize,
+        report: impl Fn(usize, usize) -> bool + Send + Sync + 'static,
+    ) -> PIIResult<Self> {
+        if every == 0 {
+            return Err(PIIError::InvalidValue {
+                field: "progress_bytes",
+                value: every.to_string(),
+            });
+        }
+        Ok(Self {
+            every,
+            totals: Mutex::new(Totals {
+                next: every,
+                ..Default::default()
+            }),
+            report: Box::new(report),
+            stopped: AtomicBool::new(false),
+        })
+    }
+
+    /// Count `bytes` more processed and `detections` more found, reporting
+    /// when another `every` bytes have gone by
+    pub fn advance(&self, bytes: usize, detections: usize) {
+        let mut totals = self.totals.lock().unwrap_or_else(PoisonError::into_inner);
+        totals.bytes += bytes;
+        totals.detections += detections;
+        if totals.bytes >= totals.next && !self.is_stopped() {
+            totals.next = (totals.bytes / self.every + 1) * self.every;
+            self.call(&mut totals);
+        }
+    }
+
+    /// Report the final totals, unless the last report already did
+    pub fn finish(&self) {
+        let mut totals = self.totals.lock().unwrap_or_else(PoisonError::into_inner);
+        if !self.is_stopped() && totals.reported != Some(totals.bytes) {
+            self.call(&mut totals);
+        }
+    }
+
+    /// Whether the callback asked to stop
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    fn call(&self, totals: &mut Totals) {
+        totals.reported = Some(totals.bytes);
+        if !(self.report)(totals.bytes, totals.detections) {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_reports_every_n_bytes() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&calls);
+        let progress = Progress::new(100, move |bytes, detections| {
+            seen.lock().unwrap().push((bytes, detections));
+            bytes < 400
+        })
+        .unwrap();
+
+        progress.advance(60, 1);
+        progress.advance(60, 0);
+        progress.advance(250, 2);
+        assert!(!progress.is_stopped());
+        progress.advance(10, 0);
+        progress.advance(100, 1);
+        assert!(progress.is_stopped());
+        progress.finish();
+
+        assert_eq!(*calls.lock().unwrap(), vec![(120, 1), (370, 3), (480, 4)]);
+        assert!(Progress::new(0, |_, _| true).is_err());
+    }
+}
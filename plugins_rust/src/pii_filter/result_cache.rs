@@ -0,0 +1,396 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Scan result cache shared by all detectors
+//
+// Detections depend only on the policy and the text, so one process-wide LRU
+// keyed by (tenant, policy hash, call settings, content hash) lets identical
+// payloads reuse a result whichever detector or worker thread scans them.
+// Tenants never share entries, so one can't learn what another scanned, and
+// per-tenant quotas keep one busy tenant from evicting everyone else's
+// entries. Entries hold only the spans of detections: values are read back
+// from the text on a hit, so no PII outlives the scan in the cache. Disabled
+// until `configure_result_cache()` gives it a size.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use super::config::{ConfigOverrides, MaskingStrategy, PIIType};
+use super::detector::Detection;
+use super::stats::ScanTally;
+
+/// SHA-256 of everything a scan result depends on
+pub type Key = [u8; 32];
+
+/// A detection without its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Span {
+    pii_type: PIIType,
+    start: usize,
+    end: usize,
+    mask_strategy: MaskingStrategy,
+    country: Option<String>,
+    subtype: Option<String>,
+}
+
+/// Detections of one scan, as spans, the matches it skipped as already
+/// masked and the pattern counters it moved
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CachedScan {
+    spans: Vec<Span>,
+    pub already_masked: usize,
+    pub tally: ScanTally,
+}
+
+impl CachedScan {
+    pub fn new(
+        detections: &BTreeMap<PIIType, Vec<Detection>>,
+        already_masked: usize,
+        tally: ScanTally,
+    ) -> Self {
+        let spans = detections
+            .iter()
+            .flat_map(|(&pii_type, items)| {
+                items.iter().map(move |detection| Span {
+                    pii_type,
+                    start: detection.start,
+                    end: detection.end,
+                    mask_strategy: detection.mask_strategy,
+                    country: detection.country.clone(),
+                    subtype: detection.subtype.clone(),
+                })
+            })
+            .collect();
+        Self {
+            spans,
+            already_masked,
+            tally,
+        }
+    }
+
+    /// The detections in `text`, the text whose scan this is
+    pub fn detections(&self, text: &str) -> BTreeMap<PIIType, Vec<Detection>> {
+        let mut detections: BTreeMap<PIIType, Vec<Detection>> = BTreeMap::new();
+        for span in &self.spans {
+            detections
+                .entry(span.pii_type)
+                .or_default()
+                .push(Detection {
+                    value: text[span.start..span.end].to_string(),
+                    start: span.start,
+                    end: span.end,
+                    mask_strategy: span.mask_strategy,
+                    country: span.country.clone(),
+                    subtype: span.subtype.clone(),
+                });
+        }
+        detections
+    }
+}
+
+static SHARED: Lazy<ResultCache> = Lazy::new(|| ResultCache::new(0, 0));
+
+/// The cache all detectors of the process share
+pub fn shared() -> &'static ResultCache {
+    &SHARED
+}
+
+/// Key of scanning `text` under the policy `policy_hash` with `overrides`,
+/// for the overrides' tenant only
+pub fn key(policy_hash: &str, overrides: &ConfigOverrides, text: &str) -> Key {
+    let mut hasher = Sha256::new();
+    let tenant = overrides.tenant.as_deref().unwrap_or("");
+    hasher.update((tenant.len() as u64).to_le_bytes());
+    hasher.update(tenant.as_bytes());
+    hasher.update(policy_hash.as_bytes());
+    // Per-call settings that change which detections are made and how
+    // they are tagged
+    let mut disabled: Vec<&str> = overrides
+        .disabled_types
+        .iter()
+        .map(PIIType::as_str)
+        .collect();
+    disabled.sort_unstable();
+    let enabled = overrides.enabled_types.as_ref().map(|types| {
+        let mut enabled: Vec<&str> = types.iter().map(PIIType::as_str).collect();
+        enabled.sort_unstable();
+        enabled
+    });
+    let scope = format!("{:?}|{:?}|{:?}", disabled, enabled, overrides.mask_strategy);
+    hasher.update((scope.len() as u64).to_le_bytes());
+    hasher.update(scope.as_bytes());
+    hasher.update(text.as_bytes());
+    hasher.finalize().into()
+}
+
+struct Entry {
+    scan: Arc<CachedScan>,
+    tenant: String,
+    /// Last use, the entry's position in the LRU orders
+    tick: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<Key, Entry>,
+    /// Tick of last use -> key, least recent first
+    order: BTreeMap<u64, Key>,
+    /// The same per tenant that stored the entries
+    tenants: HashMap<String, BTreeMap<u64, Key>>,
+    tick: u64,
+    tenant_quota: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    quota_evictions: u64,
+}
+
+impl Lru {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn remove(&mut self, key: &Key) {
+        let Some(entry) = self.entries.remove(key) else {
+            return;
+        };
+        self.order.remove(&entry.tick);
+        if let Some(order) = self.tenants.get_mut(&entry.tenant) {
+            order.remove(&entry.tick);
+            if order.is_empty() {
+                self.tenants.remove(&entry.tenant);
+            }
+        }
+    }
+}
+
+/// LRU of scan results with a global size and per-tenant quotas
+pub struct ResultCache {
+    /// Entries kept at most, 0 disabling the cache
+    max_entries: AtomicUsize,
+    lru: Mutex<Lru>,
+}
+
+impl ResultCache {
+    /// A cache of `max_entries`, each tenant holding at most `tenant_quota`
+    /// of them (0 = no quota)
+    pub fn new(max_entries: usize, tenant_quota: usize) -> Self {
+        Self {
+            max_entries: AtomicUsize::new(max_entries),
+            lru: Mutex::new(Lru {
+                tenant_quota,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Resize the cache, dropping all entries and counters
+    pub fn configure(&self, max_entries: usize, tenant_quota: usize) {
+        let mut lru = self.lock();
+        *lru = Lru {
+            tenant_quota,
+            ..Default::default()
+        };
+        self.max_entries.store(max_entries, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_entries.load(Ordering::Relaxed) > 0
+    }
+
+    /// The cached result of `key`, marking it recently used
+    pub fn get(&self, key: &Key) -> Option<Arc<CachedScan>> {
+        let mut lru = self.lock();
+        let tick = lru.next_tick();
+        let Some(entry) = lru.entries.get_mut(key) else {
+            lru.misses += 1;
+            return None;
+        };
+        let (previous, scan) = (entry.tick, Arc::clone(&entry.scan));
+        entry.tick = tick;
+        let tenant = entry.tenant.clone();
+        lru.order.remove(&previous);
+        lru.order.insert(tick, *key);
+        if let Some(order) = lru.tenants.get_mut(&tenant) {
+            order.remove(&previous);
+            order.insert(tick, *key);
+        }
+        lru.hits += 1;
+        Some(scan)
+    }
+
+    /// Store the result of `key`, charged to `tenant` (`""` for none),
+    /// evicting the tenant's least recently used entry when over quota and
+    /// then the overall least recently used ones
+    pub fn insert(&self, key: Key, scan: CachedScan, tenant: &str) {
+        let max_entries = self.max_entries.load(Ordering::Relaxed);
+        if max_entries == 0 {
+            return;
+        }
+        let mut lru = self.lock();
+        lru.remove(&key);
+
+        let quota = lru.tenant_quota;
+        let charged = lru.tenants.get(tenant).map_or(0, BTreeMap::len);
+        if quota > 0 && charged >= quota {
+            let oldest = lru.tenants[tenant].values().next().copied();
+            if let Some(oldest) = oldest {
+                lru.remove(&oldest);
+                lru.quota_evictions += 1;
+            }
+        }
+        while lru.entries.len() >= max_entries {
+            let Some(oldest) = lru.order.values().next().copied() else {
+                break;
+            };
+            lru.remove(&oldest);
+            lru.evictions += 1;
+        }
+
+        let tick = lru.next_tick();
+        lru.order.insert(tick, key);
+        lru.tenants
+            .entry(tenant.to_string())
+            .or_default()
+            .insert(tick, key);
+        lru.entries.insert(
+            key,
+            Entry {
+                scan: Arc::new(scan),
+                tenant: tenant.to_string(),
+                tick,
+            },
+        );
+    }
+
+    /// Entries currently charged to each tenant
+    pub fn tenant_entries(&self) -> BTreeMap<String, usize> {
+        self.lock()
+            .tenants
+            .iter()
+            .map(|(tenant, order)| (tenant.clone(), order.len()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        self.lru.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Size the scan result cache shared by all detectors of the process
+///
+/// Detectors whose policies hash equally reuse each other's results for
+/// identical texts, across worker threads but never across tenants. Only
+/// detection spans are kept, not the detected values. Reconfiguring drops
+/// all cached results.
+///
+/// # Arguments
+/// * `max_entries` - Results kept at most (0 disables the cache, the default)
+/// * `tenant_quota` - Results one tenant's scans may hold at most (0 = no quota);
+///   the tenant is the plugin hook context's, or the `tenant` override
+#[pyfunction]
+#[pyo3(signature = (max_entries, tenant_quota=0))]
+pub fn configure_result_cache(max_entries: usize, tenant_quota: usize) {
+    shared().configure(max_entries, tenant_quota);
+}
+
+/// Statistics of the shared scan result cache
+///
+/// # Returns
+/// ```python
+/// {"max_entries": 10000, "tenant_quota": 1000, "entries": 812, "hits": 5210,
+///  "misses": 940, "evictions": 0, "quota_evictions": 128,
+///  "tenants": {"acme": 640, "": 172}}
+/// ```
+/// Scans without a tenant are charged to `""`.
+#[pyfunction]
+pub fn result_cache_stats(py: Python) -> PyResult<Py<PyDict>> {
+    let cache = shared();
+    let py_dict = PyDict::new(py);
+    py_dict.set_item("max_entries", cache.max_entries.load(Ordering::Relaxed))?;
+    {
+        let lru = cache.lock();
+        py_dict.set_item("tenant_quota", lru.tenant_quota)?;
+        py_dict.set_item("entries", lru.entries.len())?;
+        py_dict.set_item("hits", lru.hits)?;
+        py_dict.set_item("misses", lru.misses)?;
+        py_dict.set_item("evictions", lru.evictions)?;
+        py_dict.set_item("quota_evictions", lru.quota_evictions)?;
+    }
+    py_dict.set_item("tenants", cache.tenant_entries())?;
+    Ok(py_dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(count: usize) -> CachedScan {
+        CachedScan::new(&BTreeMap::new(), count, ScanTally::default())
+    }
+
+    #[test]
+    fn test_cached_scan_keeps_no_values() {
+        let text = "mail john@example.com";
+        let detections = BTreeMap::from([(
+            PIIType::Email,
+            vec![Detection::new(
+                "john@example.com",
+                5,
+                21,
+                MaskingStrategy::Partial,
+            )],
+        )]);
+        let cached = CachedScan::new(&detections, 0, ScanTally::default());
+        assert!(!format!("{:?}", cached).contains("john"));
+        let restored = cached.detections(text);
+        assert_eq!(restored[&PIIType::Email][0].value, "john@example.com");
+        assert_eq!(CachedScan::new(&restored, 0, ScanTally::default()), cached);
+    }
+
+    #[test]
+    fn test_lru_with_tenant_quota() {
+        let cache = ResultCache::new(3, 2);
+        let overrides = ConfigOverrides::default();
+        let keys: Vec<Key> = (0..4)
+            .map(|i| key("policy", &overrides, &format!("text {}", i)))
+            .collect();
+        assert_ne!(keys[0], key("other policy", &overrides, "text 0"));
+        let tenant = ConfigOverrides {
+            tenant: Some("acme".into()),
+            ..Default::default()
+        };
+        assert_ne!(keys[0], key("policy", &tenant, "text 0"));
+
+        cache.insert(keys[0], scan(0), "acme");
+        cache.insert(keys[1], scan(1), "acme");
+        // Over quota: acme's least recently used entry makes room
+        cache.insert(keys[2], scan(2), "acme");
+        assert!(cache.get(&keys[0]).is_none());
+        assert_eq!(cache.get(&keys[1]).unwrap().already_masked, 1);
+
+        // A full cache evicts the least recent overall
+        cache.insert(keys[3], scan(3), "");
+        cache.insert(keys[0], scan(0), "");
+        assert!(cache.get(&keys[2]).is_none());
+        assert_eq!(cache.len(), 3);
+        assert_eq!(
+            cache.tenant_entries(),
+            BTreeMap::from([("".to_string(), 2), ("acme".to_string(), 1)])
+        );
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Re-identification risk analysis for PII Filter
+//
+// Per-field masking treats every value in isolation, but quasi-identifiers
+// that are harmless alone (birth date, ZIP code, gender) single out most
+// individuals when they co-occur in one payload. This pass counts them across
+// the whole payload and scores the combination.
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::collections::BTreeMap;
+
+use super::config::PIIType;
+use super::detector::Detection;
+use super::validation;
+
+/// ZIP-like number; counted only with address context before it
+static POSTAL_CODE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b\d{5}(?:-\d{4})?\b").unwrap());
+
+/// Gender value: a labeled one ("sex: F", "gender=woman") or a standalone
+/// gender term, but not words like "man" or "sex" on their own
+static GENDER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(?:gender|sex)\s*[:=]\s*(?:m|f|x|male|female|man|woman|non-?binary|other)|male|female|non-?binary|transgender)\b",
+    )
+    .unwrap()
+});
+
+/// Attribute that can help re-identify a person in combination with others
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QuasiIdentifier {
+    DateOfBirth,
+    PostalCode,
+    Gender,
+}
+
+impl QuasiIdentifier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuasiIdentifier::DateOfBirth => "date_of_birth",
+            QuasiIdentifier::PostalCode => "postal_code",
+            QuasiIdentifier::Gender => "gender",
+        }
+    }
+}
+
+/// Quasi-identifier counts accumulated over one payload
+#[derive(Debug, Clone, Default)]
+pub struct RiskReport {
+    pub counts: BTreeMap<QuasiIdentifier, usize>,
+}
+
+impl RiskReport {
+    /// Count quasi-identifiers in one text field
+    ///
+    /// `detections` are the detector's results for `text`; postal-code-like
+    /// numbers count only after an address keyword or "City, ST", and not
+    /// inside another detection (e.g. part of an SSN).
+    pub fn add_text(&mut self, text: &str, detections: &BTreeMap<PIIType, Vec<Detection>>) {
+        if let Some(items) = detections.get(&PIIType::DateOfBirth) {
+            self.add(QuasiIdentifier::DateOfBirth, items.len());
+        }
+        if let Some(items) = detections.get(&PIIType::PostalCode) {
+            self.add(QuasiIdentifier::PostalCode, items.len());
+        }
+
+        let inside_detection = |start: usize, end: usize| {
+            detections
+                .values()
+                .flatten()
+                .any(|d| start < d.end && end > d.start)
+        };
+        let postal_codes = POSTAL_CODE
+            .find_iter(text)
+            .filter(|m| {
+                !inside_detection(m.start(), m.end())
+                    && validation::has_address_context(text, m.start(), m.end())
+            })
+            .count();
+        self.add(QuasiIdentifier::PostalCode, postal_codes);
+
+        self.add(QuasiIdentifier::Gender, GENDER.find_iter(text).count());
+    }
+
+    fn add(&mut self, qi: QuasiIdentifier, count: usize) {
+        if count > 0 {
+            *self.counts.entry(qi).or_default() += count;
+        }
+    }
+
+    /// Risk score in 0.0-1.0 from the number of distinct co-occurring
+    /// quasi-identifier kinds; all three together uniquely identify most people
+    pub fn score(&self) -> f64 {
+        match self.counts.len() {
+            0 => 0.0,
+            1 => 0.2,
+            2 => 0.6,
+            _ => 1.0,
+        }
+    }
+
+    pub fn level(&self) -> &'static str {
+        match self.counts.len() {
+            0 | 1 => "low",
+            2 => "medium",
+            _ => "high",
+        }
+    }
+
+    /// Whether no quasi-identifier was found
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Convert the report to a Python dict
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let counts = PyDict::new(py);
+        for (qi, count) in &self.counts {
+            counts.set_item(qi.as_str(), count)?;
+        }
+
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("quasi_identifiers", counts)?;
+        py_dict.set_item("risk_score", self.score())?;
+        py_dict.set_item("risk_level", self.level())?;
+        Ok(py_dict)
+    }
+}
+
+/// Same shape as `to_py_dict()`, for the JSON and msgpack reports
+impl Serialize for RiskReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let counts: BTreeMap<&str, usize> = self
+            .counts
+            .iter()
+            .map(|(qi, count)| (qi.as_str(), *count))
+            .collect();
+
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("quasi_identifiers", &counts)?;
+        map.serialize_entry("risk_score", &self.score())?;
+        map.serialize_entry("risk_level", self.level())?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combination_raises_score() {
+        let mut report = RiskReport::default();
+        report.add_text("Lives at 1 Elm St, 90210", &BTreeMap::new());
+        assert_eq!(report.level(), "low");
+
+        report.add_text("Sex: F", &BTreeMap::new());
+        assert_eq!(report.level(), "medium");
+
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::DateOfBirth,
+            vec![Detection {
+                value: "DOB: 01/15/1985".to_string(),
+                start: 0,
+                end: 15,
+                mask_strategy: Default::default(),
+                country: None,
+                subtype: None,
+            }],
+        );
+        report.add_text("DOB: 01/15/1985", &detections);
+
+        assert_eq!(report.score(), 1.0);
+        assert_eq!(report.level(), "high");
+        assert_eq!(report.counts[&QuasiIdentifier::PostalCode], 1);
+    }
+
+    #[test]
+    fn test_requires_context_and_gender_values() {
+        let mut report = RiskReport::default();
+        report.add_text("Order 90210 shipped to the man", &BTreeMap::new());
+        report.add_text("Sex education and gender studies", &BTreeMap::new());
+        assert!(report.is_empty());
+
+        report.add_text("ZIP: 90210, gender = non-binary", &BTreeMap::new());
+        assert_eq!(report.counts[&QuasiIdentifier::PostalCode], 1);
+        assert_eq!(report.counts[&QuasiIdentifier::Gender], 1);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "quasi_identifiers": {"postal_code": 1, "gender": 1},
+                "risk_score": 0.6,
+                "risk_level": "medium"
+            })
+        );
+    }
+
+    #[test]
+    fn test_postal_code_inside_detection_ignored() {
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::Phone,
+            vec![Detection {
+                value: "555-123-45678".to_string(),
+                start: 9,
+                end: 22,
+                mask_strategy: Default::default(),
+                country: None,
+                subtype: None,
+            }],
+        );
+
+        let mut report = RiskReport::default();
+        report.add_text("Address: 555-123-45678", &detections);
+
+        assert!(report.counts.is_empty());
+    }
+}
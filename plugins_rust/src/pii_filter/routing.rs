@@ -0,0 +1,255 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Routing of detections to per-type callbacks
+//
+// Callbacks registered for a type run for every detection of that type,
+// whichever method found it. Synchronous callbacks are collected in the
+// call's `CallbackBatch` and run once the scan has returned, never on the
+// threads scanning; queued ones are deferred to a bounded queue that the
+// owner drains with `poll()`, keeping slow side effects (alerting, ticket
+// creation) off the request path. Detections reach the callbacks without
+// their value unless the detector includes detection details.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+use zeroize::Zeroize;
+
+use super::config::PIIType;
+use super::detector::Detection;
+
+/// Queued callback invocations kept at most; later ones are dropped
+pub const MAX_QUEUED_CALLBACKS: usize = 10_000;
+
+/// Side effect of a detection: (type, detection, field path)
+pub type Callback = Arc<dyn Fn(PIIType, &Detection, &str) + Send + Sync>;
+
+struct Route {
+    callback: Callback,
+    queued: bool,
+}
+
+/// Deferred invocation of a callback
+struct Pending {
+    callback: Callback,
+    pii_type: PIIType,
+    detection: Detection,
+    path: String,
+}
+
+impl Pending {
+    fn run(self) {
+        (self.callback)(self.pii_type, &self.detection, &self.path);
+    }
+}
+
+/// Synchronous callback invocations of one call, run when the last handle
+/// to it is dropped, i.e. after the call's scans have returned
+#[derive(Default)]
+pub struct CallbackBatch {
+    calls: Mutex<Vec<Pending>>,
+}
+
+impl CallbackBatch {
+    fn lock_calls(&self) -> MutexGuard<'_, Vec<Pending>> {
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for CallbackBatch {
+    fn drop(&mut self) {
+        let calls = std::mem::take(&mut *self.lock_calls());
+        for call in calls {
+            call.run();
+        }
+    }
+}
+
+impl fmt::Debug for CallbackBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackBatch")
+            .field("calls", &self.lock_calls().len())
+            .finish()
+    }
+}
+
+/// Callbacks registered per type, and the queue of deferred invocations
+#[derive(Default)]
+pub struct DetectionRouter {
+    routes: RwLock<BTreeMap<PIIType, Vec<Route>>>,
+    /// Whether any route is registered, so scans skip routing cheaply
+    active: AtomicBool,
+    pending: Mutex<VecDeque<Pending>>,
+    dropped: AtomicU64,
+}
+
+impl DetectionRouter {
+    /// Run `callback` for every detection of `pii_type`, deferred to
+    /// `poll()` when `queued`
+    pub fn register(&self, pii_type: PIIType, callback: Callback, queued: bool) {
+        self.routes
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(pii_type)
+            .or_default()
+            .push(Route { callback, queued });
+        self.active.store(true, Ordering::Relaxed);
+    }
+
+    /// Remove the callbacks of `pii_type`, or all of them; invocations
+    /// already queued stay queued
+    pub fn clear(&self, pii_type: Option<PIIType>) {
+        let mut routes = self.routes.write().unwrap_or_else(PoisonError::into_inner);
+        match pii_type {
+            Some(pii_type) => {
+                routes.remove(&pii_type);
+            }
+            None => routes.clear(),
+        }
+        self.active.store(!routes.is_empty(), Ordering::Relaxed);
+    }
+
+    /// Dispatch the detections of one scan: synchronous callbacks go to
+    /// `batch`, queued ones to the queue; without `details` the detections
+    /// are passed on without their value
+    pub fn route(
+        &self,
+        detections: &BTreeMap<PIIType, Vec<Detection>>,
+        path: &str,
+        details: bool,
+        batch: &CallbackBatch,
+    ) {
+        if !self.active.load(Ordering::Relaxed) || detections.is_empty() {
+            return;
+        }
+        // Callbacks are collected first so they run without the lock held,
+        // free to register more callbacks
+        let matched: Vec<(PIIType, Vec<(Callback, bool)>)> = {
+            let routes = self.routes.read().unwrap_or_else(PoisonError::into_inner);
+            detections
+                .keys()
+                .filter_map(|pii_type| {
+                    let routes = routes.get(pii_type)?;
+                    let callbacks = routes
+                        .iter()
+                        .map(|route| (Arc::clone(&route.callback), route.queued))
+                        .collect();
+                    Some((*pii_type, callbacks))
+                })
+                .collect()
+        };
+
+        for (pii_type, callbacks) in matched {
+            for detection in &detections[&pii_type] {
+                for (callback, queued) in &callbacks {
+                    let mut detection = detection.clone();
+                    if !details {
+                        detection.value.zeroize();
+                    }
+                    let call = Pending {
+                        callback: Arc::clone(callback),
+                        pii_type,
+                        detection,
+                        path: path.to_string(),
+                    };
+                    if !queued {
+                        batch.lock_calls().push(call);
+                        continue;
+                    }
+                    let mut pending = self.lock_pending();
+                    if pending.len() == MAX_QUEUED_CALLBACKS {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        continue;
+                    }
+                    pending.push_back(call);
+                }
+            }
+        }
+    }
+
+    /// Run up to `max` queued invocations (all when `None`), oldest first;
+    /// returns how many ran
+    pub fn poll(&self, max: Option<usize>) -> usize {
+        let mut ran = 0;
+        while max.is_none_or(|max| ran < max) {
+            let Some(next) = self.lock_pending().pop_front() else {
+                break;
+            };
+            next.run();
+            ran += 1;
+        }
+        ran
+    }
+
+    /// Invocations waiting for `poll()`
+    pub fn pending(&self) -> usize {
+        self.lock_pending().len()
+    }
+
+    /// Invocations dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, VecDeque<Pending>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::PIIConfig;
+    use crate::pii_filter::detector::Detector;
+
+    #[test]
+    fn test_sync_and_queued_routes() {
+        let detector = Detector::from_config(PIIConfig::default()).unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |tag: &'static str| -> Callback {
+            let seen = Arc::clone(&seen);
+            Arc::new(move |pii_type, detection, path| {
+                seen.lock().unwrap().push((
+                    tag,
+                    pii_type,
+                    detection.value.clone(),
+                    path.to_string(),
+                ));
+            })
+        };
+
+        let router = DetectionRouter::default();
+        router.register(PIIType::Ssn, recorder("sync"), false);
+        router.register(PIIType::Email, recorder("queued"), true);
+        let detections = detector.detect_internal("SSN 123-45-6789, mail john@example.com");
+        let batch = CallbackBatch::default();
+        router.route(&detections, "user", true, &batch);
+        assert!(seen.lock().unwrap().is_empty());
+        drop(batch);
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(
+                "sync",
+                PIIType::Ssn,
+                "123-45-6789".to_string(),
+                "user".to_string()
+            )]
+        );
+        assert_eq!(router.pending(), 1);
+        assert_eq!(router.poll(None), 1);
+        assert_eq!(seen.lock().unwrap()[1].0, "queued");
+        assert_eq!(router.poll(None), 0);
+
+        // Without details, queued detections don't keep the value either
+        router.route(&detections, "", false, &CallbackBatch::default());
+        assert_eq!(router.poll(None), 1);
+        assert_eq!(seen.lock().unwrap()[3].2, "");
+
+        router.clear(None);
+        router.route(&detections, "", true, &CallbackBatch::default());
+        assert_eq!(seen.lock().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,392 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Import of third-party rule files as custom patterns for PII Filter
+//
+// Two formats are supported:
+//
+// - Presidio recognizer YAML (`recognizers:` list with `patterns`, and an
+//   optional `deny_list` turned into a word-list pattern)
+// - Gitleaks TOML (`[[rules]]` with `id`, `description` and `regex`)
+//
+// Presidio import needs the `yaml` feature, Gitleaks import the `toml` one.
+//
+// Rules whose regex uses features this engine lacks (look-around,
+// backreferences) are skipped and reported rather than failing the import.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::Deserialize;
+use std::path::Path;
+
+use super::config::{CustomPattern, MaskingStrategy, PIIType};
+use super::error::{PIIError, PIIResult};
+use super::presidio;
+
+/// Patterns converted from a rule file, plus the rules that were skipped
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub patterns: Vec<CustomPattern>,
+    /// `(rule name, reason)`
+    pub skipped: Vec<(String, String)>,
+}
+
+impl ImportReport {
+    fn add(
+        &mut self,
+        name: String,
+        pattern: String,
+        mask_strategy: MaskingStrategy,
+        case_sensitive: bool,
+        multiline: bool,
+        secret_group: Option<usize>,
+    ) {
+        // Check the rule compiles here so one bad rule doesn't fail the detector
+        let regex = match regex::Regex::new(&pattern) {
+            Ok(regex) => regex,
+            Err(err) => {
+                let reason = err
+                    .to_string()
+                    .lines()
+                    .last()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                self.skipped.push((name, reason));
+                return;
+            }
+        };
+        // Group 0 is the whole match
+        let secret_group = secret_group.filter(|&group| group > 0);
+        if secret_group.is_some_and(|group| group >= regex.captures_len()) {
+            self.skipped
+                .push((name, "secret group out of range".to_string()));
+            return;
+        }
+        self.patterns.push(CustomPattern {
+            pattern,
+            description: name,
+            mask_strategy,
+            case_sensitive,
+            secret_group: secret_group.map(|group| group.to_string()),
+            multiline,
+            dot_matches_newline: multiline,
+            ..Default::default()
+        });
+    }
+}
+
+#[cfg(feature = "yaml")]
+#[derive(Debug, Deserialize)]
+struct PresidioFile {
+    recognizers: Vec<PresidioRecognizer>,
+}
+
+#[cfg(feature = "yaml")]
+#[derive(Debug, Deserialize)]
+struct PresidioRecognizer {
+    name: String,
+    supported_entity: Option<String>,
+    #[serde(default)]
+    supported_entities: Vec<String>,
+    #[serde(default)]
+    patterns: Vec<PresidioPattern>,
+    #[serde(default)]
+    deny_list: Vec<String>,
+}
+
+#[cfg(feature = "yaml")]
+#[derive(Debug, Deserialize)]
+struct PresidioPattern {
+    name: String,
+    regex: String,
+}
+
+#[cfg(feature = "toml")]
+#[derive(Debug, Deserialize)]
+struct GitleaksFile {
+    #[serde(default)]
+    rules: Vec<GitleaksRule>,
+}
+
+#[cfg(feature = "toml")]
+#[derive(Debug, Deserialize)]
+struct GitleaksRule {
+    id: String,
+    description: Option<String>,
+    regex: Option<String>,
+    #[serde(rename = "secretGroup")]
+    secret_group: Option<usize>,
+}
+
+/// Best-effort mask strategy for a Presidio entity name
+///
+/// Entities matching a built-in family whose values stay useful when
+/// partially shown (emails, phones, cards, SSNs) mask partially; secrets and
+/// everything else are redacted.
+pub fn strategy_for_entity(entity: &str) -> MaskingStrategy {
+    match presidio::pii_type_for_entity(entity) {
+        Some(PIIType::Ssn | PIIType::CreditCard | PIIType::Email | PIIType::Phone) => {
+            MaskingStrategy::Partial
+        }
+        _ => MaskingStrategy::Redact,
+    }
+}
+
+/// Convert Presidio recognizer YAML read from `path` to custom patterns
+///
+/// Presidio compiles with `IGNORECASE | MULTILINE | DOTALL` by default, so
+/// imported patterns do too.
+#[cfg(feature = "yaml")]
+pub fn import_presidio(path: &str, contents: &str) -> PIIResult<ImportReport> {
+    let file: PresidioFile = serde_yaml::from_str(contents).map_err(|e| PIIError::ParseFile {
+        path: path.to_string(),
+        message: e.to_string(),
+    })?;
+
+    let mut report = ImportReport::default();
+    for recognizer in file.recognizers {
+        let entity = recognizer
+            .supported_entity
+            .or_else(|| recognizer.supported_entities.into_iter().next())
+            .unwrap_or_default();
+        let strategy = strategy_for_entity(&entity);
+
+        for pattern in recognizer.patterns {
+            let name = format!("{}: {}", recognizer.name, pattern.name);
+            report.add(name, pattern.regex, strategy, false, true, None);
+        }
+        if !recognizer.deny_list.is_empty() {
+            let words: Vec<String> = recognizer
+                .deny_list
+                .iter()
+                .map(|word| deny_list_word(word))
+                .collect();
+            let pattern = format!("(?:{})", words.join("|"));
+            report.add(
+                format!("{}: deny list", recognizer.name),
+                pattern,
+                strategy,
+                false,
+                true,
+                None,
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// A deny-list word as a whole-word regex; `\b` is only added next to word
+/// characters, since `Mr.` can't be followed by a word boundary
+#[cfg(feature = "yaml")]
+fn deny_list_word(word: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let start = is_word(word.chars().next()).then_some(r"\b");
+    let end = is_word(word.chars().last()).then_some(r"\b");
+    format!(
+        "{}{}{}",
+        start.unwrap_or_default(),
+        regex::escape(word),
+        end.unwrap_or_default()
+    )
+}
+
+/// Convert Gitleaks TOML rules read from `path` to custom patterns
+///
+/// Gitleaks (Go RE2) regexes are case-sensitive unless they opt out with
+/// `(?i)`, and every rule detects a secret, so patterns are redacted.
+/// Path-only rules have no content regex and are skipped.
+#[cfg(feature = "toml")]
+pub fn import_gitleaks(path: &str, contents: &str) -> PIIResult<ImportReport> {
+    let file: GitleaksFile = toml::from_str(contents).map_err(|e| PIIError::ParseFile {
+        path: path.to_string(),
+        message: e.to_string(),
+    })?;
+
+    let mut report = ImportReport::default();
+    for rule in file.rules {
+        let name = rule.description.unwrap_or(rule.id);
+        match rule.regex {
+            Some(regex) => report.add(
+                name,
+                regex,
+                MaskingStrategy::Redact,
+                true,
+                false,
+                rule.secret_group,
+            ),
+            None => report.skipped.push((name, "no content regex".to_string())),
+        }
+    }
+    Ok(report)
+}
+
+fn report_to_py(py: Python, report: ImportReport) -> PyResult<Py<PyAny>> {
+    let patterns = PyList::empty(py);
+    for pattern in report.patterns {
+        let item = PyDict::new(py);
+        item.set_item("pattern", pattern.pattern)?;
+        item.set_item("description", pattern.description)?;
+        item.set_item(
+            "mask_strategy",
+            format!("{:?}", pattern.mask_strategy).to_lowercase(),
+        )?;
+        item.set_item("case_sensitive", pattern.case_sensitive)?;
+        item.set_item("multiline", pattern.multiline)?;
+        item.set_item("dot_matches_newline", pattern.dot_matches_newline)?;
+        if let Some(group) = pattern.secret_group {
+            item.set_item("secret_group", group)?;
+        }
+        patterns.append(item)?;
+    }
+    let skipped = PyList::empty(py);
+    for (name, reason) in report.skipped {
+        let item = PyDict::new(py);
+        item.set_item("name", name)?;
+        item.set_item("reason", reason)?;
+        skipped.append(item)?;
+    }
+
+    let py_dict = PyDict::new(py);
+    py_dict.set_item("patterns", patterns)?;
+    py_dict.set_item("skipped", skipped)?;
+    Ok(py_dict.into_any().unbind())
+}
+
+fn read_rules(path: &str) -> PIIResult<String> {
+    std::fs::read_to_string(Path::new(path)).map_err(|e| PIIError::ReadFile {
+        path: path.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Load Presidio recognizer YAML as `custom_patterns` entries
+///
+/// # Arguments
+/// * `path` - Path to a Presidio recognizers YAML file
+///
+/// # Returns
+/// ```python
+/// {
+///     "patterns": [{"pattern": "...", "description": "Zip code Recognizer: zip code (weak)",
+///                   "mask_strategy": "redact", "case_sensitive": False}],
+///     "skipped": [{"name": "...", "reason": "look-around ... is not supported"}],
+/// }
+/// ```
+/// Pass `patterns` as the detector's `custom_patterns`.
+#[cfg(feature = "yaml")]
+#[pyfunction]
+pub fn load_presidio_recognizers(py: Python, path: &str) -> PyResult<Py<PyAny>> {
+    let report = import_presidio(path, &read_rules(path)?)?;
+    report_to_py(py, report)
+}
+
+/// Load Gitleaks TOML rules as `custom_patterns` entries
+///
+/// # Arguments
+/// * `path` - Path to a Gitleaks config (`gitleaks.toml`)
+///
+/// # Returns
+/// Same shape as `load_presidio_recognizers()`
+#[cfg(feature = "toml")]
+#[pyfunction]
+pub fn load_gitleaks_rules(py: Python, path: &str) -> PyResult<Py<PyAny>> {
+    let report = import_gitleaks(path, &read_rules(path)?)?;
+    report_to_py(py, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_import_presidio() {
+        let report = import_presidio(
+            "recognizers.yaml",
+            r#"
+recognizers:
+  - name: "Zip code Recognizer"
+    supported_language: en
+    supported_entity: ZIP
+    patterns:
+      - name: zip code (weak)
+        regex: "(\\b\\d{5}(?:\\-\\d{4})?\\b)"
+        score: 0.01
+      - name: lookahead
+        regex: "\\d{3}(?=px)"
+        score: 0.1
+  - name: "Titles recognizer"
+    supported_entity: TITLE
+    deny_list: [Mr., Mrs.]
+  - name: "Email recognizer"
+    supported_entity: EMAIL_ADDRESS
+    patterns:
+      - name: email
+        regex: "\\S+@\\S+"
+        score: 0.5
+"#,
+        )
+        .unwrap();
+
+        let descriptions: Vec<_> = report.patterns.iter().map(|p| &p.description).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "Zip code Recognizer: zip code (weak)",
+                "Titles recognizer: deny list",
+                "Email recognizer: email"
+            ]
+        );
+        assert_eq!(report.patterns[1].pattern, r"(?:\bMr\.|\bMrs\.)");
+        assert_eq!(report.patterns[2].mask_strategy, MaskingStrategy::Partial);
+        assert!(report.patterns[2].dot_matches_newline);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].0, "Zip code Recognizer: lookahead");
+
+        let invalid = import_presidio("recognizers.yaml", "recognizers: 3");
+        assert!(matches!(invalid, Err(PIIError::ParseFile { .. })));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_import_gitleaks() {
+        let report = import_gitleaks(
+            "gitleaks.toml",
+            r#"
+title = "gitleaks config"
+
+[[rules]]
+id = "github-pat"
+description = "GitHub Personal Access Token"
+regex = '''ghp_[0-9a-zA-Z]{36}'''
+keywords = ["ghp_"]
+
+[[rules]]
+id = "generic-password"
+regex = '''password\s*=\s*"([^"]+)"'''
+secretGroup = 1
+
+[[rules]]
+id = "pkcs12-file"
+path = '''(?i)\.p12$'''
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(report.patterns.len(), 2);
+        assert_eq!(report.patterns[1].secret_group.as_deref(), Some("1"));
+        assert_eq!(
+            report.patterns[0].description,
+            "GitHub Personal Access Token"
+        );
+        assert!(report.patterns[0].case_sensitive);
+        assert_eq!(
+            report.skipped,
+            vec![("pkcs12-file".to_string(), "no content regex".to_string())]
+        );
+
+        let invalid = import_gitleaks("gitleaks.toml", "[[rules]\nid = 1");
+        assert!(matches!(invalid, Err(PIIError::ParseFile { .. })));
+    }
+}
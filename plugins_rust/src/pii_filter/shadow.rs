@@ -0,0 +1,529 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Shadow mode for PII Filter
+//
+// Before cutting the gateway over from the Python plugin to the Rust engine,
+// both can run side by side: one engine (the primary) answers every call,
+// the other (the shadow) also scans a sampled fraction of the traffic and the
+// two results are compared. Only divergence counts per type and latencies
+// are kept, never the texts or values, so the metrics are safe to export.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+use super::config::{PIIConfig, PIIType};
+use super::detector::{Detection, Detector};
+use super::error::{PIIError, PIIResult};
+use super::stats::{percentile, push_latency};
+
+/// One detection, as either engine reports it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub pii_type: String,
+    pub start: usize,
+    pub end: usize,
+    pub value: String,
+    pub mask_strategy: String,
+}
+
+impl Span {
+    fn overlaps(&self, other: &Span) -> bool {
+        self.pii_type == other.pii_type && self.start < other.end && other.start < self.end
+    }
+
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("type", &self.pii_type)?;
+        py_dict.set_item("start", self.start)?;
+        py_dict.set_item("end", self.end)?;
+        py_dict.set_item("value", &self.value)?;
+        py_dict.set_item("mask_strategy", &self.mask_strategy)?;
+        Ok(py_dict)
+    }
+}
+
+/// Disagreements on one text; pairs are (python, rust)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaseDiff {
+    pub name: String,
+    pub missed: Vec<Span>,
+    pub extra: Vec<Span>,
+    pub different_span: Vec<(Span, Span)>,
+    pub different_strategy: Vec<(Span, Span)>,
+}
+
+impl CaseDiff {
+    pub fn is_match(&self) -> bool {
+        self.missed.is_empty()
+            && self.extra.is_empty()
+            && self.different_span.is_empty()
+            && self.different_strategy.is_empty()
+    }
+}
+
+/// Compare the detections of both engines on one text
+///
+/// Spans of the same type, start and end are the same detection; otherwise
+/// overlapping spans of the same type pair up as a bounds difference.
+pub fn compare(name: &str, python: &[Span], rust: &[Span]) -> CaseDiff {
+    let mut diff = CaseDiff {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let mut unmatched: Vec<&Span> = rust.iter().collect();
+
+    for expected in python {
+        let exact = unmatched.iter().position(|actual| {
+            actual.pii_type == expected.pii_type
+                && actual.start == expected.start
+                && actual.end == expected.end
+        });
+        if let Some(idx) = exact {
+            let actual = unmatched.remove(idx);
+            if actual.mask_strategy != expected.mask_strategy {
+                diff.different_strategy
+                    .push((expected.clone(), actual.clone()));
+            }
+            continue;
+        }
+        match unmatched
+            .iter()
+            .position(|actual| actual.overlaps(expected))
+        {
+            Some(idx) => {
+                let actual = unmatched.remove(idx);
+                diff.different_span.push((expected.clone(), actual.clone()));
+            }
+            None => diff.missed.push(expected.clone()),
+        }
+    }
+    diff.extra = unmatched.into_iter().cloned().collect();
+    diff
+}
+
+/// String behind a Python `str` enum member (or a plain `str`)
+pub fn enum_str(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    match value.getattr("value") {
+        Ok(inner) => inner.extract(),
+        Err(_) => value.extract(),
+    }
+}
+
+/// Spans of a `detect()` result of either engine; `with_values` copies the
+/// detected values, otherwise they are left empty
+pub fn spans_from_py(detections: &Bound<'_, PyAny>, with_values: bool) -> PyResult<Vec<Span>> {
+    let mut spans = Vec::new();
+    for (pii_type, items) in detections.cast::<PyDict>()?.iter() {
+        let pii_type = enum_str(&pii_type)?;
+        for item in items.cast::<PyList>()?.iter() {
+            let field = |name: &str| item.get_item(name);
+            spans.push(Span {
+                pii_type: pii_type.clone(),
+                start: field("start")?.extract()?,
+                end: field("end")?.extract()?,
+                value: if with_values {
+                    field("value")?.extract()?
+                } else {
+                    String::new()
+                },
+                mask_strategy: enum_str(&field("mask_strategy")?)?,
+            });
+        }
+    }
+    spans.sort();
+    Ok(spans)
+}
+
+/// Spans of Rust detections, without values
+pub fn spans_from_detections(detections: &BTreeMap<PIIType, Vec<Detection>>) -> Vec<Span> {
+    let mut spans: Vec<Span> = detections
+        .iter()
+        .flat_map(|(pii_type, items)| {
+            items.iter().map(|detection| Span {
+                pii_type: pii_type.as_str().to_string(),
+                start: detection.start,
+                end: detection.end,
+                value: String::new(),
+                mask_strategy: format!("{:?}", detection.mask_strategy).to_lowercase(),
+            })
+        })
+        .collect();
+    spans.sort();
+    spans
+}
+
+/// Which engine answers the calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Primary {
+    #[default]
+    Python,
+    Rust,
+}
+
+impl Primary {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Primary::Python => "python",
+            Primary::Rust => "rust",
+        }
+    }
+}
+
+impl FromStr for Primary {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "python" => Ok(Primary::Python),
+            "rust" => Ok(Primary::Rust),
+            _ => Err(PIIError::InvalidValue {
+                field: "primary",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Picks an evenly spread `rate` fraction of calls
+///
+/// Deterministic rather than random, so a rate of 0.25 shadows exactly
+/// every fourth call and low-traffic tests see the expected count.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    rate: f64,
+    seen: u64,
+}
+
+impl Sampler {
+    pub fn new(rate: f64) -> PIIResult<Self> {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(PIIError::InvalidValue {
+                field: "sample_rate",
+                value: rate.to_string(),
+            });
+        }
+        Ok(Self { rate, seen: 0 })
+    }
+
+    /// Whether the next call is sampled
+    pub fn sample(&mut self) -> bool {
+        let before = (self.seen as f64 * self.rate).floor();
+        self.seen += 1;
+        (self.seen as f64 * self.rate).floor() > before
+    }
+}
+
+/// Divergence counters; missed/extra are from the Rust engine's point of
+/// view whichever engine is primary
+#[derive(Debug, Default)]
+pub struct ShadowStats {
+    pub calls: u64,
+    pub sampled: u64,
+    /// Sampled calls on which the engines disagree
+    pub diverged: u64,
+    /// Sampled calls on which the shadow engine raised
+    pub shadow_errors: u64,
+    pub missed: BTreeMap<String, u64>,
+    pub extra: BTreeMap<String, u64>,
+    pub different_span: BTreeMap<String, u64>,
+    pub different_strategy: BTreeMap<String, u64>,
+    /// Recent scan times of sampled calls in microseconds (bounded windows)
+    pub python_us: VecDeque<f64>,
+    pub rust_us: VecDeque<f64>,
+}
+
+impl ShadowStats {
+    /// Add the comparison of one sampled call
+    pub fn record(&mut self, diff: &CaseDiff) {
+        if diff.is_match() {
+            return;
+        }
+        self.diverged += 1;
+        let count = |counts: &mut BTreeMap<String, u64>, span: &Span| {
+            *counts.entry(span.pii_type.clone()).or_default() += 1;
+        };
+        diff.missed
+            .iter()
+            .for_each(|span| count(&mut self.missed, span));
+        diff.extra
+            .iter()
+            .for_each(|span| count(&mut self.extra, span));
+        for (python, _) in &diff.different_span {
+            count(&mut self.different_span, python);
+        }
+        for (python, _) in &diff.different_strategy {
+            count(&mut self.different_strategy, python);
+        }
+    }
+}
+
+/// Rust detector and Python reference side by side
+///
+/// The primary engine answers every call; on a sampled fraction of calls
+/// the shadow engine scans the same text and the results are compared.
+/// Shadow failures are counted, never raised, so the shadow cannot break
+/// the traffic it observes.
+///
+/// ```python
+/// from plugins.pii_filter.pii_filter import PIIDetector, PIIFilterConfig
+/// from plugins_rust import ShadowDetector
+///
+/// reference = PIIDetector(PIIFilterConfig(**config)).detect
+/// detector = ShadowDetector(config, reference, sample_rate=0.05)
+/// detections = detector.detect(text)  # the Python result
+/// detector.stats()["divergence_rate"]
+/// ```
+#[pyclass(name = "ShadowDetector", module = "plugins_rust", frozen)]
+pub struct ShadowDetector {
+    detector: Detector,
+    reference: Py<PyAny>,
+    primary: Primary,
+    sampler: Mutex<Sampler>,
+    stats: Mutex<ShadowStats>,
+}
+
+impl ShadowDetector {
+    fn lock_stats(&self) -> MutexGuard<'_, ShadowStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn sample(&self) -> bool {
+        self.sampler
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .sample()
+    }
+
+    /// Compare a sampled call and record the outcome
+    fn shadow(
+        &self,
+        python: Option<&Bound<'_, PyAny>>,
+        rust: &BTreeMap<PIIType, Vec<Detection>>,
+        python_us: Option<std::time::Duration>,
+        rust_us: std::time::Duration,
+    ) {
+        let python = python.and_then(|python| spans_from_py(python, false).ok());
+        let mut stats = self.lock_stats();
+        stats.sampled += 1;
+        push_latency(&mut stats.rust_us, rust_us);
+        if let Some(python_us) = python_us {
+            push_latency(&mut stats.python_us, python_us);
+        }
+        match python {
+            Some(python) => stats.record(&compare("", &python, &spans_from_detections(rust))),
+            None => stats.shadow_errors += 1,
+        }
+    }
+}
+
+#[pymethods]
+impl ShadowDetector {
+    /// Create a shadow detector
+    ///
+    /// # Arguments
+    /// * `config_dict` - Rust detector configuration (same keys as `PIIDetectorRust`)
+    /// * `reference` - Python callable taking a text and returning detections
+    ///   keyed by type, e.g. the reference plugin's `PIIDetector.detect`
+    /// * `sample_rate` - Fraction of calls on which the shadow engine runs (default: 0.01)
+    /// * `primary` - Engine whose result is returned: "python" (default) or "rust"
+    #[new]
+    #[pyo3(signature = (config_dict, reference, sample_rate=0.01, primary="python"))]
+    pub fn new(
+        config_dict: &Bound<'_, PyDict>,
+        reference: Py<PyAny>,
+        sample_rate: f64,
+        primary: &str,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            detector: Detector::from_config(PIIConfig::from_py_dict(config_dict)?)?,
+            reference,
+            primary: primary.parse()?,
+            sampler: Mutex::new(Sampler::new(sample_rate)?),
+            stats: Mutex::new(ShadowStats::default()),
+        })
+    }
+
+    /// Engine whose result `detect()` returns
+    #[getter]
+    pub fn primary(&self) -> &'static str {
+        self.primary.as_str()
+    }
+
+    /// Detect PII with the primary engine, shadowing sampled calls
+    ///
+    /// # Returns
+    /// The primary engine's result, unchanged
+    ///
+    /// # Raises
+    /// Whatever the primary engine raises
+    pub fn detect(&self, py: Python, text: &str) -> PyResult<Py<PyAny>> {
+        self.lock_stats().calls += 1;
+        let sampled = self.sample();
+
+        match self.primary {
+            Primary::Python => {
+                let started = Instant::now();
+                let result = self.reference.bind(py).call1((text,))?;
+                if sampled {
+                    let python_us = started.elapsed();
+                    let started = Instant::now();
+                    let rust = py.detach(|| self.detector.detect_internal(text));
+                    self.shadow(Some(&result), &rust, Some(python_us), started.elapsed());
+                }
+                Ok(result.unbind())
+            }
+            Primary::Rust => {
+                let started = Instant::now();
+                let rust = py.detach(|| self.detector.detect_internal(text));
+                let rust_us = started.elapsed();
+                if sampled {
+                    let started = Instant::now();
+                    let python = self.reference.bind(py).call1((text,));
+                    let python_us = python.is_ok().then(|| started.elapsed());
+                    self.shadow(python.as_ref().ok(), &rust, python_us, rust_us);
+                }
+                self.detector.rust_detections_to_py(py, &rust)
+            }
+        }
+    }
+
+    /// Divergence metrics since creation or the last `reset_stats()`
+    ///
+    /// Counts are per type; "missed" are detections only the Python engine
+    /// made, "extra" only the Rust engine, whichever is primary.
+    ///
+    /// # Returns
+    /// ```python
+    /// {
+    ///     "primary": "python", "calls": 10000, "sampled": 100, "diverged": 3,
+    ///     "divergence_rate": 0.03, "shadow_errors": 0,
+    ///     "missed": {"phone": 1}, "extra": {"email": 2},
+    ///     "different_span": {}, "different_strategy": {},
+    ///     "p50_python_us": 180.0, "p99_python_us": 950.0,
+    ///     "p50_rust_us": 12.0, "p99_rust_us": 60.0,
+    /// }
+    /// ```
+    pub fn stats(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let stats = self.lock_stats();
+        let compared = stats.sampled - stats.shadow_errors;
+
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("primary", self.primary.as_str())?;
+        py_dict.set_item("calls", stats.calls)?;
+        py_dict.set_item("sampled", stats.sampled)?;
+        py_dict.set_item("diverged", stats.diverged)?;
+        py_dict.set_item(
+            "divergence_rate",
+            if compared == 0 {
+                0.0
+            } else {
+                stats.diverged as f64 / compared as f64
+            },
+        )?;
+        py_dict.set_item("shadow_errors", stats.shadow_errors)?;
+        py_dict.set_item("missed", &stats.missed)?;
+        py_dict.set_item("extra", &stats.extra)?;
+        py_dict.set_item("different_span", &stats.different_span)?;
+        py_dict.set_item("different_strategy", &stats.different_strategy)?;
+        py_dict.set_item("p50_python_us", percentile(&stats.python_us, 0.50))?;
+        py_dict.set_item("p99_python_us", percentile(&stats.python_us, 0.99))?;
+        py_dict.set_item("p50_rust_us", percentile(&stats.rust_us, 0.50))?;
+        py_dict.set_item("p99_rust_us", percentile(&stats.rust_us, 0.99))?;
+        Ok(py_dict.into_any().unbind())
+    }
+
+    /// Clear the metrics
+    pub fn reset_stats(&self) {
+        *self.lock_stats() = ShadowStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_filter::config::MaskingStrategy;
+
+    fn span(pii_type: &str, start: usize, end: usize, mask_strategy: &str) -> Span {
+        Span {
+            pii_type: pii_type.to_string(),
+            start,
+            end,
+            value: String::new(),
+            mask_strategy: mask_strategy.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let python = vec![
+            span("ssn", 0, 11, "partial"),
+            span("email", 20, 36, "partial"),
+            span("phone", 40, 52, "partial"),
+            span("ip_address", 60, 70, "redact"),
+        ];
+        let rust = vec![
+            span("ssn", 0, 11, "partial"),
+            span("email", 20, 36, "redact"),
+            span("phone", 41, 52, "partial"),
+            span("aws_key", 80, 100, "redact"),
+        ];
+
+        let diff = compare("mixed", &python, &rust);
+        assert_eq!(diff.missed, vec![span("ip_address", 60, 70, "redact")]);
+        assert_eq!(diff.extra, vec![span("aws_key", 80, 100, "redact")]);
+        assert_eq!(
+            diff.different_span,
+            vec![(
+                span("phone", 40, 52, "partial"),
+                span("phone", 41, 52, "partial")
+            )]
+        );
+        assert_eq!(diff.different_strategy.len(), 1);
+        assert!(!diff.is_match());
+        assert!(compare("same", &rust, &rust).is_match());
+
+        let mut stats = ShadowStats::default();
+        stats.record(&diff);
+        stats.record(&compare("same", &rust, &rust));
+        assert_eq!(stats.diverged, 1);
+        assert_eq!(stats.missed.get("ip_address"), Some(&1));
+        assert_eq!(stats.different_strategy.get("email"), Some(&1));
+    }
+
+    #[test]
+    fn test_sampler_spreads_calls() {
+        let mut quarter = Sampler::new(0.25).unwrap();
+        let picks: Vec<bool> = (0..8).map(|_| quarter.sample()).collect();
+        assert_eq!(
+            picks,
+            vec![false, false, false, true, false, false, false, true]
+        );
+        assert!((0..100).all(|_| Sampler::new(1.0).unwrap().sample()));
+        let mut never = Sampler::new(0.0).unwrap();
+        assert!((0..100).all(|_| !never.sample()));
+        assert!(Sampler::new(1.5).is_err());
+    }
+
+    #[test]
+    fn test_spans_from_detections() {
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::Ssn,
+            vec![Detection::new(
+                "123-45-6789".to_string(),
+                4,
+                15,
+                MaskingStrategy::Partial,
+            )],
+        );
+        assert_eq!(
+            spans_from_detections(&detections),
+            vec![span("ssn", 4, 15, "partial")]
+        );
+    }
+}
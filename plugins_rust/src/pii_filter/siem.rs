@@ -0,0 +1,246 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// SIEM record formats for detection events
+//
+// Renders drained detection events as ArcSight CEF or QRadar LEEF records,
+// the message bodies SIEM syslog collectors parse natively, so no mapping
+// layer is needed in between. Records carry the masked value only.
+
+use std::str::FromStr;
+
+use super::config::PIIType;
+use super::error::PIIError;
+use super::events::DetectionEvent;
+
+const VENDOR: &str = "MCP Gateway";
+const PRODUCT: &str = "PII Filter";
+
+/// SIEM record format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFormat {
+    /// Common Event Format 0
+    Cef,
+    /// Log Event Extended Format 2.0, tab-delimited
+    Leef,
+}
+
+impl EventFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventFormat::Cef => "cef",
+            EventFormat::Leef => "leef",
+        }
+    }
+}
+
+impl FromStr for EventFormat {
+    type Err = PIIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cef" => Ok(EventFormat::Cef),
+            "leef" => Ok(EventFormat::Leef),
+            _ => Err(PIIError::InvalidValue {
+                field: "format",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Severity on the 0-10 scale both formats use
+///
+/// Credentials grant access outright, government and financial identifiers
+/// enable fraud, contact details and the rest mostly expose a person.
+pub fn severity(pii_type: PIIType) -> u8 {
+    match pii_type {
+        PIIType::AwsKey
+        | PIIType::ApiKey
+        | PIIType::ConnectionString
+        | PIIType::AuthCredential
+        | PIIType::CloudCredential => 9,
+        PIIType::Ssn
+        | PIIType::CreditCard
+        | PIIType::Passport
+        | PIIType::BankAccount
+        | PIIType::MedicalRecord => 8,
+        PIIType::DriverLicense | PIIType::DateOfBirth | PIIType::HighEntropyString => 6,
+        PIIType::Email | PIIType::Phone | PIIType::IpAddress | PIIType::Custom => 5,
+        PIIType::PostalCode | PIIType::SocialHandle | PIIType::LicensePlate => 3,
+    }
+}
+
+/// Render one event, stamped with the detector's `policy_hash`
+pub fn format_event(event: &DetectionEvent, format: EventFormat, policy_hash: &str) -> String {
+    match format {
+        EventFormat::Cef => format_cef(event, policy_hash),
+        EventFormat::Leef => format_leef(event, policy_hash),
+    }
+}
+
+fn millis(timestamp: f64) -> u64 {
+    (timestamp * 1000.0) as u64
+}
+
+/// `CEF:0|vendor|product|version|type|name|severity|extension`
+fn format_cef(event: &DetectionEvent, policy_hash: &str) -> String {
+    let pii_type = event.pii_type.as_str();
+    let mut record = format!(
+        "CEF:0|{}|{}|{}|{}|{}|{}|",
+        cef_header(VENDOR),
+        cef_header(PRODUCT),
+        cef_header(env!("CARGO_PKG_VERSION")),
+        cef_header(pii_type),
+        cef_header(&format!("PII detected: {}", pii_type)),
+        severity(event.pii_type),
+    );
+    let mut extensions = vec![
+        ("rt", millis(event.timestamp).to_string()),
+        ("cat", pii_type.to_string()),
+        ("cs1Label", "path".to_string()),
+        ("cs1", event.path.clone()),
+        ("cs2Label", "maskedValue".to_string()),
+        ("cs2", event.masked.clone()),
+        ("cs3Label", "policyHash".to_string()),
+        ("cs3", policy_hash.to_string()),
+    ];
+    if let Some(subtype) = &event.subtype {
+        extensions.push(("cs4Label", "subtype".to_string()));
+        extensions.push(("cs4", subtype.clone()));
+    }
+    let extensions: Vec<String> = extensions
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, cef_extension(&value)))
+        .collect();
+    record.push_str(&extensions.join(" "));
+    record
+}
+
+/// `LEEF:2.0|vendor|product|version|type|x09|key=value<TAB>...`
+fn format_leef(event: &DetectionEvent, policy_hash: &str) -> String {
+    let pii_type = event.pii_type.as_str();
+    let mut record = format!(
+        "LEEF:2.0|{}|{}|{}|{}|x09|",
+        leef_header(VENDOR),
+        leef_header(PRODUCT),
+        leef_header(env!("CARGO_PKG_VERSION")),
+        leef_header(pii_type),
+    );
+    let mut attributes = vec![
+        ("devTime", millis(event.timestamp).to_string()),
+        ("cat", pii_type.to_string()),
+        ("sev", severity(event.pii_type).to_string()),
+        ("path", event.path.clone()),
+        ("maskedValue", event.masked.clone()),
+        ("policyHash", policy_hash.to_string()),
+    ];
+    if let Some(subtype) = &event.subtype {
+        attributes.push(("subtype", subtype.clone()));
+    }
+    let attributes: Vec<String> = attributes
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, leef_attribute(&value)))
+        .collect();
+    record.push_str(&attributes.join("\t"));
+    record
+}
+
+/// CEF header fields escape `\` and `|`; line breaks are not allowed
+fn cef_header(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// CEF extension values escape `\` and `=`, and line breaks as `\n`/`\r`
+fn cef_extension(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | '=' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// LEEF header fields escape `|`
+fn leef_header(value: &str) -> String {
+    cef_header(value)
+}
+
+/// LEEF has no escaping within attribute values, so the delimiter and line
+/// breaks become spaces
+fn leef_attribute(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if matches!(c, '\t' | '\r' | '\n') {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> DetectionEvent {
+        DetectionEvent {
+            pii_type: PIIType::CreditCard,
+            subtype: Some("visa".to_string()),
+            path: "order|items[0]\nnote=x".to_string(),
+            masked: "****-****-****-1111".to_string(),
+            timestamp: 1767225600.25,
+        }
+    }
+
+    #[test]
+    fn test_cef_record() {
+        let record = format_event(&event(), EventFormat::Cef, "abc");
+        let version = env!("CARGO_PKG_VERSION");
+        assert_eq!(
+            record,
+            format!(
+                "CEF:0|MCP Gateway|PII Filter|{version}|credit_card|PII detected: credit_card|8|\
+                 rt=1767225600250 cat=credit_card cs1Label=path cs1=order|items[0]\\nnote\\=x \
+                 cs2Label=maskedValue cs2=****-****-****-1111 cs3Label=policyHash cs3=abc \
+                 cs4Label=subtype cs4=visa"
+            )
+        );
+        assert_eq!(cef_header("a|b\\c"), "a\\|b\\\\c");
+    }
+
+    #[test]
+    fn test_leef_record() {
+        let record = format_event(&event(), EventFormat::Leef, "abc");
+        let version = env!("CARGO_PKG_VERSION");
+        assert_eq!(
+            record,
+            format!(
+                "LEEF:2.0|MCP Gateway|PII Filter|{version}|credit_card|x09|\
+                 devTime=1767225600250\tcat=credit_card\tsev=8\tpath=order|items[0] note=x\t\
+                 maskedValue=****-****-****-1111\tpolicyHash=abc\tsubtype=visa"
+            )
+        );
+        assert!("syslog".parse::<EventFormat>().is_err());
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Policy simulation for PII Filter
+//
+// Before rolling out a policy change, both the current and the candidate
+// configuration can be run over a corpus of past payloads to see what the
+// change would do: detections only the candidate makes (added), detections
+// it would no longer make (removed), detections whose bounds or masking
+// strategy change, and the masked output of every sample that changes.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+use super::config::{PIIConfig, PIIType};
+use super::detector::Detector;
+use super::masking::MaskMemo;
+use super::shadow::{compare, spans_from_detections, CaseDiff, Span};
+
+/// How one sample fares under both configurations; span pairs are (a, b)
+#[derive(Debug, Clone)]
+pub struct SampleDiff {
+    pub index: usize,
+    /// `missed` holds the detections only `a` makes, `extra` those only `b`
+    /// makes
+    pub diff: CaseDiff,
+    pub masked_a: String,
+    pub masked_b: String,
+}
+
+impl SampleDiff {
+    pub fn is_match(&self) -> bool {
+        self.diff.is_match() && self.masked_a == self.masked_b
+    }
+}
+
+/// Result of a simulation run
+#[derive(Debug, Clone, Default)]
+pub struct SimulationReport {
+    pub total: usize,
+    /// Detections per type under each configuration
+    pub detections_a: BTreeMap<PIIType, usize>,
+    pub detections_b: BTreeMap<PIIType, usize>,
+    /// Samples on which the configurations disagree, in corpus order
+    pub changed: Vec<SampleDiff>,
+}
+
+/// Scan and mask every sample of `corpus` with both detectors
+pub fn run_simulation(a: &Detector, b: &Detector, corpus: &[String]) -> SimulationReport {
+    let results: Vec<_> = corpus
+        .par_iter()
+        .enumerate()
+        .map(|(index, text)| {
+            let (masked_a, found_a) = a.detect_and_mask(text, &mut MaskMemo::new());
+            let (masked_b, found_b) = b.detect_and_mask(text, &mut MaskMemo::new());
+            let counts = |found: &BTreeMap<PIIType, Vec<_>>| -> Vec<(PIIType, usize)> {
+                found
+                    .iter()
+                    .map(|(pii_type, items)| (*pii_type, items.len()))
+                    .collect()
+            };
+            let sample = SampleDiff {
+                index,
+                diff: compare(
+                    &format!("sample[{}]", index),
+                    &spans_from_detections(&found_a),
+                    &spans_from_detections(&found_b),
+                ),
+                masked_a: masked_a.into_owned(),
+                masked_b: masked_b.into_owned(),
+            };
+            (counts(&found_a), counts(&found_b), sample)
+        })
+        .collect();
+
+    let mut report = SimulationReport {
+        total: corpus.len(),
+        ..Default::default()
+    };
+    for (counts_a, counts_b, sample) in results {
+        for (pii_type, count) in counts_a {
+            *report.detections_a.entry(pii_type).or_default() += count;
+        }
+        for (pii_type, count) in counts_b {
+            *report.detections_b.entry(pii_type).or_default() += count;
+        }
+        if !sample.is_match() {
+            report.changed.push(sample);
+        }
+    }
+    report
+}
+
+/// Compare two detector configurations on a corpus of past payloads
+///
+/// Answers questions like "what would enabling `detect_bank_account` have
+/// masked last week?" before the change is rolled out. Samples are scanned
+/// in parallel without the GIL.
+///
+/// # Arguments
+/// * `config_a` - Current configuration dict (same keys as `PIIDetectorRust`)
+/// * `config_b` - Candidate configuration dict
+/// * `corpus` - Texts to scan, e.g. logged request bodies
+///
+/// # Returns
+/// ```python
+/// {"total": 1000, "changed": 12, "unchanged": 988,
+///  "detections": {"a": {"ssn": 40}, "b": {"ssn": 40, "bank_account": 12}},
+///  "summary": {"added": {"bank_account": 12}, "removed": {}, "different_span": {},
+///              "different_strategy": {}},
+///  "samples": [{"index": 17, "added": [{"type": "bank_account", "start": 8,
+///               "end": 20, "mask_strategy": "partial"}], "removed": [],
+///               "different_span": [{"a": {...}, "b": {...}}], "different_strategy": [],
+///               "masked_a": "Account 123456789012", "masked_b": "Account ********9012"}]}
+/// ```
+/// Spans carry no values; `masked_a`/`masked_b` show the effect.
+#[pyfunction]
+pub fn simulate(
+    py: Python,
+    config_a: &Bound<'_, PyDict>,
+    config_b: &Bound<'_, PyDict>,
+    corpus: Vec<String>,
+) -> PyResult<Py<PyDict>> {
+    let a = Detector::from_config(PIIConfig::from_py_dict(config_a)?)?;
+    let b = Detector::from_config(PIIConfig::from_py_dict(config_b)?)?;
+    let report = py.detach(|| run_simulation(&a, &b, &corpus));
+
+    let mut summary: BTreeMap<&str, BTreeMap<String, usize>> =
+        ["added", "removed", "different_span", "different_strategy"]
+            .into_iter()
+            .map(|kind| (kind, BTreeMap::new()))
+            .collect();
+    let mut count = |kind: &'static str, span: &Span| {
+        *summary
+            .get_mut(kind)
+            .expect("summary kinds")
+            .entry(span.pii_type.clone())
+            .or_default() += 1;
+    };
+    for sample in &report.changed {
+        let diff = &sample.diff;
+        diff.extra.iter().for_each(|span| count("added", span));
+        diff.missed.iter().for_each(|span| count("removed", span));
+        for (a, _) in &diff.different_span {
+            count("different_span", a);
+        }
+        for (a, _) in &diff.different_strategy {
+            count("different_strategy", a);
+        }
+    }
+
+    let span_to_py = |span: &Span| -> PyResult<Bound<'_, PyDict>> {
+        let py_dict = span.to_py_dict(py)?;
+        py_dict.del_item("value")?;
+        Ok(py_dict)
+    };
+    let spans_to_py = |spans: &[Span]| -> PyResult<Bound<'_, PyList>> {
+        let py_list = PyList::empty(py);
+        for span in spans {
+            py_list.append(span_to_py(span)?)?;
+        }
+        Ok(py_list)
+    };
+    let pairs_to_py = |pairs: &[(Span, Span)]| -> PyResult<Bound<'_, PyList>> {
+        let py_list = PyList::empty(py);
+        for (a, b) in pairs {
+            let pair = PyDict::new(py);
+            pair.set_item("a", span_to_py(a)?)?;
+            pair.set_item("b", span_to_py(b)?)?;
+            py_list.append(pair)?;
+        }
+        Ok(py_list)
+    };
+
+    let samples = PyList::empty(py);
+    for sample in &report.changed {
+        let diff = &sample.diff;
+        let item = PyDict::new(py);
+        item.set_item("index", sample.index)?;
+        item.set_item("added", spans_to_py(&diff.extra)?)?;
+        item.set_item("removed", spans_to_py(&diff.missed)?)?;
+        item.set_item("different_span", pairs_to_py(&diff.different_span)?)?;
+        item.set_item("different_strategy", pairs_to_py(&diff.different_strategy)?)?;
+        item.set_item("masked_a", &sample.masked_a)?;
+        item.set_item("masked_b", &sample.masked_b)?;
+        samples.append(item)?;
+    }
+
+    let detections = PyDict::new(py);
+    for (key, counts) in [("a", &report.detections_a), ("b", &report.detections_b)] {
+        let by_type = PyDict::new(py);
+        for (pii_type, count) in counts {
+            by_type.set_item(pii_type.as_str(), count)?;
+        }
+        detections.set_item(key, by_type)?;
+    }
+
+    let py_dict = PyDict::new(py);
+    py_dict.set_item("total", report.total)?;
+    py_dict.set_item("changed", report.changed.len())?;
+    py_dict.set_item("unchanged", report.total - report.changed.len())?;
+    py_dict.set_item("detections", detections)?;
+    py_dict.set_item("summary", summary)?;
+    py_dict.set_item("samples", samples)?;
+    Ok(py_dict.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulation_reports_added_detections() {
+        let current = PIIConfig {
+            detect_email: false,
+            ..Default::default()
+        };
+        let a = Detector::from_config(current).unwrap();
+        let b = Detector::from_config(PIIConfig::default()).unwrap();
+        let corpus = vec![
+            "SSN 123-45-6789".to_string(),
+            "Contact john@example.com".to_string(),
+            "Nothing here".to_string(),
+        ];
+
+        let report = run_simulation(&a, &b, &corpus);
+        assert_eq!(report.total, 3);
+        assert_eq!(report.changed.len(), 1);
+        let sample = &report.changed[0];
+        assert_eq!(sample.index, 1);
+        assert_eq!(sample.diff.extra.len(), 1);
+        assert_eq!(sample.diff.extra[0].pii_type, "email");
+        assert!(sample.diff.missed.is_empty());
+        assert_eq!(sample.masked_a, "Contact john@example.com");
+        assert_ne!(sample.masked_b, sample.masked_a);
+        assert_eq!(report.detections_a.get(&PIIType::Email), None);
+        assert_eq!(report.detections_b[&PIIType::Email], 1);
+        assert_eq!(report.detections_a[&PIIType::Ssn], 1);
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Per-detector statistics for PII Filter
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Duration;
+
+use super::config::{MaskingStrategy, PIIType};
+use super::detector::Detection;
+
+/// Number of most recent scan latencies kept for percentile estimates
+const LATENCY_WINDOW: usize = 1024;
+
+/// Append a latency in microseconds to a bounded window, evicting the oldest
+pub fn push_latency(window: &mut VecDeque<f64>, elapsed: Duration) {
+    if window.len() == LATENCY_WINDOW {
+        window.pop_front();
+    }
+    window.push_back(elapsed.as_secs_f64() * 1e6);
+}
+
+/// Percentile (0.0-1.0) of a latency window, 0 when empty
+pub fn percentile(window: &VecDeque<f64>, p: f64) -> f64 {
+    if window.is_empty() {
+        return 0.0;
+    }
+    let mut sorted: Vec<f64> = window.iter().copied().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let idx = ((sorted.len() as f64 - 1.0) * p).round() as usize;
+    sorted[idx]
+}
+
+/// Cumulative statistics collected by a detector across scans
+#[derive(Debug, Default, Clone)]
+pub struct DetectorStats {
+    pub total_scans: u64,
+    pub bytes_processed: u64,
+    pub detections_by_type: BTreeMap<PIIType, u64>,
+    pub masks_by_strategy: BTreeMap<MaskingStrategy, u64>,
+    /// Most recent scan latencies in microseconds (bounded window)
+    pub latencies_us: VecDeque<f64>,
+    /// Number of scans in which each pattern exceeded the match limit,
+    /// keyed by pattern description
+    pub match_limit_trips: BTreeMap<String, u64>,
+    /// Number of `process_nested` payloads cut short by each traversal limit
+    pub nesting_limit_trips: BTreeMap<&'static str, u64>,
+    /// Matches skipped because they were already masked (`skip_masked_values`)
+    pub already_masked: u64,
+    pub triage: TriageStats,
+}
+
+/// Pattern counters moved by one scan, kept with its cached result so a
+/// cache hit moves them as the scan did
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScanTally {
+    /// Detections made per pattern index
+    pub hits: BTreeMap<usize, u64>,
+    /// Indices of the patterns that hit `max_matches_per_pattern`, once per trip
+    pub match_limit_trips: Vec<usize>,
+}
+
+/// Outcomes of `likely_contains_pii`, including a confusion matrix over the
+/// calls verified against a full scan
+#[derive(Debug, Default, Clone)]
+pub struct TriageStats {
+    pub checks: u64,
+    /// Checks that ruled out PII, so a full scan could be skipped
+    pub negatives: u64,
+    pub verified: u64,
+    /// Verified checks that said "likely" but the full scan found nothing
+    pub false_positives: u64,
+    /// Verified checks that ruled out PII the full scan found; always a bug
+    pub false_negatives: u64,
+}
+
+impl TriageStats {
+    /// Record a triage result, and the full scan result when verified
+    pub fn record(&mut self, likely: bool, found: Option<bool>) {
+        self.checks += 1;
+        if !likely {
+            self.negatives += 1;
+        }
+        if let Some(found) = found {
+            self.verified += 1;
+            match (likely, found) {
+                (true, false) => self.false_positives += 1,
+                (false, true) => self.false_negatives += 1,
+                _ => {}
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &TriageStats) {
+        self.checks += other.checks;
+        self.negatives += other.negatives;
+        self.verified += other.verified;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+    }
+
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("checks", self.checks)?;
+        py_dict.set_item("negatives", self.negatives)?;
+        py_dict.set_item("verified", self.verified)?;
+        py_dict.set_item("false_positives", self.false_positives)?;
+        py_dict.set_item("false_negatives", self.false_negatives)?;
+        Ok(py_dict)
+    }
+}
+
+impl DetectorStats {
+    /// Record a completed scan
+    pub fn record_scan(
+        &mut self,
+        bytes: usize,
+        detections: &BTreeMap<PIIType, Vec<Detection>>,
+        elapsed: Duration,
+    ) {
+        self.total_scans += 1;
+        self.bytes_processed += bytes as u64;
+        for (pii_type, items) in detections {
+            *self.detections_by_type.entry(*pii_type).or_default() += items.len() as u64;
+        }
+
+        push_latency(&mut self.latencies_us, elapsed);
+    }
+
+    /// Record masking of a set of detections
+    pub fn record_masking(&mut self, detections: &BTreeMap<PIIType, Vec<Detection>>) {
+        for detection in detections.values().flatten() {
+            *self
+                .masks_by_strategy
+                .entry(detection.mask_strategy)
+                .or_default() += 1;
+        }
+    }
+
+    /// Add the statistics collected separately in `other`, e.g. by one
+    /// worker of a batch
+    pub fn merge(&mut self, other: DetectorStats) {
+        self.total_scans += other.total_scans;
+        self.bytes_processed += other.bytes_processed;
+        for (pii_type, count) in other.detections_by_type {
+            *self.detections_by_type.entry(pii_type).or_default() += count;
+        }
+        for (strategy, count) in other.masks_by_strategy {
+            *self.masks_by_strategy.entry(strategy).or_default() += count;
+        }
+        for latency in other.latencies_us {
+            if self.latencies_us.len() == LATENCY_WINDOW {
+                self.latencies_us.pop_front();
+            }
+            self.latencies_us.push_back(latency);
+        }
+        for (description, count) in other.match_limit_trips {
+            *self.match_limit_trips.entry(description).or_default() += count;
+        }
+        for (limit, count) in other.nesting_limit_trips {
+            *self.nesting_limit_trips.entry(limit).or_default() += count;
+        }
+        self.already_masked += other.already_masked;
+        self.triage.merge(&other.triage);
+    }
+
+    /// Scan latency percentile (0.0-1.0) over the recent window
+    pub fn latency_percentile(&self, p: f64) -> f64 {
+        percentile(&self.latencies_us, p)
+    }
+
+    /// Record that a pattern exceeded `max_matches_per_pattern` in a scan
+    pub fn record_match_limit_trip(&mut self, description: &str) {
+        *self
+            .match_limit_trips
+            .entry(description.to_string())
+            .or_default() += 1;
+    }
+
+    pub fn record_nesting_limit_trip(&mut self, limit: &'static str) {
+        *self.nesting_limit_trips.entry(limit).or_default() += 1;
+    }
+
+    /// Convert statistics to a Python dict
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("total_scans", self.total_scans)?;
+        py_dict.set_item("bytes_processed", self.bytes_processed)?;
+
+        let by_type = PyDict::new(py);
+        for (pii_type, count) in &self.detections_by_type {
+            by_type.set_item(pii_type.as_str(), count)?;
+        }
+        py_dict.set_item("detections_by_type", by_type)?;
+
+        let by_strategy = PyDict::new(py);
+        for (strategy, count) in &self.masks_by_strategy {
+            by_strategy.set_item(format!("{:?}", strategy).to_lowercase(), count)?;
+        }
+        py_dict.set_item("masks_by_strategy", by_strategy)?;
+
+        py_dict.set_item("p50_latency_us", self.latency_percentile(0.50))?;
+        py_dict.set_item("p99_latency_us", self.latency_percentile(0.99))?;
+
+        let trips = PyDict::new(py);
+        for (description, count) in &self.match_limit_trips {
+            trips.set_item(description, count)?;
+        }
+        py_dict.set_item("match_limit_trips", trips)?;
+        let trips = PyDict::new(py);
+        for (limit, count) in &self.nesting_limit_trips {
+            trips.set_item(limit, count)?;
+        }
+        py_dict.set_item("nesting_limit_trips", trips)?;
+        py_dict.set_item("already_masked", self.already_masked)?;
+        py_dict.set_item("triage", self.triage.to_py_dict(py)?)?;
+
+        Ok(py_dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triage_confusion_matrix() {
+        let mut triage = TriageStats::default();
+        triage.record(false, None);
+        triage.record(true, Some(false));
+        triage.record(true, Some(true));
+        triage.record(false, Some(false));
+
+        assert_eq!(
+            (triage.checks, triage.negatives, triage.verified),
+            (4, 2, 3)
+        );
+        assert_eq!((triage.false_positives, triage.false_negatives), (1, 0));
+    }
+
+    #[test]
+    fn test_record_match_limit_trip() {
+        let mut stats = DetectorStats::default();
+        stats.record_match_limit_trip("Bank account number");
+        stats.record_match_limit_trip("Bank account number");
+
+        assert_eq!(stats.match_limit_trips["Bank account number"], 2);
+    }
+
+    #[test]
+    fn test_record_scan_and_masking() {
+        let mut stats = DetectorStats::default();
+        let mut detections = BTreeMap::new();
+        detections.insert(
+            PIIType::Ssn,
+            vec![Detection {
+                value: "123-45-6789".to_string(),
+                start: 0,
+                end: 11,
+                mask_strategy: MaskingStrategy::Partial,
+                country: None,
+                subtype: None,
+            }],
+        );
+
+        stats.record_scan(11, &detections, Duration::from_micros(10));
+        stats.record_scan(5, &BTreeMap::new(), Duration::from_micros(30));
+        stats.record_masking(&detections);
+
+        assert_eq!(stats.total_scans, 2);
+        assert_eq!(stats.bytes_processed, 16);
+        assert_eq!(stats.detections_by_type[&PIIType::Ssn], 1);
+        assert_eq!(stats.masks_by_strategy[&MaskingStrategy::Partial], 1);
+        assert_eq!(stats.latency_percentile(0.0), 10.0);
+        assert_eq!(stats.latency_percentile(1.0), 30.0);
+
+        let mut merged = DetectorStats::default();
+        merged.record_scan(1, &detections, Duration::from_micros(50));
+        merged.merge(stats);
+        assert_eq!(merged.total_scans, 3);
+        assert_eq!(merged.bytes_processed, 17);
+        assert_eq!(merged.detections_by_type[&PIIType::Ssn], 2);
+        assert_eq!(merged.masks_by_strategy[&MaskingStrategy::Partial], 1);
+        assert_eq!(merged.latencies_us.len(), 3);
+    }
+
+    #[test]
+    fn test_latency_window_is_bounded() {
+        let mut stats = DetectorStats::default();
+        for _ in 0..(LATENCY_WINDOW + 10) {
+            stats.record_scan(0, &BTreeMap::new(), Duration::from_micros(1));
+        }
+
+        assert_eq!(stats.latencies_us.len(), LATENCY_WINDOW);
+        assert_eq!(stats.total_scans, (LATENCY_WINDOW + 10) as u64);
+    }
+}
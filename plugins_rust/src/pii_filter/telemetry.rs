@@ -0,0 +1,229 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// OpenTelemetry export for PII Filter scans
+//
+// With the `otel` feature enabled every scan emits a `pii_filter.scan` span
+// and updates OTLP counters/histograms directly from Rust. The exporter is
+// configured from the standard environment variables:
+//
+// - `OTEL_EXPORTER_OTLP_ENDPOINT` (or the `_TRACES_`/`_METRICS_` variants)
+// - `OTEL_EXPORTER_OTLP_HEADERS`, `OTEL_EXPORTER_OTLP_TIMEOUT`
+// - `OTEL_SERVICE_NAME` and `OTEL_RESOURCE_ATTRIBUTES`, read by the SDK's
+//   resource detectors; `plugins_rust` names the service only when neither
+//   sets `service.name`
+//
+// Without the feature `record_scan` compiles to a no-op.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::config::PIIType;
+use super::detector::Detection;
+
+/// Plugin name attached to every exported span and data point
+#[cfg_attr(not(feature = "otel"), allow(dead_code))]
+const PLUGIN_NAME: &str = "pii_filter";
+
+/// Export telemetry for a completed scan
+#[cfg(feature = "otel")]
+pub fn record_scan(
+    bytes: usize,
+    detections: &BTreeMap<PIIType, Vec<Detection>>,
+    elapsed: Duration,
+) {
+    otel::record_scan(bytes, detections, elapsed);
+}
+
+/// Export telemetry for a completed scan (no-op without the `otel` feature)
+#[cfg(not(feature = "otel"))]
+#[inline(always)]
+pub fn record_scan(
+    _bytes: usize,
+    _detections: &BTreeMap<PIIType, Vec<Detection>>,
+    _elapsed: Duration,
+) {
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use once_cell::sync::Lazy;
+    use opentelemetry::metrics::{Counter, Histogram, MeterProvider};
+    use opentelemetry::trace::{Span, Tracer, TracerProvider};
+    use opentelemetry::{Key, KeyValue};
+    use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+    use opentelemetry_sdk::Resource;
+    use std::collections::BTreeMap;
+    use std::time::{Duration, SystemTime};
+
+    use super::PLUGIN_NAME;
+    use crate::pii_filter::config::PIIType;
+    use crate::pii_filter::detector::Detection;
+
+    /// `service.name` when the environment names no service
+    const FALLBACK_SERVICE_NAME: &str = "plugins_rust";
+
+    /// What the SDK detectors report when no service name is configured
+    const UNKNOWN_SERVICE: &str = "unknown_service";
+
+    /// `detected`, named `FALLBACK_SERVICE_NAME` unless the environment
+    /// already named the service
+    fn with_fallback_name(detected: Resource) -> Resource {
+        let named = detected
+            .get(&Key::new("service.name"))
+            .is_some_and(|name| name.as_str() != UNKNOWN_SERVICE);
+        if named {
+            return detected;
+        }
+        Resource::builder_empty()
+            .with_attributes(
+                detected
+                    .iter()
+                    .map(|(k, v)| KeyValue::new(k.clone(), v.clone())),
+            )
+            .with_service_name(FALLBACK_SERVICE_NAME)
+            .build()
+    }
+
+    /// Exporters and instruments, created on the first scan
+    struct Telemetry {
+        tracer: Option<SdkTracer>,
+        scans: Option<Counter<u64>>,
+        bytes: Option<Counter<u64>>,
+        detections: Option<Counter<u64>>,
+        latency: Option<Histogram<f64>>,
+    }
+
+    /// Telemetry must never fail a scan: if an exporter cannot be built the
+    /// corresponding signal is simply disabled
+    static TELEMETRY: Lazy<Telemetry> = Lazy::new(|| {
+        let resource = with_fallback_name(Resource::builder().build());
+
+        let tracer = SpanExporter::builder()
+            .with_http()
+            .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+            .build()
+            .ok()
+            .map(|exporter| {
+                SdkTracerProvider::builder()
+                    .with_batch_exporter(exporter)
+                    .with_resource(resource.clone())
+                    .build()
+                    .tracer(PLUGIN_NAME)
+            });
+
+        let meter = MetricExporter::builder()
+            .with_http()
+            .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+            .build()
+            .ok()
+            .map(|exporter| {
+                SdkMeterProvider::builder()
+                    .with_periodic_exporter(exporter)
+                    .with_resource(resource)
+                    .build()
+                    .meter(PLUGIN_NAME)
+            });
+
+        Telemetry {
+            tracer,
+            scans: meter.as_ref().map(|m| {
+                m.u64_counter("pii_filter.scans")
+                    .with_description("Number of scans")
+                    .build()
+            }),
+            bytes: meter.as_ref().map(|m| {
+                m.u64_counter("pii_filter.bytes_processed")
+                    .with_unit("By")
+                    .build()
+            }),
+            detections: meter.as_ref().map(|m| {
+                m.u64_counter("pii_filter.detections")
+                    .with_description("Detections by PII type")
+                    .build()
+            }),
+            latency: meter.as_ref().map(|m| {
+                m.f64_histogram("pii_filter.scan.duration")
+                    .with_unit("us")
+                    .build()
+            }),
+        }
+    });
+
+    pub fn record_scan(
+        bytes: usize,
+        detections: &BTreeMap<PIIType, Vec<Detection>>,
+        elapsed: Duration,
+    ) {
+        let telemetry = &*TELEMETRY;
+        let plugin = KeyValue::new("plugin", PLUGIN_NAME);
+        let total: usize = detections.values().map(Vec::len).sum();
+
+        if let Some(counter) = &telemetry.scans {
+            counter.add(1, std::slice::from_ref(&plugin));
+        }
+        if let Some(counter) = &telemetry.bytes {
+            counter.add(bytes as u64, std::slice::from_ref(&plugin));
+        }
+        if let Some(counter) = &telemetry.detections {
+            for (pii_type, items) in detections {
+                counter.add(
+                    items.len() as u64,
+                    &[plugin.clone(), KeyValue::new("pii_type", pii_type.as_str())],
+                );
+            }
+        }
+        if let Some(histogram) = &telemetry.latency {
+            histogram.record(elapsed.as_secs_f64() * 1e6, std::slice::from_ref(&plugin));
+        }
+
+        if let Some(tracer) = &telemetry.tracer {
+            let end = SystemTime::now();
+            let mut span = tracer
+                .span_builder("pii_filter.scan")
+                .with_start_time(end - elapsed)
+                .with_attributes([
+                    plugin,
+                    KeyValue::new("pii_filter.bytes", bytes as i64),
+                    KeyValue::new("pii_filter.detections", total as i64),
+                ])
+                .start(tracer);
+            span.end_with_timestamp(end);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_service_name_fallback() {
+            let service_name = |resource: Resource| {
+                resource
+                    .get(&Key::new("service.name"))
+                    .map(|name| name.as_str().into_owned())
+            };
+
+            let unnamed = Resource::builder_empty()
+                .with_service_name(UNKNOWN_SERVICE)
+                .with_attribute(KeyValue::new("deployment.environment", "prod"))
+                .build();
+            let resource = with_fallback_name(unnamed);
+            assert_eq!(
+                service_name(resource.clone()).unwrap(),
+                FALLBACK_SERVICE_NAME
+            );
+            assert!(resource.get(&Key::new("deployment.environment")).is_some());
+
+            let named = Resource::builder_empty()
+                .with_service_name("mcp-gateway")
+                .build();
+            assert_eq!(
+                service_name(with_fallback_name(named)).unwrap(),
+                "mcp-gateway"
+            );
+        }
+    }
+}
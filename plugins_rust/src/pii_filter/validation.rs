@@ -0,0 +1,651 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Post-match validation for PII Filter
+// Rejects regex matches that are syntactically valid but semantically unlikely
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::config::{AwsSecretMode, DobMode};
+
+/// Oldest plausible age for a date of birth, in years
+const MAX_AGE_YEARS: i32 = 120;
+
+/// Bytes of text before a date searched for a DOB context keyword
+const DOB_CONTEXT_WINDOW: usize = 32;
+
+static DOB_CONTEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:dob|d\.o\.b\.?|date of birth|birth ?date|birthday|born)\b").unwrap()
+});
+
+/// Bytes of text before a postal code searched for address context
+const ADDRESS_CONTEXT_WINDOW: usize = 32;
+
+static ADDRESS_CONTEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:zip|zip ?code|postal|post ?code|plz|address|addr|street|st|avenue|ave|road|rd|boulevard|blvd|lane|ln|drive|dr|city|suite|apt)\b",
+    )
+    .unwrap()
+});
+
+/// Bytes of text before a license plate searched for vehicle context
+const VEHICLE_CONTEXT_WINDOW: usize = 32;
+
+static VEHICLE_CONTEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:plates?|licen[cs]e|vehicle|car|registration|reg|tag|vrn)\b").unwrap()
+});
+
+/// Bytes of text before an AWS secret searched for its key name
+const AWS_SECRET_CONTEXT_WINDOW: usize = 48;
+
+/// Minimum entropy (bits/char) for a context-free AWS secret in lenient mode;
+/// hex strings such as git SHAs top out at 4.0
+const AWS_SECRET_MIN_ENTROPY: f64 = 4.5;
+
+static AWS_SECRET_CONTEXT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:aws_?secret(?:_access)?_?key|secret_?access_?key)").unwrap());
+
+/// "Springfield, IL " immediately before a US ZIP code
+static US_CITY_STATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[A-Za-z]+,\s*[A-Z]{2}\s*$").unwrap());
+
+static GERMAN_CONTEXT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(?:germany|deutschland|plz)\b").unwrap());
+
+static DATE_PARTS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\d{1,2})[-/](\d{1,2})[-/](\d{2,4})").unwrap());
+
+/// Calendar date as (year, month, day)
+pub type Ymd = (i32, u32, u32);
+
+/// Whether the date at `text[start..end]` should be classified as a date of birth
+///
+/// Strict mode requires a DOB context keyword in or just before the match and
+/// a plausible birth date. Lenient mode accepts either.
+pub fn is_probable_dob(text: &str, start: usize, end: usize, mode: DobMode) -> bool {
+    is_probable_dob_on(text, start, end, mode, today())
+}
+
+fn is_probable_dob_on(text: &str, start: usize, end: usize, mode: DobMode, today: Ymd) -> bool {
+    let context_start = start - context_before(text, start, DOB_CONTEXT_WINDOW).len();
+    let has_context = DOB_CONTEXT.is_match(&text[context_start..end]);
+    let plausible = parse_us_date(&text[start..end], today.0)
+        .is_some_and(|date| is_plausible_birth_date(date, today));
+
+    match mode {
+        DobMode::Strict => has_context && plausible,
+        DobMode::Lenient => has_context || plausible,
+    }
+}
+
+/// Whether an address keyword or a "City, ST" prefix precedes `text[start..end]`
+pub fn has_address_context(text: &str, start: usize, _end: usize) -> bool {
+    let before = context_before(text, start, ADDRESS_CONTEXT_WINDOW);
+    ADDRESS_CONTEXT.is_match(before) || US_CITY_STATE.is_match(before)
+}
+
+/// Whether a vehicle keyword ("plate", "vehicle", ...) precedes `text[start..end]`
+pub fn has_vehicle_context(text: &str, start: usize, _end: usize) -> bool {
+    VEHICLE_CONTEXT.is_match(context_before(text, start, VEHICLE_CONTEXT_WINDOW))
+}
+
+/// ISO 3166 country code of the postal code at `text[start..end]`
+///
+/// Derived from the code's shape; plain five-digit codes are US ZIP codes
+/// unless German context ("Germany", "PLZ", ...) surrounds them.
+pub fn postal_country(text: &str, start: usize, end: usize) -> &'static str {
+    let value = &text[start..end];
+    if value.starts_with("D-") || value.starts_with("d-") {
+        return "DE";
+    }
+    if value.chars().any(|c| c.is_ascii_alphabetic()) {
+        // Canadian codes alternate letter/digit: A1A 1A1
+        let compact: Vec<char> = value.chars().filter(|c| !c.is_whitespace()).collect();
+        let canadian = compact.len() == 6
+            && compact
+                .iter()
+                .enumerate()
+                .all(|(i, c)| (i % 2 == 0) == c.is_ascii_alphabetic());
+        return if canadian { "CA" } else { "GB" };
+    }
+
+    let (before_start, after_end) = context_bounds(text, start, end, ADDRESS_CONTEXT_WINDOW);
+    if GERMAN_CONTEXT.is_match(&text[before_start..after_end]) {
+        "DE"
+    } else {
+        "US"
+    }
+}
+
+/// Bounds of `text[start..end]` widened by up to `window` bytes on each
+/// side, on character boundaries
+pub fn context_bounds(text: &str, start: usize, end: usize, window: usize) -> (usize, usize) {
+    let mut after_end = (end + window).min(text.len());
+    while !text.is_char_boundary(after_end) {
+        after_end -= 1;
+    }
+    (start - context_before(text, start, window).len(), after_end)
+}
+
+/// Up to `window` bytes of `text` before `start`, on a character boundary
+fn context_before(text: &str, start: usize, window: usize) -> &str {
+    let mut context_start = start.saturating_sub(window);
+    while !text.is_char_boundary(context_start) {
+        context_start -= 1;
+    }
+    &text[context_start..start]
+}
+
+/// Parse the first `MM/DD/YYYY` (or `MM-DD-YY`) date in `value`
+///
+/// Two-digit years resolve to the most recent matching year not after
+/// `current_year`.
+pub fn parse_us_date(value: &str, current_year: i32) -> Option<Ymd> {
+    let caps = DATE_PARTS.captures(value)?;
+    let month: u32 = caps[1].parse().ok()?;
+    let day: u32 = caps[2].parse().ok()?;
+    let mut year: i32 = caps[3].parse().ok()?;
+
+    if caps[3].len() == 2 {
+        let century = current_year - current_year.rem_euclid(100);
+        year += century;
+        if year > current_year {
+            year -= 100;
+        }
+    } else if caps[3].len() != 4 {
+        return None;
+    }
+
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+/// A real calendar date that is not in the future and not older than
+/// `MAX_AGE_YEARS`
+fn is_plausible_birth_date(date: Ymd, today: Ymd) -> bool {
+    date <= today && date.0 >= today.0 - MAX_AGE_YEARS
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Whether the domain of `email` is one of `domains` or a subdomain of one
+///
+/// Comparison is ASCII case-insensitive; list entries may carry a leading `@`.
+pub fn email_domain_listed(email: &str, domains: &[String]) -> bool {
+    let Some((_, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+
+    domains.iter().any(|listed| {
+        let listed = listed.trim_start_matches('@');
+        if domain.eq_ignore_ascii_case(listed) {
+            return true;
+        }
+        domain.len() > listed.len()
+            && domain.as_bytes()[domain.len() - listed.len() - 1] == b'.'
+            && domain[domain.len() - listed.len()..].eq_ignore_ascii_case(listed)
+    })
+}
+
+/// Whether an Authorization credential is genuine
+///
+/// `Basic` values must decode as base64 to `user:password`; `Bearer` tokens
+/// are opaque and always accepted.
+pub fn is_valid_auth_credential(value: &str) -> bool {
+    let Some(encoded) = value
+        .get(..6)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("basic "))
+        .map(|_| value[6..].trim_start())
+    else {
+        return true;
+    };
+
+    STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .is_some_and(|decoded| decoded.contains(':'))
+}
+
+/// Whether the 40-character token at `text[start..end]` is an AWS secret key
+pub fn is_probable_aws_secret(text: &str, start: usize, end: usize, mode: AwsSecretMode) -> bool {
+    if AWS_SECRET_CONTEXT.is_match(context_before(text, start, AWS_SECRET_CONTEXT_WINDOW)) {
+        return true;
+    }
+    mode == AwsSecretMode::Lenient && shannon_entropy(&text[start..end]) >= AWS_SECRET_MIN_ENTROPY
+}
+
+/// Shannon entropy of `value` in bits per character
+pub fn shannon_entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut total = 0usize;
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return 0.0;
+    }
+
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Whether the digits of `value` pass the Luhn check; separators are ignored
+pub fn luhn_valid(value: &str) -> bool {
+    let digits: Vec<u32> = value.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    digits.len() >= 2 && sum.is_multiple_of(10)
+}
+
+/// Card number lengths, most common first, tried on unseparated digit runs
+/// that are not a card number as a whole
+const CARD_LENGTHS: [usize; 7] = [16, 15, 14, 19, 18, 17, 13];
+
+/// Fewest digits in a group of a separated card number
+const MIN_CARD_GROUP: usize = 3;
+
+/// A payment network: IIN prefix range (inclusive, compared on as many
+/// leading digits) and the lengths of its card numbers
+type CardNetwork = (&'static str, &'static str, &'static str, &'static [usize]);
+
+const CARD_NETWORKS: &[CardNetwork] = &[
+    ("amex", "34", "34", &[15]),
+    ("amex", "37", "37", &[15]),
+    ("diners", "300", "305", &[14]),
+    ("diners", "36", "36", &[14]),
+    ("diners", "38", "39", &[14]),
+    ("jcb", "3528", "3589", &[16, 17, 18, 19]),
+    ("visa", "4", "4", &[13, 16, 19]),
+    ("mastercard", "51", "55", &[16]),
+    ("mastercard", "2221", "2720", &[16]),
+    ("discover", "6011", "6011", &[16, 19]),
+    ("discover", "644", "649", &[16, 19]),
+    ("discover", "65", "65", &[16, 19]),
+    ("unionpay", "62", "62", &[16, 17, 18, 19]),
+];
+
+/// Leading digits (the industry identifier) of payment cards; numbers of
+/// networks not listed above, such as Maestro, Mir or RuPay, are cards when
+/// they start with one of these and have 13 to 19 digits
+const CARD_INDUSTRIES: &str = "234568";
+
+/// Network of a card number by its prefix and length; separators are ignored
+pub fn card_network(value: &str) -> Option<&'static str> {
+    let digits: String = value.chars().filter(char::is_ascii_digit).collect();
+    network_of_digits(&digits)
+}
+
+/// `CARD_NETWORKS` entries whose prefix `digits` start with
+fn networks_by_prefix(digits: &str) -> impl Iterator<Item = &'static CardNetwork> + '_ {
+    CARD_NETWORKS.iter().filter(|(_, low, high, _)| {
+        digits
+            .get(..low.len())
+            .is_some_and(|prefix| (*low..=*high).contains(&prefix))
+    })
+}
+
+fn network_of_digits(digits: &str) -> Option<&'static str> {
+    networks_by_prefix(digits)
+        .find(|(.., lengths)| lengths.contains(&digits.len()))
+        .map(|(network, ..)| *network)
+}
+
+/// Whether `digits` (ASCII digits only) are a card number: a listed
+/// network's prefix and length, or another payment card's industry
+/// identifier and length, and a valid Luhn check digit
+fn is_card_number(digits: &str) -> bool {
+    let plausible = if networks_by_prefix(digits).next().is_some() {
+        network_of_digits(digits).is_some()
+    } else {
+        (13..=19).contains(&digits.len()) && digits.starts_with(|c| CARD_INDUSTRIES.contains(c))
+    };
+    plausible && luhn_valid(digits)
+}
+
+/// Card numbers in the run of digits and single separators at `start..end`
+///
+/// The whole run is taken when it is a card number. Otherwise cards are
+/// searched from the left inside it, longest first: whole groups of at
+/// least three digits when the run is separated (`12 4111 1111 1111 1111`),
+/// digit windows when it isn't (`004111111111111111`).
+pub fn card_spans(text: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let digits_in = |from: usize, to: usize| -> String {
+        text[from..to]
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect()
+    };
+    let digits = digits_in(start, end);
+    if is_card_number(&digits) {
+        return vec![(start, end)];
+    }
+
+    let mut groups = Vec::new();
+    let mut group_start = None;
+    for (idx, c) in text[start..end].char_indices() {
+        match (c.is_ascii_digit(), group_start) {
+            (true, None) => group_start = Some(start + idx),
+            (false, Some(from)) => {
+                groups.push((from, start + idx));
+                group_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = group_start {
+        groups.push((from, end));
+    }
+
+    let mut spans = Vec::new();
+    if groups.len() == 1 {
+        let mut idx = 0;
+        while idx + 13 <= digits.len() {
+            let found = CARD_LENGTHS
+                .iter()
+                .find(|&&len| idx + len <= digits.len() && is_card_number(&digits[idx..idx + len]));
+            match found {
+                Some(&len) => {
+                    spans.push((start + idx, start + idx + len));
+                    idx += len;
+                }
+                None => idx += 1,
+            }
+        }
+        return spans;
+    }
+
+    let mut first = 0;
+    while first < groups.len() {
+        let (from, _) = groups[first];
+        let mut len = 0;
+        let mut longest = None;
+        for (last, &(group_from, group_to)) in groups.iter().enumerate().skip(first) {
+            len += group_to - group_from;
+            if group_to - group_from < MIN_CARD_GROUP || len > 19 {
+                break;
+            }
+            if is_card_number(&digits_in(from, group_to)) {
+                longest = Some(last);
+            }
+        }
+        match longest {
+            Some(last) => {
+                spans.push((from, groups[last].1));
+                first = last + 1;
+            }
+            None => first += 1,
+        }
+    }
+    spans
+}
+
+/// Whether a dashed SSN (`AAA-GG-SSSS`) was ever assignable: no all-zero
+/// part, and an area number other than 666 or 900-999
+pub fn is_valid_ssn(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [area, group, serial] = parts[..] else {
+        return false;
+    };
+    let number = |part: &str, len: usize| {
+        (part.len() == len && part.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| part.parse::<u32>().ok())
+            .flatten()
+    };
+    matches!(
+        (number(area, 3), number(group, 2), number(serial, 4)),
+        (Some(area), Some(group), Some(serial))
+            if area != 0 && area != 666 && area < 900 && group != 0 && serial != 0
+    )
+}
+
+/// Whether `value` is an IBAN with correct ISO 7064 mod-97 check digits
+pub fn iban_valid(value: &str) -> bool {
+    value.len() > 4
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        && iban_remainder(&value[4..], &value[..4]) == 1
+}
+
+/// Mod-97 remainder of `bban` followed by `suffix`, letters counting as 10-35
+pub fn iban_remainder(bban: &str, suffix: &str) -> u32 {
+    bban.chars()
+        .chain(suffix.chars())
+        .filter_map(|c| c.to_digit(36))
+        .fold(0, |acc, d| {
+            if d >= 10 {
+                (acc * 100 + d) % 97
+            } else {
+                (acc * 10 + d) % 97
+            }
+        })
+}
+
+/// Current UTC date
+pub fn today() -> Ymd {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    civil_from_days((secs / 86_400) as i64)
+}
+
+/// Convert days since 1970-01-01 to a proleptic Gregorian date
+fn civil_from_days(days: i64) -> Ymd {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400) as i32 + i32::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODAY: Ymd = (2025, 6, 15);
+
+    fn check(text: &str, date: &str, mode: DobMode) -> bool {
+        let start = text.find(date).unwrap();
+        is_probable_dob_on(text, start, start + date.len(), mode, TODAY)
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn test_lenient_mode() {
+        assert!(check("Born 01/15/1985", "01/15/1985", DobMode::Lenient));
+        assert!(check(
+            "Appointment on 12/31/2020",
+            "12/31/2020",
+            DobMode::Lenient
+        ));
+        // Future and implausibly old dates need a keyword
+        assert!(!check(
+            "Ships on 12/31/2030",
+            "12/31/2030",
+            DobMode::Lenient
+        ));
+        assert!(!check("Founded 01/01/1850", "01/01/1850", DobMode::Lenient));
+        assert!(check("DOB: 12/31/2030", "12/31/2030", DobMode::Lenient));
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        assert!(check(
+            "Date of birth 01/15/1985",
+            "01/15/1985",
+            DobMode::Strict
+        ));
+        assert!(!check(
+            "Invoice date 01/15/2024",
+            "01/15/2024",
+            DobMode::Strict
+        ));
+        assert!(!check("DOB: 02/30/1985", "02/30/1985", DobMode::Strict));
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert!(luhn_valid("4111 1111 1111 1111"));
+        assert!(!luhn_valid("4111 1111 1111 1112"));
+        assert!(is_card_number("4111111111111111"));
+        assert!(!is_card_number("1111111111111117"));
+        assert!(!is_card_number("3782822463100054"));
+        for unlisted in ["6759649826438453", "2200123456789019", "5085123456789012"] {
+            assert!(is_card_number(unlisted));
+            assert_eq!(card_network(unlisted), None);
+        }
+        assert!(!is_card_number("675964982648"));
+        assert_eq!(card_network("3782 822463 10005"), Some("amex"));
+        assert_eq!(card_network("3056 930902 5904"), Some("diners"));
+        assert_eq!(card_network("5555-5555-5555-4444"), Some("mastercard"));
+        assert_eq!(card_network("378282246310005"), Some("amex"));
+        assert_eq!(card_network("3782822463100054"), None);
+    }
+
+    #[test]
+    fn test_card_spans() {
+        fn spans(text: &str) -> Vec<&str> {
+            card_spans(text, 0, text.len())
+                .into_iter()
+                .map(|(start, end)| &text[start..end])
+                .collect()
+        }
+        assert_eq!(spans("4111.1111.1111.1111"), vec!["4111.1111.1111.1111"]);
+        assert_eq!(spans("4111 1111 1111 1112"), Vec::<&str>::new());
+        assert_eq!(spans("00004111111111111111"), vec!["4111111111111111"]);
+        assert_eq!(spans("12 4111 1111 1111 1111"), vec!["4111 1111 1111 1111"]);
+        assert_eq!(
+            spans("4111 1111 1111 1111 5555 5555 5555 4444"),
+            vec!["4111 1111 1111 1111", "5555 5555 5555 4444"]
+        );
+        assert_eq!(spans("1700000000000"), Vec::<&str>::new());
+        assert_eq!(spans("3782 822463 10005"), vec!["3782 822463 10005"]);
+        assert_eq!(spans("00030569309025904"), vec!["30569309025904"]);
+        assert!(is_valid_ssn("123-45-6789"));
+        assert!(!is_valid_ssn("666-45-6789"));
+        assert!(!is_valid_ssn("123-00-6789"));
+        assert!(iban_valid("DE89370400440532013000"));
+        assert!(iban_valid("NL91ABNA0417164300"));
+        assert!(!iban_valid("NL92ABNA0417164300"));
+    }
+
+    #[test]
+    fn test_two_digit_year() {
+        assert_eq!(parse_us_date("01/15/85", 2025), Some((1985, 1, 15)));
+        assert_eq!(parse_us_date("01/15/24", 2025), Some((2024, 1, 15)));
+    }
+
+    #[test]
+    fn test_email_domain_listed() {
+        let domains = vec!["corp.com".to_string(), "@Example.org".to_string()];
+        assert!(email_domain_listed("jane@corp.com", &domains));
+        assert!(email_domain_listed("jane@mail.CORP.com", &domains));
+        assert!(email_domain_listed("jane@example.org", &domains));
+        assert!(!email_domain_listed("jane@notcorp.com", &domains));
+        assert!(!email_domain_listed("not-an-email", &domains));
+    }
+
+    #[test]
+    fn test_address_context() {
+        let text = "Ship to 12 Main St, Springfield, IL 62704";
+        let start = text.find("62704").unwrap();
+        assert!(has_address_context(text, start, start + 5));
+
+        let text = "Order 62704 confirmed";
+        let start = text.find("62704").unwrap();
+        assert!(!has_address_context(text, start, start + 5));
+    }
+
+    #[test]
+    fn test_postal_country() {
+        let country = |text: &str, code: &str| {
+            let start = text.find(code).unwrap();
+            postal_country(text, start, start + code.len())
+        };
+        assert_eq!(country("Zip 62704-1234", "62704-1234"), "US");
+        assert_eq!(country("Postcode SW1A 1AA", "SW1A 1AA"), "GB");
+        assert_eq!(country("Postal code K1A 0B1", "K1A 0B1"), "CA");
+        assert_eq!(country("PLZ 10115 Berlin", "10115"), "DE");
+        assert_eq!(country("Address D-10115", "D-10115"), "DE");
+    }
+
+    #[test]
+    fn test_auth_credential() {
+        // "alice:s3cret"
+        assert!(is_valid_auth_credential("Basic YWxpY2U6czNjcmV0"));
+        // "no colon here"
+        assert!(!is_valid_auth_credential("Basic bm8gY29sb24gaGVyZQ=="));
+        assert!(!is_valid_auth_credential("Basic notbase64!!"));
+        assert!(is_valid_auth_credential("Bearer abcdef0123456789abcdef"));
+    }
+
+    #[test]
+    fn test_shannon_entropy() {
+        assert_eq!(shannon_entropy(""), 0.0);
+        assert_eq!(shannon_entropy("aaaa"), 0.0);
+        assert_eq!(shannon_entropy("abcd"), 2.0);
+        assert!(shannon_entropy("wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY") > 4.0);
+    }
+
+    #[test]
+    fn test_aws_secret_context() {
+        let secret = "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY";
+        let sha = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        let check = |text: &str, token: &str, mode| {
+            let start = text.find(token).unwrap();
+            is_probable_aws_secret(text, start, start + token.len(), mode)
+        };
+
+        let text = format!("aws_secret_access_key = {}", secret);
+        assert!(check(&text, secret, AwsSecretMode::Strict));
+        let text = format!("SecretAccessKey={}", secret);
+        assert!(check(&text, secret, AwsSecretMode::Strict));
+
+        let text = format!("blob {}", secret);
+        assert!(!check(&text, secret, AwsSecretMode::Strict));
+        assert!(check(&text, secret, AwsSecretMode::Lenient));
+
+        let text = format!("commit {}", sha);
+        assert!(!check(&text, sha, AwsSecretMode::Lenient));
+    }
+}
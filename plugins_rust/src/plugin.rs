@@ -0,0 +1,496 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Common interface for Rust gateway plugins
+//
+// Every Rust plugin implements `GatewayPlugin` and is listed in the registry
+// in `lib.rs`, so the Python side can discover plugins and the hooks they
+// support uniformly via `plugins_rust.list_plugins()`.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::pii_filter::error::exceptions::BlockedContentError;
+use crate::pii_filter::PIIError;
+
+/// Gateway hook points a plugin can handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hook {
+    OnRequest,
+    OnResponse,
+    OnToolResult,
+}
+
+impl Hook {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Hook::OnRequest => "on_request",
+            Hook::OnResponse => "on_response",
+            Hook::OnToolResult => "on_tool_result",
+        }
+    }
+
+    /// Payload direction name used by `HookContext`
+    pub fn direction(&self) -> &'static str {
+        match self {
+            Hook::OnRequest => "request",
+            Hook::OnResponse => "response",
+            Hook::OnToolResult => "tool_result",
+        }
+    }
+
+    pub fn from_direction(direction: &str) -> Option<Self> {
+        match direction {
+            "request" => Some(Hook::OnRequest),
+            "response" => Some(Hook::OnResponse),
+            "tool_result" => Some(Hook::OnToolResult),
+            _ => None,
+        }
+    }
+}
+
+/// Payload and call metadata passed to every Rust plugin
+///
+/// The plugin manager builds one context per hook invocation and hands it to
+/// each plugin's `process(context)` in turn. Plugins may replace `payload`
+/// and record findings for plugins that run after them.
+///
+/// ```python
+/// from plugins_rust import HookContext
+///
+/// ctx = HookContext({"args": {...}}, direction="tool_result", tool_name="crm.lookup")
+/// detector.process(ctx)
+/// ctx.payload   # masked payload
+/// ctx.findings  # {"pii_filter": [{"detections": {"ssn": 1}}]}
+/// ```
+#[pyclass(name = "HookContext", module = "plugins_rust")]
+pub struct HookContext {
+    pub hook: Hook,
+    #[pyo3(get, set)]
+    pub tenant: Option<String>,
+    #[pyo3(get, set)]
+    pub tool_name: Option<String>,
+    #[pyo3(get, set)]
+    pub payload: Py<PyAny>,
+    #[pyo3(get)]
+    pub metadata: Py<PyDict>,
+    /// Findings recorded by plugins, keyed by plugin name
+    #[pyo3(get)]
+    pub findings: Py<PyDict>,
+}
+
+#[pymethods]
+impl HookContext {
+    /// Create a context for one hook invocation
+    ///
+    /// # Arguments
+    /// * `payload` - Request, response or tool result data
+    /// * `direction` - "request", "response" or "tool_result"
+    /// * `tenant` - Optional tenant identifier
+    /// * `tool_name` - Optional name of the tool being invoked
+    /// * `metadata` - Optional free-form metadata dict
+    #[new]
+    #[pyo3(signature = (payload, direction="request", tenant=None, tool_name=None, metadata=None))]
+    pub fn new(
+        py: Python,
+        payload: Py<PyAny>,
+        direction: &str,
+        tenant: Option<String>,
+        tool_name: Option<String>,
+        metadata: Option<Py<PyDict>>,
+    ) -> PyResult<Self> {
+        let hook = Hook::from_direction(direction).ok_or_else(|| PIIError::InvalidValue {
+            field: "direction",
+            value: direction.to_string(),
+        })?;
+        Ok(Self {
+            hook,
+            tenant,
+            tool_name,
+            payload,
+            metadata: metadata.unwrap_or_else(|| PyDict::new(py).unbind()),
+            findings: PyDict::new(py).unbind(),
+        })
+    }
+
+    #[getter]
+    pub fn direction(&self) -> &'static str {
+        self.hook.direction()
+    }
+
+    /// Record a finding from `plugin` for downstream plugins and the caller
+    pub fn add_finding(&self, py: Python, plugin: &str, finding: Py<PyAny>) -> PyResult<()> {
+        let findings = self.findings.bind(py);
+        match findings.get_item(plugin)? {
+            Some(existing) => existing.cast::<PyList>()?.append(finding),
+            None => findings.set_item(plugin, PyList::new(py, [finding])?),
+        }
+    }
+}
+
+/// A Rust plugin that can run at gateway hook points
+///
+/// Each hook receives the shared `HookContext`; it may replace the payload
+/// and record findings, and raising blocks the request. Hooks a plugin does
+/// not list in `hooks()` are never called by `process`.
+pub trait GatewayPlugin: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn version(&self) -> &'static str;
+
+    /// Hooks this plugin implements
+    fn hooks(&self) -> &'static [Hook];
+
+    fn on_request(&self, _py: Python, _context: &Bound<'_, HookContext>) -> PyResult<()> {
+        Ok(())
+    }
+
+    fn on_response(&self, _py: Python, _context: &Bound<'_, HookContext>) -> PyResult<()> {
+        Ok(())
+    }
+
+    fn on_tool_result(&self, _py: Python, _context: &Bound<'_, HookContext>) -> PyResult<()> {
+        Ok(())
+    }
+
+    /// Run the hook matching the context's direction, if this plugin has it
+    fn process(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        let hook = context.borrow().hook;
+        if !self.hooks().contains(&hook) {
+            return Ok(());
+        }
+        match hook {
+            Hook::OnRequest => self.on_request(py, context),
+            Hook::OnResponse => self.on_response(py, context),
+            Hook::OnToolResult => self.on_tool_result(py, context),
+        }
+    }
+}
+
+/// Ordered chain of Rust plugins run over a payload in a single call
+///
+/// ```python
+/// from plugins_rust import PluginPipeline
+///
+/// pipeline = PluginPipeline([{"name": "pii_filter", "config": {"block_on_detection": False}}])
+/// result = pipeline.run(payload, direction="tool_result", tool_name="crm.lookup")
+/// result["payload"], result["findings"], result["blocked"]
+/// ```
+#[pyclass(name = "PluginPipeline", module = "plugins_rust", frozen)]
+pub struct PluginPipeline {
+    plugins: Vec<Box<dyn GatewayPlugin>>,
+}
+
+#[pymethods]
+impl PluginPipeline {
+    /// Build a pipeline from plugin specs, run in list order
+    ///
+    /// # Arguments
+    /// * `plugins` - List of `{"name": str, "config": dict}`; `config` is
+    ///   optional and names must match `list_plugins()`
+    #[new]
+    pub fn new(py: Python, plugins: &Bound<'_, PyList>) -> PyResult<Self> {
+        let mut built = Vec::with_capacity(plugins.len());
+        for spec in plugins.iter() {
+            let spec = spec.cast::<PyDict>()?;
+            let name: String = spec
+                .get_item("name")?
+                .ok_or(PIIError::MissingField("name"))?
+                .extract()?;
+            let registration = find_plugin(&name).ok_or_else(|| PIIError::InvalidValue {
+                field: "plugin",
+                value: name.clone(),
+            })?;
+            let config = match spec.get_item("config")? {
+                Some(config) => config.cast_into::<PyDict>()?,
+                None => PyDict::new(py),
+            };
+            built.push((registration.build)(&config)?);
+        }
+        Ok(Self { plugins: built })
+    }
+
+    /// Names of the plugins, in execution order
+    #[getter]
+    pub fn names(&self) -> Vec<&'static str> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+
+    /// Run every plugin over `payload`, stopping at the first that blocks
+    ///
+    /// # Arguments
+    /// Same as `HookContext(...)`.
+    ///
+    /// # Returns
+    /// ```python
+    /// {
+    ///     "payload": {...},            # transformed payload
+    ///     "findings": {"pii_filter": [...]},
+    ///     "blocked": False,
+    ///     "blocked_by": None,          # plugin name when blocked
+    ///     "reason": None,              # block message when blocked
+    /// }
+    /// ```
+    /// Errors other than `BlockedContentError` propagate.
+    #[pyo3(signature = (payload, direction="request", tenant=None, tool_name=None, metadata=None))]
+    pub fn run(
+        &self,
+        py: Python,
+        payload: Py<PyAny>,
+        direction: &str,
+        tenant: Option<String>,
+        tool_name: Option<String>,
+        metadata: Option<Py<PyDict>>,
+    ) -> PyResult<Py<PyDict>> {
+        let context = Bound::new(
+            py,
+            HookContext::new(py, payload, direction, tenant, tool_name, metadata)?,
+        )?;
+
+        let mut blocked: Option<(&'static str, PyErr)> = None;
+        for plugin in &self.plugins {
+            match plugin.process(py, &context) {
+                Ok(()) => {}
+                Err(err) if err.is_instance_of::<BlockedContentError>(py) => {
+                    blocked = Some((plugin.name(), err));
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let context = context.borrow();
+        let result = PyDict::new(py);
+        result.set_item("payload", context.payload.bind(py))?;
+        result.set_item("findings", context.findings.bind(py))?;
+        result.set_item("blocked", blocked.is_some())?;
+        match blocked {
+            Some((name, err)) => {
+                result.set_item("blocked_by", name)?;
+                // BlockedContentError carries (message, counts) as its args
+                let value = err.value(py);
+                let reason = match value.getattr("args")?.get_item(0) {
+                    Ok(message) => message.str()?,
+                    Err(_) => value.str()?,
+                };
+                result.set_item("reason", reason)?;
+            }
+            None => {
+                result.set_item("blocked_by", py.None())?;
+                result.set_item("reason", py.None())?;
+            }
+        }
+        Ok(result.unbind())
+    }
+}
+
+/// Registry entry describing a plugin and how to build it from its config
+pub struct PluginRegistration {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub hooks: &'static [Hook],
+    pub build: fn(&Bound<'_, PyDict>) -> PyResult<Box<dyn GatewayPlugin>>,
+}
+
+impl PluginRegistration {
+    /// Describe the plugin as a Python dict
+    pub fn to_py_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let py_dict = PyDict::new(py);
+        py_dict.set_item("name", self.name)?;
+        py_dict.set_item("version", self.version)?;
+        let hooks = PyList::empty(py);
+        for hook in self.hooks {
+            hooks.append(hook.as_str())?;
+        }
+        py_dict.set_item("hooks", hooks)?;
+        Ok(py_dict)
+    }
+}
+
+/// Look up a registered plugin by name
+pub fn find_plugin(name: &str) -> Option<&'static PluginRegistration> {
+    crate::PLUGINS.iter().find(|plugin| plugin.name == name)
+}
+
+/// List available Rust plugins and the hooks they support
+///
+/// # Returns
+/// ```python
+/// [{"name": "pii_filter", "version": "0.9.0", "hooks": ["on_request", ...]}]
+/// ```
+#[pyfunction]
+pub fn list_plugins(py: Python) -> PyResult<Py<PyList>> {
+    let plugins = PyList::empty(py);
+    for plugin in crate::PLUGINS {
+        plugins.append(plugin.to_py_dict(py)?)?;
+    }
+    Ok(plugins.unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_names() {
+        let names: Vec<_> = crate::pii_filter::PIIDetectorRust::HOOKS
+            .iter()
+            .map(Hook::as_str)
+            .collect();
+        assert_eq!(names, vec!["on_request", "on_response", "on_tool_result"]);
+    }
+
+    #[test]
+    fn test_direction_round_trip() {
+        for hook in [Hook::OnRequest, Hook::OnResponse, Hook::OnToolResult] {
+            assert_eq!(Hook::from_direction(hook.direction()), Some(hook));
+        }
+        assert_eq!(Hook::from_direction("inbound"), None);
+    }
+}
+
+/// Tests that build plugins and run them through the interpreter
+///
+/// They need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them. The
+/// registry tests live here too, since `PLUGINS` references every plugin's
+/// constructor.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::*;
+    use pyo3::types::IntoPyDict;
+
+    fn pipeline(py: Python, specs: &[(&str, &[(&str, bool)])]) -> PyResult<PluginPipeline> {
+        let list = PyList::empty(py);
+        for (name, config) in specs {
+            let spec = PyDict::new(py);
+            spec.set_item("name", name)?;
+            spec.set_item("config", config.into_py_dict(py)?)?;
+            list.append(spec)?;
+        }
+        PluginPipeline::new(py, &list)
+    }
+
+    fn payload(py: Python) -> Py<PyAny> {
+        let payload = PyDict::new(py);
+        payload.set_item("note", "SSN 123-45-6789").unwrap();
+        payload.into_any().unbind()
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        for plugin in crate::PLUGINS {
+            assert_eq!(find_plugin(plugin.name).unwrap().name, plugin.name);
+            assert!(!plugin.hooks.is_empty(), "{} has no hooks", plugin.name);
+        }
+        let mut names: Vec<_> = crate::PLUGINS.iter().map(|plugin| plugin.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), crate::PLUGINS.len());
+        assert!(find_plugin("no_such_plugin").is_none());
+    }
+
+    #[test]
+    fn test_list_plugins_matches_registry() {
+        Python::initialize();
+        Python::attach(|py| {
+            let plugins = list_plugins(py).unwrap();
+            let plugins = plugins.bind(py);
+            assert_eq!(plugins.len(), crate::PLUGINS.len());
+            for (listed, registered) in plugins.iter().zip(crate::PLUGINS) {
+                let name: String = listed.get_item("name").unwrap().extract().unwrap();
+                let hooks: Vec<String> = listed.get_item("hooks").unwrap().extract().unwrap();
+                assert_eq!(name, registered.name);
+                assert_eq!(
+                    hooks,
+                    registered
+                        .hooks
+                        .iter()
+                        .map(Hook::as_str)
+                        .collect::<Vec<_>>()
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_pipeline_chains_payload() {
+        Python::initialize();
+        Python::attach(|py| {
+            let pipeline = pipeline(
+                py,
+                &[
+                    ("pii_filter", &[]),
+                    ("pii_filter", &[("block_on_detection", true)]),
+                ],
+            )
+            .unwrap();
+            assert_eq!(pipeline.names(), vec!["pii_filter", "pii_filter"]);
+
+            let result = pipeline
+                .run(py, payload(py), "tool_result", None, None, None)
+                .unwrap();
+            let result = result.bind(py);
+            // The second plugin sees the first one's masked payload
+            assert!(!result
+                .get_item("blocked")
+                .unwrap()
+                .unwrap()
+                .extract::<bool>()
+                .unwrap());
+            let note: String = result
+                .get_item("payload")
+                .unwrap()
+                .unwrap()
+                .get_item("note")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(note, "SSN ***-**-6789");
+            let findings = result.get_item("findings").unwrap().unwrap();
+            assert!(findings.get_item("pii_filter").unwrap().len().unwrap() >= 1);
+        });
+    }
+
+    #[test]
+    fn test_pipeline_stops_at_block() {
+        Python::initialize();
+        Python::attach(|py| {
+            let pipeline = pipeline(
+                py,
+                &[
+                    ("pii_filter", &[("block_on_detection", true)]),
+                    ("pii_filter", &[]),
+                ],
+            )
+            .unwrap();
+            let result = pipeline
+                .run(py, payload(py), "request", None, None, None)
+                .unwrap();
+            let result = result.bind(py);
+            let get = |key: &str| result.get_item(key).unwrap().unwrap();
+            assert!(get("blocked").extract::<bool>().unwrap());
+            assert_eq!(get("blocked_by").extract::<String>().unwrap(), "pii_filter");
+            let reason: String = get("reason").extract().unwrap();
+            assert!(reason.starts_with("Content blocked"), "{reason}");
+            // The masking plugin after the block never ran
+            let note: String = get("payload").get_item("note").unwrap().extract().unwrap();
+            assert_eq!(note, "SSN 123-45-6789");
+            assert!(get("findings").cast::<PyDict>().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_pipeline_rejects_bad_specs() {
+        Python::initialize();
+        Python::attach(|py| {
+            assert!(pipeline(py, &[("no_such_plugin", &[])]).is_err());
+            let missing_name = PyList::new(py, [PyDict::new(py)]).unwrap();
+            assert!(PluginPipeline::new(py, &missing_name).is_err());
+            let pipeline = pipeline(py, &[]).unwrap();
+            assert!(pipeline
+                .run(py, payload(py), "inbound", None, None, None)
+                .is_err());
+        });
+    }
+}
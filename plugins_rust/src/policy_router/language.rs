@@ -0,0 +1,232 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Lightweight language identification
+//
+// Non-Latin scripts are identified from character ranges; Latin-script text
+// is scored against short lists of frequent function words. This is enough
+// to pick a policy per payload and needs no model data.
+
+/// Language code returned when no language can be determined
+pub const UNDETERMINED: &str = "und";
+
+/// Frequent function words for Latin-script languages (ISO 639-1)
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "is", "in", "that", "it", "for", "with", "was", "are",
+            "this", "you", "my", "your", "have", "be", "not", "please",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "mit", "den", "von", "zu", "ein",
+            "eine", "auf", "für", "sie", "es", "mein", "bitte", "ihre",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "une", "des", "du", "pour", "dans", "que", "qui",
+            "pas", "je", "vous", "avec", "sur", "mon", "ce", "votre",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "es", "una", "del", "que", "por", "para", "con", "en",
+            "no", "mi", "se", "su", "al", "lo", "usted",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "la", "gli", "e", "è", "una", "del", "che", "per", "con", "non", "di",
+            "sono", "mio", "un", "della", "questo", "suo",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "e", "é", "um", "uma", "do", "da", "que", "para", "com", "não",
+            "meu", "em", "no", "na", "por", "você",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "van", "dat", "niet", "ik", "met", "voor", "op",
+            "zijn", "mijn", "je", "u", "uw",
+        ],
+    ),
+];
+
+/// Result of language identification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LanguageGuess {
+    /// ISO 639-1 code, or `UNDETERMINED`
+    pub language: &'static str,
+    /// Share of the evidence supporting `language`, in [0, 1]
+    pub confidence: f64,
+}
+
+impl LanguageGuess {
+    const UNKNOWN: Self = Self {
+        language: UNDETERMINED,
+        confidence: 0.0,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+}
+
+fn script(c: char) -> Option<Script> {
+    match c as u32 {
+        0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F => Some(Script::Latin),
+        0x370..=0x3FF => Some(Script::Greek),
+        0x400..=0x4FF => Some(Script::Cyrillic),
+        0x590..=0x5FF => Some(Script::Hebrew),
+        0x600..=0x6FF | 0x750..=0x77F => Some(Script::Arabic),
+        0x900..=0x97F => Some(Script::Devanagari),
+        0xE00..=0xE7F => Some(Script::Thai),
+        0x1100..=0x11FF | 0xAC00..=0xD7AF => Some(Script::Hangul),
+        0x3040..=0x30FF => Some(Script::Kana),
+        0x4E00..=0x9FFF => Some(Script::Han),
+        _ => None,
+    }
+}
+
+/// Identify the dominant language of `text`
+pub fn detect_language(text: &str) -> LanguageGuess {
+    const SCRIPTS: usize = 10;
+    let mut counts = [0usize; SCRIPTS];
+    let mut total = 0;
+    for c in text.chars() {
+        if let Some(script) = script(c) {
+            counts[script as usize] += 1;
+            total += 1;
+        }
+    }
+    if total == 0 {
+        return LanguageGuess::UNKNOWN;
+    }
+
+    let share = |script: Script| counts[script as usize] as f64 / total as f64;
+    // Japanese mixes kana with Han characters
+    if counts[Script::Kana as usize] > 0 {
+        return LanguageGuess {
+            language: "ja",
+            confidence: share(Script::Kana) + share(Script::Han),
+        };
+    }
+
+    let (dominant, _) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(idx, count)| (**count, std::cmp::Reverse(*idx)))
+        .unwrap_or((0, &0));
+    let language = match dominant {
+        d if d == Script::Latin as usize => return detect_latin(text),
+        d if d == Script::Cyrillic as usize => {
+            // Letters used in Ukrainian but not Russian
+            if text.chars().any(|c| matches!(c, 'і' | 'ї' | 'є' | 'ґ')) {
+                "uk"
+            } else {
+                "ru"
+            }
+        }
+        d if d == Script::Greek as usize => "el",
+        d if d == Script::Arabic as usize => "ar",
+        d if d == Script::Hebrew as usize => "he",
+        d if d == Script::Devanagari as usize => "hi",
+        d if d == Script::Thai as usize => "th",
+        d if d == Script::Hangul as usize => "ko",
+        _ => "zh",
+    };
+    LanguageGuess {
+        language,
+        confidence: counts[dominant] as f64 / total as f64,
+    }
+}
+
+/// Score Latin-script text by function-word hits
+fn detect_latin(text: &str) -> LanguageGuess {
+    let mut hits = [0usize; STOPWORDS.len()];
+    for word in text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        for (idx, (_, words)) in STOPWORDS.iter().enumerate() {
+            if words.contains(&word.as_str()) {
+                hits[idx] += 1;
+            }
+        }
+    }
+
+    let total: usize = hits.iter().sum();
+    let Some((best, &count)) = hits
+        .iter()
+        .enumerate()
+        .max_by_key(|(idx, count)| (**count, std::cmp::Reverse(*idx)))
+    else {
+        return LanguageGuess::UNKNOWN;
+    };
+    if count == 0 {
+        return LanguageGuess::UNKNOWN;
+    }
+    LanguageGuess {
+        language: STOPWORDS[best].0,
+        confidence: count as f64 / total as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_latin_languages() {
+        let cases = [
+            ("Please send the invoice to my address, it is urgent", "en"),
+            (
+                "Bitte senden Sie die Rechnung an meine Adresse, es ist dringend",
+                "de",
+            ),
+            ("Merci de nous envoyer la facture pour le mois de mai", "fr"),
+            (
+                "Por favor envíe la factura a mi dirección, es urgente",
+                "es",
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_language(text).language, expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_scripts() {
+        assert_eq!(detect_language("Пожалуйста, отправьте счёт").language, "ru");
+        assert_eq!(
+            detect_language("Будь ласка, надішліть рахунок і квитанцію").language,
+            "uk"
+        );
+        assert_eq!(detect_language("請把發票寄給我").language, "zh");
+        assert_eq!(detect_language("請求書を送ってください").language, "ja");
+        assert_eq!(detect_language("청구서를 보내주세요").language, "ko");
+        assert_eq!(detect_language("12345 !!"), LanguageGuess::UNKNOWN);
+    }
+}
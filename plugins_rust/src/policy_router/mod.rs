@@ -0,0 +1,13 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Policy Router - Rust Implementation
+//
+// Detects the language of each payload and applies the PII policy (pattern
+// pack and masking settings) configured for it, in a single call, instead of
+// Python glue choosing between several detector instances.
+
+pub mod language;
+pub mod router;
+
+pub use router::PolicyRouter;
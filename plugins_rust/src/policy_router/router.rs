@@ -0,0 +1,437 @@
+// Copyright 2025
+// SPDX-License-Identifier: Apache-2.0
+//
+// Per-language selection of PII detector policies
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString, PyTuple};
+
+use super::language::{detect_language, LanguageGuess, UNDETERMINED};
+use crate::pii_filter::config::{PIIConfig, MAX_DEPTH};
+use crate::pii_filter::PIIError;
+use crate::pii_filter::{Detector, PIIDetectorRust};
+use crate::plugin::{GatewayPlugin, Hook, HookContext};
+
+/// Name of the fallback policy
+pub const DEFAULT_POLICY: &str = "default";
+
+/// Bytes of payload text sampled for language identification
+const SAMPLE_BYTES: usize = 4096;
+
+/// Primary language subtag of a locale ("de" for "de-CH")
+fn primary_language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
+}
+
+/// Lowercase `locale` with "-" separators, so "de_CH" and "de-ch" agree
+fn normalize_locale(locale: &str) -> String {
+    locale.to_lowercase().replace('_', "-")
+}
+
+/// Append string values of a nested payload, from `value` at `path` and
+/// nesting `depth` down, to `sample`, up to `SAMPLE_BYTES`
+fn sample_text(
+    value: &Bound<'_, PyAny>,
+    path: &str,
+    depth: usize,
+    sample: &mut String,
+) -> PyResult<()> {
+    if sample.len() >= SAMPLE_BYTES {
+        return Ok(());
+    }
+    // Bounds the recursion, e.g. on a list that contains itself
+    if depth > MAX_DEPTH {
+        return Err(PIIError::NestingLimit {
+            limit: "max_depth",
+            max: MAX_DEPTH,
+            path: path.to_string(),
+        }
+        .into());
+    }
+
+    if let Ok(text) = value.cast::<PyString>() {
+        let text = text.to_string_lossy();
+        let mut end = text.len().min(SAMPLE_BYTES - sample.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        sample.push_str(&text[..end]);
+        if sample.len() < SAMPLE_BYTES {
+            sample.push('\n');
+        }
+    } else if let Ok(dict) = value.cast::<PyDict>() {
+        for (key, item) in dict.iter() {
+            let key = key.str()?;
+            let key = key.to_string_lossy();
+            let item_path = if path.is_empty() {
+                key.into_owned()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            sample_text(&item, &item_path, depth + 1, sample)?;
+        }
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        for (idx, item) in value.try_iter()?.enumerate() {
+            sample_text(&item?, &format!("{}[{}]", path, idx), depth + 1, sample)?;
+        }
+    }
+    Ok(())
+}
+
+/// Chooses a PII detector per payload from its language or locale
+///
+/// Policies are keyed by locale ("de-CH"), language ("de") or "default";
+/// a payload uses the most specific policy available. Each policy is a full
+/// `PIIDetectorRust` config, so it can enable a different pattern pack
+/// (detect flags, `custom_patterns` such as a profanity word list) and
+/// different masking settings.
+///
+/// # Example (Python)
+/// ```python
+/// from plugins_rust import PolicyRouter
+///
+/// router = PolicyRouter({
+///     "policies": {
+///         "default": {"default_mask_strategy": "redact"},
+///         "de": {"custom_patterns": [...], "default_mask_strategy": "partial"},
+///     },
+/// })
+/// result = router.route({"message": "Bitte rufen Sie mich an"})
+/// result["language"], result["policy"], result["payload"]
+/// ```
+#[pyclass(name = "PolicyRouter", module = "plugins_rust", frozen)]
+pub struct PolicyRouter {
+    /// Policies in config order; always contains `DEFAULT_POLICY`
+    policies: Vec<(String, PIIDetectorRust)>,
+    /// Detected languages below this confidence are treated as undetermined
+    min_confidence: f64,
+}
+
+#[pymethods]
+impl PolicyRouter {
+    /// Create a policy router
+    ///
+    /// # Arguments
+    /// * `config` - Dict with:
+    ///   * `policies` (dict): Locale, language or "default" -> detector config
+    ///     dict. Names are matched case-insensitively, with "_" and "-" alike.
+    ///     Without a "default" entry the default detector config is used
+    ///   * `min_confidence` (float): Lowest language confidence trusted (default: 0.3)
+    #[new]
+    pub fn new(config: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut policies = Vec::new();
+        if let Some(value) = config.get_item("policies")? {
+            for (name, policy) in value.cast::<PyDict>()?.iter() {
+                let name: String = name.extract()?;
+                let detector = PIIDetectorRust::new(policy.cast::<PyDict>()?)?;
+                policies.push((normalize_locale(&name), detector));
+            }
+        }
+        if !policies.iter().any(|(name, _)| name == DEFAULT_POLICY) {
+            policies.push((
+                DEFAULT_POLICY.to_string(),
+                Detector::from_config(PIIConfig::default())?.into(),
+            ));
+        }
+        let min_confidence = match config.get_item("min_confidence")? {
+            Some(value) => value.extract()?,
+            None => 0.3,
+        };
+        Ok(Self {
+            policies,
+            min_confidence,
+        })
+    }
+
+    /// Names of the configured policies, lowercased with "-" separators
+    #[getter]
+    pub fn policies(&self) -> Vec<String> {
+        self.policies.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Identify the language of `text`
+    ///
+    /// # Returns
+    /// `(language, confidence)`; language is an ISO 639-1 code or "und"
+    pub fn detect_language(&self, py: Python, text: &str) -> (&'static str, f64) {
+        let guess = py.detach(|| detect_language(text));
+        (guess.language, guess.confidence)
+    }
+
+    /// Mask `payload` with the policy for its language
+    ///
+    /// # Arguments
+    /// * `payload` - String or nested dict/list
+    /// * `locale` - Known locale (e.g. "de-CH"), skipping language detection
+    ///
+    /// # Returns
+    /// ```python
+    /// {"language": "de", "confidence": 0.9, "policy": "de",
+    ///  "payload": {...}, "detections": {...}}
+    /// ```
+    /// Raises `BlockedContentError` when the selected policy blocks a
+    /// detected type.
+    #[pyo3(signature = (payload, locale=None))]
+    pub fn route(
+        &self,
+        py: Python,
+        payload: &Bound<'_, PyAny>,
+        locale: Option<&str>,
+    ) -> PyResult<Py<PyDict>> {
+        let (language, confidence) = self.identify(py, payload, locale)?;
+        let (policy, detector) = self.select(locale, &language);
+        let detector = detector.current();
+
+        let (_, masked, detections) = detector.process_nested(py, payload, "", None)?;
+        detector.check_blocking(&detector.py_detections_to_rust(detections.bind(py))?, None)?;
+
+        let result = PyDict::new(py);
+        result.set_item("language", language)?;
+        result.set_item("confidence", confidence)?;
+        result.set_item("policy", policy)?;
+        result.set_item("payload", masked)?;
+        result.set_item("detections", detections)?;
+        Ok(result.unbind())
+    }
+
+    /// Run the router as a gateway plugin on a shared `HookContext`
+    ///
+    /// Uses `context.metadata["locale"]` when present. The selected policy
+    /// records its detections under `findings["pii_filter"]`.
+    pub fn process(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        GatewayPlugin::process(self, py, context)
+    }
+}
+
+impl PolicyRouter {
+    pub const PLUGIN_NAME: &'static str = "policy_router";
+    pub const HOOKS: &'static [Hook] = &[Hook::OnRequest, Hook::OnResponse, Hook::OnToolResult];
+
+    /// Language of `payload`, from `locale` when given
+    fn identify(
+        &self,
+        py: Python,
+        payload: &Bound<'_, PyAny>,
+        locale: Option<&str>,
+    ) -> PyResult<(String, f64)> {
+        if let Some(locale) = locale {
+            return Ok((primary_language(locale).to_lowercase(), 1.0));
+        }
+
+        let mut sample = String::new();
+        sample_text(payload, "", 1, &mut sample)?;
+        let LanguageGuess {
+            language,
+            confidence,
+        } = py.detach(|| detect_language(&sample));
+        if confidence < self.min_confidence {
+            return Ok((UNDETERMINED.to_string(), confidence));
+        }
+        Ok((language.to_string(), confidence))
+    }
+
+    /// Most specific policy for `locale`, then `language`, then the default
+    fn select(&self, locale: Option<&str>, language: &str) -> (&str, &PIIDetectorRust) {
+        let find = |key: &str| {
+            let key = normalize_locale(key);
+            self.policies.iter().find(|(name, _)| *name == key)
+        };
+        let (name, detector) = locale
+            .and_then(&find)
+            .or_else(|| locale.and_then(|locale| find(primary_language(locale))))
+            .or_else(|| find(language))
+            .or_else(|| find(DEFAULT_POLICY))
+            .expect("default policy is always configured");
+        (name, detector)
+    }
+
+    fn route_context(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        let (payload, locale) = {
+            let context = context.borrow();
+            let locale: Option<String> = match context.metadata.bind(py).get_item("locale")? {
+                Some(locale) if !locale.is_none() => Some(locale.extract()?),
+                _ => None,
+            };
+            (context.payload.clone_ref(py), locale)
+        };
+        let (language, confidence) = self.identify(py, payload.bind(py), locale.as_deref())?;
+        let (policy, detector) = self.select(locale.as_deref(), &language);
+
+        let finding = PyDict::new(py);
+        finding.set_item("language", language)?;
+        finding.set_item("confidence", confidence)?;
+        finding.set_item("policy", policy)?;
+        context
+            .borrow()
+            .add_finding(py, Self::PLUGIN_NAME, finding.into_any().unbind())?;
+
+        GatewayPlugin::process(detector, py, context)
+    }
+}
+
+impl GatewayPlugin for PolicyRouter {
+    fn name(&self) -> &'static str {
+        Self::PLUGIN_NAME
+    }
+
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn hooks(&self) -> &'static [Hook] {
+        Self::HOOKS
+    }
+
+    fn on_request(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.route_context(py, context)
+    }
+
+    fn on_response(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.route_context(py, context)
+    }
+
+    fn on_tool_result(&self, py: Python, context: &Bound<'_, HookContext>) -> PyResult<()> {
+        self.route_context(py, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_locale() {
+        assert_eq!(normalize_locale("de_CH"), "de-ch");
+        assert_eq!(normalize_locale("DE-ch"), "de-ch");
+        assert_eq!(normalize_locale("default"), "default");
+    }
+
+    #[test]
+    fn test_primary_language() {
+        assert_eq!(primary_language("de-CH"), "de");
+        assert_eq!(primary_language("pt_BR"), "pt");
+        assert_eq!(primary_language("fr"), "fr");
+    }
+}
+
+/// These tests need libpython linked into the test binary, which the
+/// `extension-module` feature leaves out; `make test` runs them.
+#[cfg(all(test, not(feature = "extension-module")))]
+mod python_tests {
+    use super::*;
+    use crate::pii_filter::error::exceptions;
+
+    const GERMAN: &str = "Bitte rufen Sie mich morgen an, ich bin den ganzen Tag zu Hause \
+                          und warte auf die Antwort von der Bank";
+
+    fn router(py: Python, config: &std::ffi::CStr) -> PolicyRouter {
+        let config = py.eval(config, None, None).unwrap();
+        PolicyRouter::new(config.cast::<PyDict>().unwrap()).unwrap()
+    }
+
+    fn eval<'py>(py: Python<'py>, code: &std::ffi::CStr) -> Bound<'py, PyAny> {
+        py.eval(code, None, None).unwrap()
+    }
+
+    #[test]
+    fn test_select_precedence() {
+        Python::initialize();
+        Python::attach(|py| {
+            let router = router(
+                py,
+                c"{'policies': {'de_CH': {}, 'de': {}, 'fr': {}, 'default': {}}}",
+            );
+            let select = |locale, language| router.select(locale, language).0.to_string();
+            // Locale, then its primary language, then the detected language
+            assert_eq!(select(Some("de-CH"), "fr"), "de-ch");
+            assert_eq!(select(Some("de_CH"), "fr"), "de-ch");
+            assert_eq!(select(Some("DE-ch"), "fr"), "de-ch");
+            assert_eq!(select(Some("de-AT"), "fr"), "de");
+            assert_eq!(select(Some("it-IT"), "fr"), "fr");
+            assert_eq!(select(None, "fr"), "fr");
+            assert_eq!(select(None, "es"), DEFAULT_POLICY);
+            assert_eq!(select(None, UNDETERMINED), DEFAULT_POLICY);
+
+            let router = super::python_tests::router(py, c"{'policies': {'de-ch': {}}}");
+            assert_eq!(router.select(Some("de_CH"), "de").0, "de-ch");
+            assert_eq!(router.select(Some("de-AT"), "de").0, DEFAULT_POLICY);
+            assert_eq!(router.policies(), vec!["de-ch", DEFAULT_POLICY]);
+        });
+    }
+
+    #[test]
+    fn test_min_confidence_falls_back_to_default() {
+        Python::initialize();
+        Python::attach(|py| {
+            let payload = PyString::new(py, GERMAN);
+            let route = |config| {
+                let result = router(py, config).route(py, &payload, None).unwrap();
+                let result = result.bind(py);
+                let field = |name| result.get_item(name).unwrap().unwrap();
+                (
+                    field("language").extract::<String>().unwrap(),
+                    field("policy").extract::<String>().unwrap(),
+                )
+            };
+            assert_eq!(
+                route(c"{'policies': {'de': {}}, 'min_confidence': 0.0}"),
+                ("de".to_string(), "de".to_string())
+            );
+            assert_eq!(
+                route(c"{'policies': {'de': {}}, 'min_confidence': 1.1}"),
+                (UNDETERMINED.to_string(), DEFAULT_POLICY.to_string())
+            );
+        });
+    }
+
+    #[test]
+    fn test_route_masks_and_blocks() {
+        Python::initialize();
+        Python::attach(|py| {
+            let router = router(
+                py,
+                c"{'policies': {'de': {'block_on_detection': True}, 'default': {}}}",
+            );
+            let payload = eval(py, c"{'note': 'SSN 123-45-6789'}");
+
+            let result = router.route(py, &payload, Some("en-US")).unwrap();
+            let result = result.bind(py);
+            assert_eq!(
+                result
+                    .get_item("policy")
+                    .unwrap()
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                DEFAULT_POLICY
+            );
+            let masked = result.get_item("payload").unwrap().unwrap();
+            let note: String = masked.get_item("note").unwrap().extract().unwrap();
+            assert!(!note.contains("123-45-6789"));
+
+            let err = router.route(py, &payload, Some("de-DE")).unwrap_err();
+            assert!(err.is_instance_of::<exceptions::BlockedContentError>(py));
+        });
+    }
+
+    #[test]
+    fn test_sample_text_is_bounded() {
+        Python::initialize();
+        Python::attach(|py| {
+            // 'é' is two bytes, so the odd budget left after "xy\n" ends
+            // mid-character
+            let payload = eval(py, c"['xy', ['\\xe9' * 4000], 'z']");
+            let mut sample = String::new();
+            sample_text(&payload, "", 1, &mut sample).unwrap();
+            assert_eq!(sample.len(), SAMPLE_BYTES);
+            assert!(sample.starts_with("xy\n\u{e9}"));
+            assert!(sample.ends_with("\u{e9}\n"));
+
+            let looped = PyList::empty(py);
+            looped.append(&looped).unwrap();
+            let err = sample_text(&looped, "", 1, &mut String::new()).unwrap_err();
+            assert!(err.is_instance_of::<exceptions::PayloadLimitError>(py));
+            looped.call_method0("clear").unwrap();
+        });
+    }
+}